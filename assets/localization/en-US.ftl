@@ -4,6 +4,27 @@ main-menu-load-game = Load game
 main-menu-quit = Quit
 
 main-menu-load-back = Back
+main-menu-legends = Legends
+main-menu-load-error = Could not load the save:
+
+## World generation options
+worldgen-options-preset-quick = Quick
+worldgen-options-preset-standard = Standard
+worldgen-options-preset-epic = Epic
+worldgen-options-seed = Seed
+worldgen-options-size = World size
+worldgen-options-size-small = Small
+worldgen-options-size-medium = Medium
+worldgen-options-size-large = Large
+worldgen-options-history-length = History (years)
+worldgen-options-plates = Tectonic plates
+worldgen-options-seed-cities = Founding cities
+worldgen-options-storyteller = Storyteller
+worldgen-options-strength = Storyteller strength
+worldgen-options-city-count = Target cities
+worldgen-options-village-count = Target villages
+worldgen-options-back = Back
+worldgen-options-generate = Generate
 
 ## In-game menu
 ingame-menu-title-paused = Paused
@@ -26,4 +47,169 @@ body-part-name-Torso = torso
 body-part-name-LeftArm = left arm
 body-part-name-RightArm = right arm
 body-part-name-LeftLeg = left leg
-body-part-name-RightLeg = right leg
\ No newline at end of file
+body-part-name-RightLeg = right leg
+
+## Professions
+profession-None = nobody
+profession-Beast = beast
+profession-Bandit = bandit
+profession-Peasant = peasant
+profession-Farmer = farmer
+profession-Miner = miner
+profession-Woodcutter = woodcutter
+profession-Guard = guard
+profession-Blacksmith = blacksmith
+profession-Sculptor = sculptor
+profession-Priest = priest
+profession-Ruler = ruler
+
+## History
+# Each event has a long form, dated, for the codex and the chronicles, and a short form for lists and logs.
+# $gender is the gender of $name, either male or female.
+event-long = On the { $date }, { $text }.
+event-list = { $year }: { $text }
+
+event-CreatureDeath = { $cause ->
+        [OldAge] { $name } died of old age
+        [Disease] { $name } died of a disease
+        [Plague] { $name } died of the { $epidemic }
+        [KilledWithWeapon] { $name } was slain by { $killer } with { $weapon }
+       *[KilledInBattle] { $name } was slain by { $killer }
+    }
+    .short = { $name } died
+event-CreatureBirth = { $name } was born to { $father } and { $mother }
+    .short = { $name } was born
+event-CreatureMarriage = { $name } and { $other } were married
+    .short = { $name } married { $other }
+event-CreatureProfessionChange = { $profession ->
+        [None] { $name } gave up working
+        [Beast] { $name } went wild
+        [Bandit] { $name } turned to banditry
+        [Farmer] { $name } took up farming
+        [Guard] { $name } joined the guard
+        [Priest] { $name } was ordained a priest
+        [Ruler] { $name } rose to rule
+       *[other] { $name } became a { $profession_name }
+    }
+    .short = { $profession ->
+        [None] { $name } gave up working
+       *[other] { $name } became a { $profession_name }
+    }
+event-ArtifactCreated = { $creator } created { $artifact } in { $site }
+    .short = { $creator } created { $artifact }
+event-InheritedArtifact = { $name } inherited { $artifact } from { $other }
+    .short = { $name } inherited { $artifact }
+event-BurriedWithPosessions = { $name } was buried with { $gender ->
+        [female] her
+       *[male] his
+    } possessions
+    .short = { $name } was buried
+event-ArtifactComission = { $name } commissioned { $artifact } from { $other }, on turning { $age }
+    .short = { $name } commissioned { $artifact }
+event-NewLeaderElected = { $name } was elected the new leader of { $site }
+    .short = { $name } was elected in { $site }
+event-JoinBanditCamp = { $name } left { $site } and joined the bandits at { $new_site }
+    .short = { $name } joined the bandits
+event-CreateBanditCamp = { $name } left { $site } and started a bandit camp at { $new_site }
+    .short = { $name } started a bandit camp
+event-WarDeclared = { $name }, leader of { $attacker }, declared war on { $defender }, { $cause ->
+        [FoodShortage] driven by hunger
+        [Grudge] to settle a grudge with { $target }
+       *[Hostility] out of hatred for the { $culture }
+    }
+    .short = { $attacker } declared war on { $defender }
+event-BattleFought = the armies of { $attacker } and { $defender } met in battle. { $victor } was victorious. { $attacker_casualties } fell from { $attacker } and { $defender_casualties } from { $defender }
+    .short = { $victor } won a battle between { $attacker } and { $defender }
+event-SiteConquered = { $site } was conquered by { $conqueror }, and its people were taken in by the victors
+    .short = { $conqueror } conquered { $site }
+event-PeaceSigned = { $attacker } and { $defender } signed a peace
+    .short = { $attacker } made peace with { $defender }
+event-TempleBuilt = the people of { $site } raised a temple to { $deity }
+    .short = { $site } raised a temple
+event-CreatureConverted = { $name } was converted to the { $religion } by { $other }
+    .short = { $name } converted to the { $religion }
+event-ReligiousSchism = { $name } broke away from the { $religion } and founded the { $new_religion }, exalting { $deity } above all other gods
+    .short = { $name } founded the { $new_religion }
+event-HeresyDeclared = { $name } denounced { $other } as a heretic for following the { $religion }
+    .short = { $name } denounced { $other }
+event-LeaderOverthrown = { $name } overthrew { $other } and seized the rule of { $site }
+    .short = { $name } seized { $site }
+event-ArtifactStolen = { $name } stole { $artifact } from { $other }
+    .short = { $name } stole { $artifact }
+event-SettlementFounded = { $name } led a group of settlers out of { $site } and founded { $new_site }
+    .short = { $name } founded { $new_site }
+event-PlotFoiled = { $other } uncovered a plot by { $name }
+    .short = { $other } foiled { $name }
+event-NobleHouseFounded = { $name } raised the house of { $house } to nobility, bearing { $arms }
+    .short = { $name } ennobled the house of { $house }
+event-no-arms = no arms
+event-TitleInherited = { $name } inherited the rule of { $site } from { $other }
+    .short = { $name } inherited { $site }
+event-SuccessionCrisis = { $rivals ->
+        [0] with no heir to rule { $site }, { $name } claimed the title unopposed
+       *[other] with no heir to rule { $site }, { $name } prevailed over the claims of { $claimants }
+    }
+    .short = { $name } won the succession of { $site }
+event-PlagueStarted = the { $epidemic } broke out in { $site }
+    .short = The { $epidemic } broke out in { $site }
+event-PlagueSpread = travellers from { $from } brought the { $epidemic } to { $site }
+    .short = The { $epidemic } reached { $site }
+event-PlagueSubsided = { $abandoned ->
+        [0] the { $epidemic } left { $site }. { $deaths } were buried in its mass graves
+       *[other] the { $epidemic } left { $site }. { $deaths } were buried in its mass graves, and { $abandoned } homes stood empty
+    }
+    .short = The { $epidemic } left { $site }
+event-Migrated = { $company ->
+        [0] { $name } left { $site } for { $new_site }, { $reason }
+       *[other] { $name } left { $site } with { $company } of { $gender ->
+            [female] her
+           *[male] his
+        } family for { $new_site }, { $reason }
+    }
+    .short = { $name } moved to { $new_site }
+migration-cause-Famine = fleeing famine
+migration-cause-War = fleeing the war
+migration-cause-Plague = fleeing the plague
+migration-cause-Overcrowding = looking for land of their own
+event-NaturalDisaster = { $disaster ->
+        [Drought] the rains failed and a great drought withered the fields around { $site }
+       *[Flood] the rivers overflowed and flooded the fields around { $site }
+    }
+    .short = { $disaster ->
+        [Drought] A drought struck { $site }
+       *[Flood] A flood struck { $site }
+    }
+event-Famine = the granaries of { $site } ran empty and famine struck
+    .short = Famine struck { $site }
+event-RoadBuilt = the people of { $site } cleared a trail to { $to }
+    .short = A trail was cleared from { $site } to { $to }
+event-RoadPaved = the busy trail between { $site } and { $to } was paved
+    .short = The road from { $site } to { $to } was paved
+event-BecameCity = { $site } had grown into a city
+    .short = { $site } became a city
+event-DistrictBuilt = the city of { $site } built { $district ->
+        [Market] a market quarter
+        [Barracks] barracks for the city guard
+       *[other] a new district
+    }
+    .short = { $site } built a new district
+event-SiteFounded = { $site_type ->
+        [Castle] { $name } raised the castle of { $new_site } to guard the lands of { $site }
+        [Mine] { $name } led the miners of { $site } to the hills and opened the mine of { $new_site }
+        [Monastery] { $name } withdrew from { $site } with a few brethren and founded the monastery of { $new_site }
+       *[other] { $name } left { $site } and founded { $new_site }
+    }
+    .short = { $name } founded { $new_site }
+event-SiteRuined = the last of the people of { $site } were gone, and it fell to ruin
+    .short = { $site } fell to ruin
+event-RuinsResettled = { $name } led { $gender ->
+        [male] his
+       *[female] her
+    } family out of { $site } and made a home in the ruins of { $new_site }
+    .short = { $name } resettled { $new_site }
+event-RuinsLooted = the bandits of { $looters } picked through the ruins of { $site } and carried off { $artifact }
+    .short = { $artifact } was looted from { $site }
+event-BeastAppeared = { $name } appeared and made its lair at { $site }
+    .short = { $name } appeared at { $site }
+event-BeastBred = { $name } was born to { $parent } in { $site }
+    .short = { $name } was born in { $site }
@@ -5,10 +5,23 @@ use engine::astar::{AStar, MovementCost};
 use math::Vec2i;
 use noise::{NoiseFn, Perlin};
 
-use crate::{chunk_gen::jigsaw_structure_generator::JigsawPieceRequirement, commons::{id_vec::Id, rng::Rng}, engine::tilemap::Tile, game::chunk::{Chunk, ChunkLayer, Spawner}, info, resources::resources::resources, warn, world::{date::{Duration, WorldDate}, site::{Site, SiteType, Structure, StructureGeneratedData, StructureStatus, StructureType}, world::World}, Coord2, Resources};
+use crate::{chunk_gen::jigsaw_structure_generator::JigsawPieceRequirement, commons::{id_vec::Id, rng::Rng}, engine::tilemap::Tile, game::chunk::{Chunk, ChunkLayer, Spawner}, info, resources::{resources::resources, species::SpeciesId}, warn, world::{date::{Duration, WorldDate}, site::{Site, SiteType, Structure, StructureGeneratedData, StructureStatus, StructureType}, road::RoadKind, topology::Water, world::World}, Coord2, Resources};
 
 use super::{jigsaw_parser::JigsawParser, jigsaw_structure_generator::{JigsawPiece, JigsawPieceTile, JigsawSolver}, structure_filter::{AbandonedStructureFilter, NoopFilter, StructureFilter}};
 
+/// Width range of a river, in tiles from its middle to each bank
+const RIVER_WIDTH: (f32, f32) = (2., 4.);
+/// How far the sea or a lake on a neighbouring world tile reaches into the chunk
+const SHORE_DEPTH: f32 = 14.;
+/// Width of the sand between the sea and the grass
+const BEACH_WIDTH: f32 = 4.;
+/// How much the banks wander
+const BANK_WOBBLE: f32 = 3.;
+/// Structures are not built closer than this to the water
+const STRUCTURE_WATER_CLEARANCE: i32 = 4;
+/// Lair structure used when there's no beast left to tell what it looked like
+const DEFAULT_LAIR: &str = "varningr";
+
 pub(crate) struct ChunkGenerator<'a> {
     rng: Rng,
     chunk: &'a mut Chunk,
@@ -33,6 +46,10 @@ impl<'a> ChunkGenerator<'a> {
     pub(crate) fn generate(&mut self, world: &World, resources: &Resources) {
         let now = Instant::now();
         self.generate_fixed_terrain_features();
+        if self.chunk.coord.layer == ChunkLayer::Surface {
+            self.generate_water(world);
+            self.generate_roads(world);
+        }
         info!("[Chunk gen] Terrain: {:.2?}", now.elapsed());
 
         self.structure_point_cloud = self.generate_point_cloud(1000);
@@ -51,9 +68,10 @@ impl<'a> ChunkGenerator<'a> {
 
         if let Some(mut site) = found_site {
 
+            let ruined = site.site_type == SiteType::Ruins;
             for structure in site.structures.iter_mut() {
                 if structure.generated_data.is_none() {
-                    match self.generate_structure(structure, &mut solver, world.date) {
+                    match self.generate_structure(structure, ruined, &mut solver, world.date) {
                         Ok(data) => structure.generated_data = Some(data),
                         Err(err) => warn!("{err}")
                     }
@@ -61,7 +79,7 @@ impl<'a> ChunkGenerator<'a> {
             }
 
             match &site.site_type {
-                SiteType::BanditCamp | SiteType::Village => {
+                SiteType::BanditCamp | SiteType::Village | SiteType::City | SiteType::Castle | SiteType::Mine | SiteType::Monastery | SiteType::Ruins => {
 
                     if self.path_endpoints.len() > 0 {
                         let now = Instant::now();
@@ -70,11 +88,18 @@ impl<'a> ChunkGenerator<'a> {
                     }
 
                 },
-                SiteType::VarningrLair => {
+                SiteType::Lair => {
                     let now = Instant::now();
+                    // The beast shapes its lair, even after it's dead
+                    let (lair, minions) = site.creatures.iter().chain(site.cemetery.iter()).next()
+                        .and_then(|creature_id| {
+                            let species = resources.species.get(&world.creatures.get(creature_id).species);
+                            species.ecology.as_ref().map(|ecology| (ecology.lair.clone(), ecology.minions))
+                        })
+                        .unwrap_or((String::from(DEFAULT_LAIR), None));
                     match self.chunk.coord.layer {
-                        ChunkLayer::Surface => self.generate_lair_entrance(&mut solver, resources),
-                        ChunkLayer::Underground => self.generate_lair(&site, &mut solver, resources),
+                        ChunkLayer::Surface => self.generate_lair_entrance(&lair, minions, &mut solver),
+                        ChunkLayer::Underground => self.generate_lair(&site, &lair, minions, &mut solver),
                     };
                     info!("[Chunk gen] Large structs: {:.2?}", now.elapsed());
                 },
@@ -97,10 +122,11 @@ impl<'a> ChunkGenerator<'a> {
 
         if let Some(site) = world.get_site_at(&self.chunk.coord.xy.into()) {
             let mut site = world.sites.get_mut(&site);
+            let ruined = site.site_type == SiteType::Ruins;
             for structure in site.structures.iter_mut() {
                 match &structure.generated_data {
                     None => {
-                        match self.generate_structure(structure, &mut solver, world.date) {
+                        match self.generate_structure(structure, ruined, &mut solver, world.date) {
                             Ok(data) => structure.generated_data = Some(data),
                             Err(err) => warn!("{err}")
                         }
@@ -127,11 +153,20 @@ impl<'a> ChunkGenerator<'a> {
         self.chunk.last_generated = world.date;
     }
 
-    fn generate_structure(&mut self, structure: &Structure, solver: &mut JigsawSolver, now: WorldDate) -> Result<StructureGeneratedData, Error> {
+    /// Structures of ruined sites are seen for the first time as rubble, not as they were when standing
+    fn generate_structure(&mut self, structure: &Structure, ruined: bool, solver: &mut JigsawSolver, now: WorldDate) -> Result<StructureGeneratedData, Error> {
         let pool =  match structure.get_type() {
+            StructureType::House | StructureType::MineShaft if ruined => "ruins_house",
+            _ if ruined => "ruins_hall",
             StructureType::House => "village_house_start",
             StructureType::TownHall => "village_house_ruler",
             StructureType::BanditCamp => "camp_start",
+            StructureType::Temple => "village_temple",
+            StructureType::Market => "city_market",
+            StructureType::Barracks => "city_barracks",
+            StructureType::Keep => "castle_keep",
+            StructureType::MineShaft => "mine_shaft",
+            StructureType::Cloister => "monastery_cloister",
         };
 
         let mut generated_data = StructureGeneratedData::new(structure.get_status().clone());
@@ -241,6 +276,105 @@ impl<'a> ChunkGenerator<'a> {
         }
     }
 
+    /// The rivers, lakes and sea of the world map, as they look up close
+    fn generate_water(&mut self, world: &World) {
+        let resources = resources();
+        let water = resources.tiles.id_of("tile:water").as_usize();
+        let sand = resources.tiles.id_of("tile:sand").as_usize();
+        let xy = self.chunk.coord.xy;
+        let here = match world.map.water_at(xy) {
+            Some(here) => here,
+            None => return,
+        };
+        let center = self.edge_towards(&Vec2i(0, 0));
+        let edges = [Vec2i(0, -1), Vec2i(0, 1), Vec2i(-1, 0), Vec2i(1, 0)].map(|direction| (direction, self.edge_towards(&direction)));
+        let river_width = self.rng.randf_range(RIVER_WIDTH.0, RIVER_WIDTH.1);
+        let noise = Perlin::new(self.rng.derive("water").seed());
+
+        for x in 0..self.chunk.size.x() {
+            for y in 0..self.chunk.size.y() {
+                let point = [x as f32, y as f32];
+                let wobble = noise.get([x as f64 / 10., y as f64 / 10.]) as f32 * BANK_WOBBLE;
+                let mut tile = match here {
+                    Water::Ocean | Water::Lake => Some(water),
+                    _ => None,
+                };
+                for (direction, edge) in edges.iter() {
+                    let neighbour = world.map.water_at(xy + *direction);
+                    // The river runs from the middle of the chunk to wherever the water goes on
+                    if here == Water::River && neighbour.is_some() && neighbour != Some(Water::None) {
+                        if dist_to_segment(point, center, *edge) + wobble < river_width {
+                            tile = Some(water);
+                        }
+                    }
+                    // Water next door shows up by the edge
+                    let reach = match neighbour {
+                        Some(Water::Ocean) | Some(Water::Lake) => SHORE_DEPTH,
+                        Some(Water::River) if here != Water::River => river_width,
+                        _ => continue,
+                    };
+                    let dist = match direction.x() {
+                        0 => (point[1] - edge[1]).abs(),
+                        _ => (point[0] - edge[0]).abs(),
+                    } + wobble;
+                    if dist < reach {
+                        tile = Some(water);
+                    } else if dist < reach + BEACH_WIDTH && reach == SHORE_DEPTH && tile.is_none() {
+                        tile = Some(sand);
+                    }
+                }
+                if let Some(tile) = tile {
+                    self.chunk.ground_layer.set_tile(x, y, tile);
+                }
+            }
+        }
+    }
+
+    /// The roads of the world map that go through the chunk, from its middle to the edges they leave by
+    fn generate_roads(&mut self, world: &World) {
+        let cobblestone = resources().tiles.id_of("tile:cobblestone").as_usize();
+        let center = self.edge_towards(&Vec2i(0, 0));
+        let mut segments = Vec::new();
+        for road in world.roads.iter() {
+            let road = road.borrow();
+            let half_width = match road.kind {
+                RoadKind::Trail => 1.,
+                RoadKind::Paved => 2.,
+            };
+            for direction in road.directions_at(&self.chunk.coord.xy) {
+                segments.push((self.edge_towards(&direction), half_width));
+            }
+        }
+        for x in 0..self.chunk.size.x() {
+            for y in 0..self.chunk.size.y() {
+                let point = [x as f32, y as f32];
+                if segments.iter().any(|(edge, half_width)| dist_to_segment(point, center, *edge) < *half_width) {
+                    self.chunk.ground_layer.set_tile(x, y, cobblestone);
+                }
+            }
+        }
+    }
+
+    /// Middle of the chunk edge facing the neighbouring world tile in the direction. The middle of the chunk for no direction
+    fn edge_towards(&self, direction: &Vec2i) -> [f32; 2] {
+        let (half_width, half_height) = ((self.chunk.size.x() as f32 - 1.) / 2., (self.chunk.size.y() as f32 - 1.) / 2.);
+        return [half_width * (1. + direction.x() as f32), half_height * (1. + direction.y() as f32)]
+    }
+
+    fn near_water(&self, pos: &Coord2, water: usize) -> bool {
+        for x in (pos.x - STRUCTURE_WATER_CLEARANCE)..=(pos.x + STRUCTURE_WATER_CLEARANCE) {
+            for y in (pos.y - STRUCTURE_WATER_CLEARANCE)..=(pos.y + STRUCTURE_WATER_CLEARANCE) {
+                if x < 0 || y < 0 || x >= self.chunk.size.x() as i32 || y >= self.chunk.size.y() as i32 {
+                    continue;
+                }
+                if self.chunk.ground_layer.tile(x as usize, y as usize) == Some(water) {
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
     fn generate_point_cloud(&mut self, number_of_points: usize) -> Vec<Coord2> {
         let mut point_cloud = BTreeSet::new();
         for _ in 0..number_of_points {
@@ -250,7 +384,8 @@ impl<'a> ChunkGenerator<'a> {
             ));
         }
         let center = Coord2::xy(self.chunk.size.x() as i32 / 2, self.chunk.size.y() as i32 / 2);
-        let mut building_seed_cloud: Vec<Coord2> = point_cloud.into_iter().collect();
+        let water = resources().tiles.id_of("tile:water").as_usize();
+        let mut building_seed_cloud: Vec<Coord2> = point_cloud.into_iter().filter(|point| !self.near_water(point, water)).collect();
         building_seed_cloud.sort_by(|a, b| {
             let a = a.dist_squared(&center);
             let b = b.dist_squared(&center);
@@ -292,16 +427,17 @@ impl<'a> ChunkGenerator<'a> {
         }
     }
 
-    fn generate_lair_entrance(&mut self, solver: &mut JigsawSolver, resources: &Resources) {
-        let structure = solver.solve_structure("varningr_surface", Coord2::xy(self.chunk.size.0 as i32 / 2, self.chunk.size.1 as i32 / 2), &mut self.rng, Vec::new());
+    fn generate_lair_entrance(&mut self, lair: &str, minions: Option<SpeciesId>, solver: &mut JigsawSolver) {
+        let structure = solver.solve_structure(&format!("{lair}_surface"), Coord2::xy(self.chunk.size.0 as i32 / 2, self.chunk.size.1 as i32 / 2), &mut self.rng, Vec::new());
         if let Ok(structure) = structure {
             for (pos, piece) in structure.vec.iter() {
                 let mut spawns = Vec::new();
                 self.place_template(*pos, &piece, &mut spawns);
-                let wolf_id = resources.species.id_of("species:wolf");
-                for spawn in spawns {
-                    if self.rng.rand_chance(0.3) {
-                        self.spawn(Spawner::Species(wolf_id), spawn);
+                if let Some(minion_id) = minions {
+                    for spawn in spawns {
+                        if self.rng.rand_chance(0.3) {
+                            self.spawn(Spawner::Species(minion_id), spawn);
+                        }
                     }
                 }
             }
@@ -315,14 +451,13 @@ impl<'a> ChunkGenerator<'a> {
         }
     }
 
-    fn generate_lair(&mut self, site: &Site, solver: &mut JigsawSolver, resources: &Resources) {
+    fn generate_lair(&mut self, site: &Site, lair: &str, minions: Option<SpeciesId>, solver: &mut JigsawSolver) {
         let requirements = vec!(
-            JigsawPieceRequirement::Exactly("varningr_lair".to_string(), 1),
-            JigsawPieceRequirement::Exactly("varningr_entrance".to_string(), 1)
+            JigsawPieceRequirement::Exactly(format!("{lair}_lair"), 1),
+            JigsawPieceRequirement::Exactly(format!("{lair}_entrance"), 1)
         );
-        let structure = solver.solve_structure("varningr_lair", Coord2::xy(self.chunk.size.0 as i32 / 2, self.chunk.size.1 as i32 / 2), &mut self.rng, requirements);
+        let structure = solver.solve_structure(&format!("{lair}_lair"), Coord2::xy(self.chunk.size.0 as i32 / 2, self.chunk.size.1 as i32 / 2), &mut self.rng, requirements);
         if let Ok(structure) = structure {
-            let wolf_id = resources.species.id_of("species:wolf");
             let mut iter = structure.vec.iter();
 
             // First piece
@@ -330,24 +465,29 @@ impl<'a> ChunkGenerator<'a> {
                 let mut spawns = Vec::new();
                 self.place_template(*pos, &piece, &mut spawns);
                 let mut spawns = spawns.iter();
-                // Spawn varningr(s)
+                // Spawn the beast(s)
                 for creature_id in site.creatures.iter() {
-                    let spawn = spawns.next().unwrap();
-                    self.spawn(Spawner::CreatureId(*creature_id), *spawn);
+                    if let Some(spawn) = spawns.next() {
+                        self.spawn(Spawner::CreatureId(*creature_id), *spawn);
+                    }
                 }
-                // Minion wolves
-                for spawn in spawns {
-                    if self.rng.rand_chance(0.3) {
-                        self.spawn(Spawner::Species(wolf_id), *spawn);
+                // Minions
+                if let Some(minion_id) = minions {
+                    for spawn in spawns {
+                        if self.rng.rand_chance(0.3) {
+                            self.spawn(Spawner::Species(minion_id), *spawn);
+                        }
                     }
                 }
             }
             for (pos, piece) in iter {
                 let mut spawns = Vec::new();
                 self.place_template(*pos, &piece, &mut spawns);
-                for spawn in spawns {
-                    if self.rng.rand_chance(0.3) {
-                        self.spawn(Spawner::Species(wolf_id), spawn);
+                if let Some(minion_id) = minions {
+                    for spawn in spawns {
+                        if self.rng.rand_chance(0.3) {
+                            self.spawn(Spawner::Species(minion_id), spawn);
+                        }
                     }
                 }
             }
@@ -393,6 +533,54 @@ impl<'a> ChunkGenerator<'a> {
             }
         }
 
+        if let Ok(pools) = parser.parse_file("assets/structures/dragon_lair.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/spider_lair.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/troll_lair.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/city.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/castle.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/mine.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/monastery.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
+        if let Ok(pools) = parser.parse_file("assets/structures/ruins.toml") {
+            for (name, pool) in pools {
+                solver.add_pool(&name, pool);
+            }
+        }
+
         return solver;
     }
 
@@ -491,4 +679,16 @@ impl<'a> ChunkGenerator<'a> {
         }
     }
 
-}
\ No newline at end of file
+}
+
+/// Distance from the point to the closest point of the segment between a and b
+fn dist_to_segment(point: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let segment = [b[0] - a[0], b[1] - a[1]];
+    let length_squared = segment[0] * segment[0] + segment[1] * segment[1];
+    let mut t = 0.;
+    if length_squared > 0. {
+        t = (((point[0] - a[0]) * segment[0] + (point[1] - a[1]) * segment[1]) / length_squared).clamp(0., 1.);
+    }
+    let closest = [a[0] + t * segment[0], a[1] + t * segment[1]];
+    return ((point[0] - closest[0]).powi(2) + (point[1] - closest[1]).powi(2)).sqrt()
+}
@@ -61,6 +61,12 @@ impl<V> IdVec<V> {
         return (0..self.len()).map(|idx| K::new(idx))
     }
 
+    /// Every value, borrowed without the RefCells so they can be read from other threads.
+    /// Nothing can change them while the references are alive.
+    pub(crate) fn values(&mut self) -> Vec<&V> {
+        return self.vector.iter_mut().map(|value| &*value.get_mut()).collect()
+    }
+
 }
 
 impl<V> Default for IdVec<V> {
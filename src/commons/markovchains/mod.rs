@@ -25,6 +25,13 @@ pub(crate) struct MarkovChainSingleWordModel {
     model_o3: Option<Vec<f32>>
 }
 
+impl std::fmt::Debug for MarkovChainSingleWordModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The models are too big to print
+        f.debug_struct("MarkovChainSingleWordModel").finish_non_exhaustive()
+    }
+}
+
 impl MarkovChainSingleWordModel {
     pub(crate) fn train(words: Vec<&str>, order: u8) -> MarkovChainSingleWordModel {
         assert!(order >= 1 && order <= 3, "Order must be between 1 and 3");
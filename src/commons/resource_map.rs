@@ -32,6 +32,10 @@ impl<I, V> ResourceMap<I, V> where I: Id {
         return IdentifiedResource::new(id.clone(), value);
     }
 
+    pub(crate) fn get_mut(&mut self, id: &I) -> &mut V {
+        return self.vector.get_mut(id.as_usize()).expect("Using ResourceMap should be safe to unwrap");
+    }
+
     pub(crate) fn try_get(&self, id: usize) -> Option<&V> {
         return self.vector.get(id)
     }
@@ -37,6 +37,11 @@ impl Rng {
         return self.seed;
     }
 
+    /// Continues from a state taken with `seed`, eg. after a save
+    pub(crate) fn restore(seed: u32) -> Rng {
+        return Rng { seed };
+    }
+
     pub(crate) fn derive(&self, deriver: impl Hash) -> Rng {
         let mut hasher = DefaultHasher::new();
         deriver.hash(&mut hasher);
@@ -0,0 +1,104 @@
+use crate::{engine::{assets::assets, gui::{layout_component::LayoutComponent, UIEvent, UINode}, Color, COLOR_BACKDROP, COLOR_WHITE}, GameContext, RenderContext};
+
+/// Space left around the plot for the axis labels
+const MARGIN: f64 = 12.;
+
+/// Plots a single series of (x, y) points as a line
+pub(crate) struct LineChart {
+    layout: LayoutComponent,
+    title: String,
+    points: Vec<(f64, f64)>,
+    color: Color,
+}
+
+impl LineChart {
+
+    pub(crate) fn new(title: &str, points: Vec<(f64, f64)>) -> Self {
+        let mut layout = LayoutComponent::new();
+        layout.size([256., 96.]);
+
+        Self {
+            layout,
+            title: String::from(title),
+            points,
+            color: Color::from_hex("e8c170"),
+        }
+    }
+
+    fn bounds(&self) -> [f64; 4] {
+        let mut bounds = [f64::MAX, f64::MAX, f64::MIN, f64::MIN];
+        for (x, y) in self.points.iter() {
+            bounds[0] = bounds[0].min(*x);
+            bounds[1] = bounds[1].min(*y);
+            bounds[2] = bounds[2].max(*x);
+            bounds[3] = bounds[3].max(*y);
+        }
+        // Charts start at zero, and a flat series still needs some height
+        bounds[1] = bounds[1].min(0.);
+        if bounds[2] <= bounds[0] {
+            bounds[2] = bounds[0] + 1.;
+        }
+        if bounds[3] <= bounds[1] {
+            bounds[3] = bounds[1] + 1.;
+        }
+        return bounds;
+    }
+
+}
+
+impl UINode for LineChart {
+    type State = ();
+    type Input = UIEvent;
+
+    fn layout_component(&mut self) -> &mut LayoutComponent {
+        return &mut self.layout
+    }
+
+    fn render(&mut self, _state: &Self::State, ctx: &mut RenderContext, _game_ctx: &mut GameContext) {
+        let layout = self.layout.compute_inner_layout_rect(ctx.layout_rect);
+        ctx.rectangle_fill(layout, &COLOR_BACKDROP);
+
+        let mut assets = assets();
+        let font = assets.font_standard();
+        let line_height = font.line_height();
+        ctx.text_shadow(&self.title, font, [layout[0] as i32 + 4, (layout[1] + line_height) as i32], &COLOR_WHITE);
+
+        let plot = [
+            layout[0] + MARGIN * 2.,
+            layout[1] + line_height + 4.,
+            layout[2] - MARGIN * 3.,
+            layout[3] - line_height - 4. - MARGIN,
+        ];
+        let axis = COLOR_WHITE.alpha(0.5);
+        ctx.line([plot[0], plot[1]], [plot[0], plot[1] + plot[3]], 1., &axis);
+        ctx.line([plot[0], plot[1] + plot[3]], [plot[0] + plot[2], plot[1] + plot[3]], 1., &axis);
+
+        if self.points.is_empty() {
+            return;
+        }
+
+        let bounds = self.bounds();
+        let to_screen = |x: f64, y: f64| {
+            return [
+                plot[0] + (x - bounds[0]) / (bounds[2] - bounds[0]) * plot[2],
+                plot[1] + plot[3] - (y - bounds[1]) / (bounds[3] - bounds[1]) * plot[3],
+            ];
+        };
+        for pair in self.points.windows(2) {
+            let from = to_screen(pair[0].0, pair[0].1);
+            let to = to_screen(pair[1].0, pair[1].1);
+            ctx.line(from, to, 1., &self.color);
+        }
+
+        let max_y = format!("{}", bounds[3].round());
+        ctx.text_shadow(&max_y, font, [layout[0] as i32 + 2, (plot[1] + line_height / 2.) as i32], &COLOR_WHITE);
+        let min_y = format!("{}", bounds[1].round());
+        ctx.text_shadow(&min_y, font, [layout[0] as i32 + 2, (plot[1] + plot[3]) as i32], &COLOR_WHITE);
+        let min_x = format!("{}", bounds[0].round());
+        ctx.text_shadow(&min_x, font, [plot[0] as i32, (plot[1] + plot[3] + line_height) as i32], &COLOR_WHITE);
+        let max_x = format!("{}", bounds[2].round());
+        let width = font.width(&max_x);
+        ctx.text_shadow(&max_x, font, [(plot[0] + plot[2] - width) as i32, (plot[1] + plot[3] + line_height) as i32], &COLOR_WHITE);
+    }
+
+}
@@ -3,11 +3,13 @@ use std::ops::ControlFlow;
 use crate::{engine::{gui::layout_component::LayoutComponent}, GameContext, InputEvent, RenderContext, Update};
 
 pub(crate) mod button;
+pub(crate) mod chart;
 pub(crate) mod containers;
 pub(crate) mod context_menu;
 pub(crate) mod dialog;
 pub(crate) mod layout_component;
 pub(crate) mod label;
+pub(crate) mod text_input;
 pub(crate) mod tooltip;
 
 
@@ -43,5 +45,7 @@ pub(crate) enum UIEvent {
     None,
     DialogClosed,
     ButtonClicked(String),
+    /// The value of a text input changed, by its key
+    InputChanged(String),
     ShowContextMenu([f64; 2]),
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::ops::ControlFlow;
+
+use piston::{Key, MouseButton};
+
+use crate::{engine::{assets::assets, gui::{layout_component::LayoutComponent, UIEvent, UINode}, Color, COLOR_BACKDROP, COLOR_WHITE}, GameContext, InputEvent, RenderContext};
+
+/// A single line of text the player can type into, after clicking it
+pub(crate) struct TextInput {
+    layout: LayoutComponent,
+    value: String,
+    key: Option<String>,
+    /// Only accepts digits, '.' and '-'
+    numeric: bool,
+    max_length: usize,
+    focused: bool,
+    invalid: bool,
+}
+
+impl TextInput {
+
+    pub(crate) fn text(value: &str) -> Self {
+        let mut layout = LayoutComponent::new();
+        layout.size([64., 16.]);
+
+        Self {
+            layout,
+            value: String::from(value),
+            key: None,
+            numeric: false,
+            max_length: 24,
+            focused: false,
+            invalid: false,
+        }
+    }
+
+    pub(crate) fn numeric(value: &str) -> Self {
+        let mut input = Self::text(value);
+        input.numeric = true;
+        input.max_length = 10;
+        return input;
+    }
+
+    pub(crate) fn key(mut self, key: &str) -> Self {
+        self.key = Some(String::from(key));
+        return self;
+    }
+
+    pub(crate) fn value(&self) -> &str {
+        return &self.value;
+    }
+
+    pub(crate) fn set_value(&mut self, value: &str) {
+        self.value = String::from(value);
+    }
+
+    /// Whether the player is typing in this input
+    pub(crate) fn focused(&self) -> bool {
+        return self.focused;
+    }
+
+    /// Draws the input in red, eg. when the value is out of range
+    pub(crate) fn set_invalid(&mut self, invalid: bool) {
+        self.invalid = invalid;
+    }
+
+    /// Applies a key to the value. Returns whether the value changed
+    fn type_key(&mut self, key: Key) -> bool {
+        if key == Key::Backspace {
+            return self.value.pop().is_some();
+        }
+        let typed = match key_char(key) {
+            Some(typed) => typed,
+            None => return false,
+        };
+        if self.numeric && !(typed.is_ascii_digit() || typed == '.' || typed == '-') {
+            return false;
+        }
+        if self.value.chars().count() >= self.max_length {
+            return false;
+        }
+        self.value.push(typed);
+        return true;
+    }
+
+}
+
+impl UINode for TextInput {
+    type State = ();
+    type Input = UIEvent;
+
+    fn layout_component(&mut self) -> &mut LayoutComponent {
+        return &mut self.layout
+    }
+
+    fn render(&mut self, _state: &Self::State, ctx: &mut RenderContext, _game_ctx: &mut GameContext) {
+        let layout = self.layout.compute_inner_layout_rect(ctx.layout_rect);
+        ctx.rectangle_fill(layout, &COLOR_BACKDROP);
+
+        let border = match (self.invalid, self.focused) {
+            (true, _) => Color::from_hex("d95763"),
+            (false, true) => Color::from_hex("e8c170"),
+            (false, false) => COLOR_WHITE.alpha(0.5),
+        };
+        let [x, y, w, h] = layout;
+        ctx.line([x, y], [x + w, y], 1., &border);
+        ctx.line([x, y + h], [x + w, y + h], 1., &border);
+        ctx.line([x, y], [x, y + h], 1., &border);
+        ctx.line([x + w, y], [x + w, y + h], 1., &border);
+
+        let mut assets = assets();
+        let font = assets.font_standard();
+        let text = match self.focused {
+            true => format!("{}_", self.value),
+            false => self.value.clone(),
+        };
+        let text_height = font.line_height();
+        let pos = [x + 4., y + (h / 2.) - (text_height / 2.) + text_height - 1.];
+        ctx.text(&text, font, [pos[0] as i32, pos[1] as i32], &COLOR_WHITE);
+    }
+
+    fn input(&mut self, _state: &mut Self::State, evt: &InputEvent, _ctx: &mut GameContext) -> ControlFlow<UIEvent> {
+        match evt {
+            InputEvent::Click { button: MouseButton::Left, pos } => {
+                self.focused = self.layout.hitbox(pos);
+            },
+            InputEvent::Key { key: Key::Return | Key::Tab | Key::Escape } => {
+                self.focused = false;
+            },
+            InputEvent::Key { key } => {
+                if self.focused {
+                    if self.type_key(*key) {
+                        return ControlFlow::Break(UIEvent::InputChanged(self.key.clone().unwrap_or_default()));
+                    }
+                    // Typing doesn't reach anything else
+                    return ControlFlow::Break(UIEvent::None);
+                }
+            },
+            _ => ()
+        }
+        return ControlFlow::Continue(());
+    }
+
+}
+
+fn key_char(key: Key) -> Option<char> {
+    let typed = match key {
+        Key::A => 'a', Key::B => 'b', Key::C => 'c', Key::D => 'd', Key::E => 'e', Key::F => 'f', Key::G => 'g',
+        Key::H => 'h', Key::I => 'i', Key::J => 'j', Key::K => 'k', Key::L => 'l', Key::M => 'm', Key::N => 'n',
+        Key::O => 'o', Key::P => 'p', Key::Q => 'q', Key::R => 'r', Key::S => 's', Key::T => 't', Key::U => 'u',
+        Key::V => 'v', Key::W => 'w', Key::X => 'x', Key::Y => 'y', Key::Z => 'z',
+        Key::D0 | Key::NumPad0 => '0', Key::D1 | Key::NumPad1 => '1', Key::D2 | Key::NumPad2 => '2',
+        Key::D3 | Key::NumPad3 => '3', Key::D4 | Key::NumPad4 => '4', Key::D5 | Key::NumPad5 => '5',
+        Key::D6 | Key::NumPad6 => '6', Key::D7 | Key::NumPad7 => '7', Key::D8 | Key::NumPad8 => '8',
+        Key::D9 | Key::NumPad9 => '9',
+        Key::Period | Key::NumPadPeriod => '.',
+        Key::Minus | Key::NumPadMinus => '-',
+        Key::Space => ' ',
+        _ => return None,
+    };
+    return Some(typed);
+}
+
+#[cfg(test)]
+mod tests_text_input {
+    use super::*;
+
+    #[test]
+    fn test_type_keys() {
+        let mut input = TextInput::numeric("1");
+        assert!(input.type_key(Key::D2));
+        assert!(!input.type_key(Key::A));
+        assert!(input.type_key(Key::Period));
+        assert_eq!(input.value(), "12.");
+        assert!(input.type_key(Key::Backspace));
+        assert_eq!(input.value(), "12");
+
+        let mut input = TextInput::text("");
+        for key in [Key::K, Key::A, Key::T, Key::H, Key::A, Key::Y] {
+            input.type_key(key);
+        }
+        assert_eq!(input.value(), "kathay");
+    }
+
+}
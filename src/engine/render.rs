@@ -1,4 +1,4 @@
-use graphics::{image, rectangle, Context, Text, Transformed, Image as GlImage};
+use graphics::{image, line, rectangle, Context, Text, Transformed, Image as GlImage};
 use opengl_graphics::{GlGraphics, Texture};
 
 use crate::engine::assets::{assets, Font};
@@ -56,6 +56,10 @@ impl<'a> RenderContext<'a> {
         rectangle(color.f32_arr(), rect, self.context.transform, self.gl);
     }
 
+    pub(crate) fn line(&mut self, from: [f64; 2], to: [f64; 2], width: f64, color: &Color) {
+        line(color.f32_arr(), width / 2., [from[0], from[1], to[0], to[1]], self.context.transform, self.gl);
+    }
+
     pub(crate) fn set_clip_rect(&mut self, rect: Option<[u32; 4]>) -> Option<[u32; 4]> {
         let copy = self.context.draw_state.scissor.clone();
         self.context.draw_state.scissor = rect;
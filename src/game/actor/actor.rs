@@ -36,7 +36,7 @@ pub(crate) struct Actor {
 impl Actor {
 
     pub(crate) fn from_species(xy: Coord2, species_id: &SpeciesId, species: &Species, ai_group: u8) -> Actor {
-        let gender = CreatureGender::random();
+        let gender = CreatureGender::random(&mut Rng::rand());
         Actor {
             xy: xy.to_vec2i(),
             animation: AnimationTransform::new(),
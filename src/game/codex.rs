@@ -2,13 +2,17 @@ use std::{collections::HashMap, slice::Iter};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::bitmask::{bitmask_get, bitmask_set}, world::{creature::CreatureId, item::ItemId, site::SiteId}};
+use crate::{commons::bitmask::{bitmask_get, bitmask_set}, world::{creature::CreatureId, item::ItemId, lineage::LineageId, religion::ReligionId, site::SiteId}};
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct Codex {
     creatures: HashMap<CreatureId, CreatureCodex>,
     artifacts: HashMap<ItemId, ArtifactCodex>,
     sites: HashMap<SiteId, SiteCodex>,
+    #[serde(default)]
+    religions: HashMap<ReligionId, ReligionCodex>,
+    #[serde(default)]
+    houses: HashMap<LineageId, HouseCodex>,
     quests: Vec<Quest>,
 }
 
@@ -19,6 +23,8 @@ impl Codex {
             creatures: HashMap::new(),
             artifacts: HashMap::new(),
             sites: HashMap::new(),
+            religions: HashMap::new(),
+            houses: HashMap::new(),
             quests: Vec::new()
         }
     }
@@ -68,10 +74,40 @@ impl Codex {
         return self.sites.get_mut(site_id).expect("Just inserted");
     }
 
+    pub(crate) fn religions(&self) -> std::collections::hash_map::Keys<'_, ReligionId, ReligionCodex> {
+        return self.religions.keys();
+    }
+
+    pub(crate) fn religion(&self, religion_id: &ReligionId) -> Option<&ReligionCodex> {
+        return self.religions.get(religion_id);
+    }
+
+    pub(crate) fn religion_mut(&mut self, religion_id: &ReligionId) -> &mut ReligionCodex {
+        if !self.religions.contains_key(religion_id) {
+            self.religions.insert(*religion_id, ReligionCodex { events: Vec::new() });
+        }
+        return self.religions.get_mut(religion_id).expect("Just inserted");
+    }
+
+    pub(crate) fn houses(&self) -> std::collections::hash_map::Keys<'_, LineageId, HouseCodex> {
+        return self.houses.keys();
+    }
+
+    pub(crate) fn house(&self, lineage_id: &LineageId) -> Option<&HouseCodex> {
+        return self.houses.get(lineage_id);
+    }
+
+    pub(crate) fn house_mut(&mut self, lineage_id: &LineageId) -> &mut HouseCodex {
+        if !self.houses.contains_key(lineage_id) {
+            self.houses.insert(*lineage_id, HouseCodex { events: Vec::new() });
+        }
+        return self.houses.get_mut(lineage_id).expect("Just inserted");
+    }
+
     pub(crate) fn add_quest(&mut self, quest: Quest) {
         // Makes sure the basic info about the quest is known
         match &quest.objective {
-            QuestObjective::KillVarningr(creature_id) => {
+            QuestObjective::KillBeast(creature_id) => {
                 let creature = self.creature_mut(creature_id);
                 creature.add_name();
             },
@@ -177,6 +213,40 @@ impl ArtifactCodex {
 
 }
 
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ReligionCodex {
+    events: Vec<usize>,
+}
+
+impl ReligionCodex {
+
+    pub(crate) fn add_event(&mut self, event: usize) {
+        self.events.push(event)
+    }
+
+    pub(crate) fn events(&'_ self) -> Iter<'_, usize> {
+        return self.events.iter()
+    }
+
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct HouseCodex {
+    events: Vec<usize>,
+}
+
+impl HouseCodex {
+
+    pub(crate) fn add_event(&mut self, event: usize) {
+        self.events.push(event)
+    }
+
+    pub(crate) fn events(&'_ self) -> Iter<'_, usize> {
+        return self.events.iter()
+    }
+
+}
+
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct Quest {
@@ -199,8 +269,8 @@ impl Quest {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum QuestObjective {
-    /// Kill a varningr
-    KillVarningr(CreatureId),
+    /// Kill a great beast
+    KillBeast(CreatureId),
     /// Kill wolves
     KillWolves(SiteId),
     /// Kill bandits
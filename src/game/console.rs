@@ -1,9 +1,9 @@
-use std::ops::ControlFlow;
+use std::{ops::ControlFlow, path::Path};
 
 use math::Vec2i;
 use piston::Key;
 
-use crate::{chunk_gen::chunk_generator::ChunkGenerator, commons::rng::Rng, engine::{assets::assets, geometry::Coord2, input::InputEvent, render::RenderContext, COLOR_BLACK, COLOR_WHITE}, game::{actor::actor::Actor, codex::QuestStatus, GameSceneState}, resources::{item_blueprint::{ItemBlueprintId, ItemBlueprints, ItemMaker}, species::{SpeciesId, SpeciesMap}}, world::{date::Duration, item::{ItemMakeArguments, ItemQuality}}, GameContext};
+use crate::{chunk_gen::chunk_generator::ChunkGenerator, commons::rng::Rng, engine::{assets::assets, geometry::Coord2, input::InputEvent, render::RenderContext, COLOR_BLACK, COLOR_WHITE}, game::{actor::actor::Actor, codex::QuestStatus, GameSceneState}, resources::{item_blueprint::{ItemBlueprintId, ItemBlueprints, ItemMaker}, species::{SpeciesId, SpeciesMap}}, world::{date::Duration, export::{export_world, ExportFormat}, genealogy::{export_genealogy, GenealogyFormat, GenealogyRoot}, item::{ItemMakeArguments, ItemQuality}}, GameContext};
 
 pub(crate) struct Console {
     visible: bool,
@@ -207,6 +207,29 @@ impl Console {
 
                 return Result::Ok(format!("Days simulated"));
             },
+            Some("/export") => {
+                let directory = parts.next().unwrap_or("export");
+                let formats = match parts.next() {
+                    Some(format) => vec!(ExportFormat::parse(format)?),
+                    None => ExportFormat::ALL.to_vec(),
+                };
+                for format in formats {
+                    export_world(&scene.world, &ctx.resources, Path::new(directory), format)?;
+                }
+                return Result::Ok(format!("History exported to {directory}"));
+            },
+            Some("/genealogy") => {
+                let root = parts.next().ok_or("Param 1 should be lineage:<id> or creature:<id>")?;
+                let root = GenealogyRoot::parse(root, &scene.world)?;
+                let formats = match parts.next() {
+                    Some(format) => vec!(GenealogyFormat::parse(format)?),
+                    None => GenealogyFormat::ALL.to_vec(),
+                };
+                for format in formats {
+                    export_genealogy(&scene.world, &ctx.resources, root, format, Path::new("export"))?;
+                }
+                return Result::Ok(format!("Family tree exported to export"));
+            },
             
             Some(cmd) => return Result::Err(format!("Command {} not found", cmd))
         }
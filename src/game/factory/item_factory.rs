@@ -1,6 +1,6 @@
 use text::capitalize;
 
-use crate::{commons::{bitmask::bitmask_get, rng::Rng}, resources::{item_blueprint::{ItemMaker, NameBlueprintComponent}, material::MaterialId, resources::resources}, world::item::{ArtworkScene, ItemMakeArguments, ItemQuality}, Item, Resources};
+use crate::{commons::{bitmask::bitmask_get, rng::Rng}, resources::{culture::CultureId, item_blueprint::{ItemMaker, NameBlueprintComponent}, material::{MaterialId, MAT_TAG_METAL}, resources::resources}, world::item::{ArtworkScene, ItemMakeArguments, ItemQuality}, Item, Resources};
 
 pub(crate) struct ItemFactory {}
 
@@ -16,6 +16,7 @@ impl ItemFactory {
             blueprint_id: ItemBlueprintId::mock(0),
             action_provider: None,
             artwork_scene: None,
+            dedicated_to: None,
             material: None,
             mellee_damage: None,
             armor: None,
@@ -45,11 +46,11 @@ impl ItemFactory {
     }
 
     pub(crate) fn weapon<'a>(rng: &'a mut Rng, resources: &'a Resources) -> WeaponFactory<'a> {
-        return WeaponFactory { rng: rng, resources: resources, quality: None, primary_material: None, material_pool: None, named: false }
+        return WeaponFactory { rng: rng, resources: resources, quality: None, primary_material: None, material_pool: None, culture: None, named: false }
     }
 
     pub(crate) fn starter_weapon<'a>(rng: &'a mut Rng, resources: &'a Resources) -> WeaponFactory<'a> {
-        return WeaponFactory { rng: rng, resources: resources, quality: Some(ItemQuality::Normal), primary_material: Some(resources.materials.id_of("mat:copper")), material_pool: None, named: false }
+        return WeaponFactory { rng: rng, resources: resources, quality: Some(ItemQuality::Normal), primary_material: Some(resources.materials.id_of("mat:copper")), material_pool: None, culture: None, named: false }
     }
 
     pub(crate) fn spell_tome<'a>(_rng: &'a mut Rng, resources: &'a Resources) -> Item {
@@ -171,8 +172,12 @@ impl ItemFactory {
         return item;
     }
 
-    pub(crate) fn statue(rng: &mut Rng, resources: &Resources, scene: ArtworkScene) -> Item {
-        let material_id = random_metal(rng);
+    /// Makes a statue. If a material pool is given, the metal comes from it, and there's no statue if it has none
+    pub(crate) fn statue(rng: &mut Rng, resources: &Resources, scene: ArtworkScene, material_pool: Option<&mut MaterialPool>) -> Option<Item> {
+        let material_id = match material_pool {
+            Some(pool) => take_from_pool(rng, resources, pool, MAT_TAG_METAL)?,
+            None => random_metal(rng),
+        };
 
         let blueprint = resources.item_blueprints.find("itb:statue");
 
@@ -180,12 +185,12 @@ impl ItemFactory {
             ItemMakeArguments::PrimaryMaterial(material_id),
             ItemMakeArguments::Scene(scene),
         ), &resources);
-        return item;
+        return Some(item);
     }
 
 }
 
-type MaterialPool = Vec<(MaterialId, usize)>;
+pub(crate) type MaterialPool = Vec<(MaterialId, usize)>;
 
 pub(crate) struct WeaponFactory<'a> {
     rng: &'a mut Rng,
@@ -193,6 +198,7 @@ pub(crate) struct WeaponFactory<'a> {
     quality: Option<ItemQuality>,
     primary_material: Option<MaterialId>,
     material_pool: Option<&'a mut MaterialPool>,
+    culture: Option<CultureId>,
     named: bool,
 }
 
@@ -209,12 +215,24 @@ impl<'a> WeaponFactory<'a> {
         return self
     }
 
+    /// The culture of the maker, that decides how the item is named
+    pub(crate) fn culture(mut self, culture: Option<CultureId>) -> Self {
+        self.culture = culture;
+        return self
+    }
+
     pub(crate) fn named(mut self) -> Self {
         self.named = true;
         return self
     }
 
     pub(crate) fn make(&mut self) -> Item {
+        return self.try_make().expect("No materials available to create item")
+    }
+
+    /// Makes the weapon, unless the material pool lacks the materials for it.
+    /// With a material pool, only the materials in it are used.
+    pub(crate) fn try_make(&mut self) -> Option<Item> {
         let quality = match self.quality {
             Some(quality) => quality,
             None => {
@@ -241,30 +259,40 @@ impl<'a> WeaponFactory<'a> {
         let mut arguments = vec!(ItemMakeArguments::Quality(quality));
 
         if let Some(material_blueprint) = &blueprint.material {
-            let always_available = vec!(
-                self.resources.materials.id_of("mat:oak"),
-                self.resources.materials.id_of("mat:birch"),
-                self.resources.materials.id_of("mat:copper"),
-                self.resources.materials.id_of("mat:bronze"),
-                self.resources.materials.id_of("mat:iron"),
-                self.resources.materials.id_of("mat:steel"),
-            );
+            let always_available = match &self.material_pool {
+                Some(_) => Vec::new(),
+                None => vec!(
+                    self.resources.materials.id_of("mat:oak"),
+                    self.resources.materials.id_of("mat:birch"),
+                    self.resources.materials.id_of("mat:copper"),
+                    self.resources.materials.id_of("mat:bronze"),
+                    self.resources.materials.id_of("mat:iron"),
+                    self.resources.materials.id_of("mat:steel"),
+                ),
+            };
+
+            // Picks from a copy of the pool, so nothing is taken unless every material is available
+            let mut pool = self.material_pool.as_deref().cloned();
 
             let primary = match &self.primary_material {
                 Some(id) => *id,
-                None => self.pick_material(material_blueprint.primary_tag_bitmask, &always_available)
+                None => self.pick_material(&mut pool, material_blueprint.primary_tag_bitmask, &always_available)?
             };
             arguments.push(ItemMakeArguments::PrimaryMaterial(primary));
 
             if let Some(secondary_bitmask) = material_blueprint.secondary_tag_bitmask {
-                let secondary = self.pick_material(secondary_bitmask, &always_available);
+                let secondary = self.pick_material(&mut pool, secondary_bitmask, &always_available)?;
                 arguments.push(ItemMakeArguments::SecondaryMaterial(secondary));
             }
 
             if let Some(details_bitmask) = material_blueprint.details_tag_bitmask {
-                let details = self.pick_material(details_bitmask, &always_available);
+                let details = self.pick_material(&mut pool, details_bitmask, &always_available)?;
                 arguments.push(ItemMakeArguments::DetailsMaterial(details));
             }
+
+            if let (Some(material_pool), Some(pool)) = (&mut self.material_pool, pool) {
+                **material_pool = pool;
+            }
         }
 
         item = blueprint.make(arguments, &self.resources);
@@ -275,10 +303,10 @@ impl<'a> WeaponFactory<'a> {
             }
         }
 
-        return item;
+        return Some(item);
     }
 
-    fn pick_material(&mut self, mat_tag_bitmask: u8, always_available_materials: &Vec<MaterialId>) -> MaterialId {
+    fn pick_material(&mut self, pool: &mut Option<MaterialPool>, mat_tag_bitmask: u8, always_available_materials: &Vec<MaterialId>) -> Option<MaterialId> {
 
         enum MaterialSource {
             Pool(MaterialId),
@@ -286,7 +314,7 @@ impl<'a> WeaponFactory<'a> {
         }
         let mut candidates = Vec::new();
 
-        if let Some(material_pool) = &self.material_pool {
+        if let Some(material_pool) = pool.as_ref() {
             for (material_id, _count) in material_pool.iter() {
                 let material = self.resources.materials.get(material_id);
                 if bitmask_get(material.tags_bitmask, mat_tag_bitmask) {
@@ -305,30 +333,18 @@ impl<'a> WeaponFactory<'a> {
         let selected_material = self.rng.item(&candidates);
         match selected_material {
             Some(MaterialSource::Pool(id)) => {
-                Self::consume_material(self.material_pool.as_mut().expect("Checked above"), id).expect("I don't see how this would happen");
-                *id
+                consume_material(pool.as_mut().expect("Checked above"), id).expect("I don't see how this would happen");
+                Some(*id)
             },
-            Some(MaterialSource::AlwaysAvailable(id)) => *id,
-            _ => panic!("No materials available to create item"),
+            Some(MaterialSource::AlwaysAvailable(id)) => Some(*id),
+            None => None,
         }
     }
 
-    fn consume_material(pool: &mut MaterialPool, id: &MaterialId) -> Result<(), ()> {
-        let position = pool.iter().position(|(l_id, _count)| l_id == id);
-        if let Some(position) = position {
-            pool[position].1 -= 1;
-            if pool[position].1 <= 0 {
-                pool.remove(position);
-            }
-            return Ok(())
-        }
-        return Err(())
-    }
-
     fn make_item_name(&mut self, blueprint: &NameBlueprintComponent) -> String {
-        let preffixes = [
-            "whisper", "storm", "fire", "moon", "sun", "ice", "raven", "thunder", "flame", "frost", "ember"
-        ];
+        let culture_id = self.culture.unwrap_or(self.resources.cultures.id_of("culture:default"));
+        let culture = self.resources.cultures.get(&culture_id);
+        let preffixes = &culture.artifact_name_prefixes;
         let prefix = preffixes[self.rng.randu_range(0, preffixes.len())];
         let suffix = self.rng.item(&blueprint.suffixes).expect("Namable items should have suffixes");
         return capitalize(format!("{prefix}{suffix}").as_str());
@@ -336,6 +352,29 @@ impl<'a> WeaponFactory<'a> {
 
 }
 
+fn consume_material(pool: &mut MaterialPool, id: &MaterialId) -> Result<(), ()> {
+    let position = pool.iter().position(|(l_id, _count)| l_id == id);
+    if let Some(position) = position {
+        pool[position].1 -= 1;
+        if pool[position].1 <= 0 {
+            pool.remove(position);
+        }
+        return Ok(())
+    }
+    return Err(())
+}
+
+/// Picks a random material with the tags from the pool, and consumes it
+fn take_from_pool(rng: &mut Rng, resources: &Resources, pool: &mut MaterialPool, mat_tag_bitmask: u8) -> Option<MaterialId> {
+    let candidates: Vec<MaterialId> = pool.iter()
+        .filter(|(material_id, _count)| bitmask_get(resources.materials.get(material_id).tags_bitmask, mat_tag_bitmask))
+        .map(|(material_id, _count)| *material_id)
+        .collect();
+    let material_id = *rng.item(&candidates)?;
+    consume_material(pool, &material_id).ok()?;
+    return Some(material_id);
+}
+
 fn random_quality(rng: &mut Rng) -> ItemQuality {
     let f_quality = rng.randf();
     if f_quality < 0.5 {
@@ -380,4 +419,23 @@ fn random_metal(rng: &mut Rng) -> MaterialId {
         2 => resources().materials.id_of("mat:copper"),
         _ => resources().materials.id_of("mat:bronze")
     };
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests_item_factory {
+    use super::*;
+
+    #[test]
+    fn test_failed_weapon_keeps_materials() {
+        let mut resources = Resources::new();
+        resources.load();
+        let mut rng = Rng::seeded("weapon");
+        // Enough for the blade and the handle, but not the details
+        let mut pool = vec!((resources.materials.id_of("mat:iron"), 1), (resources.materials.id_of("mat:oak"), 1));
+
+        let item = ItemFactory::weapon(&mut rng, &resources).material_pool(Some(&mut pool)).try_make();
+
+        assert!(item.is_none());
+        assert_eq!(pool, vec!((resources.materials.id_of("mat:iron"), 1), (resources.materials.id_of("mat:oak"), 1)));
+    }
+
+}
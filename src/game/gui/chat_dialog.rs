@@ -107,7 +107,7 @@ impl ChatDialog {
             }
 
             let quest_objective = match site.site_type {
-                SiteType::VarningrLair => Some(QuestObjective::KillVarningr(site.creatures.first().unwrap().clone())),
+                SiteType::Lair => Some(QuestObjective::KillBeast(site.creatures.first().unwrap().clone())),
                 SiteType::BanditCamp => Some(QuestObjective::KillBandits(sites_id)),
                 SiteType::WolfPack => Some(QuestObjective::KillWolves(sites_id)),
                 _ => None,
@@ -116,7 +116,7 @@ impl ChatDialog {
             if let Some(quest_objective) = quest_objective {
                 let mult = match quest_objective {
                     QuestObjective::KillWolves(_) => 3.,
-                    QuestObjective::KillVarningr(_) => 1.5,
+                    QuestObjective::KillBeast(_) => 1.5,
                     QuestObjective::KillBandits(_) => 1.,
                 };
                 let score = (MAX_DST - dst) * mult;
@@ -143,6 +143,21 @@ impl ChatDialog {
             return;
         }
 
+        let war = world.get_site_at(&self.data.world_coord).and_then(|site_id| {
+            world.site_ongoing_war(&site_id).map(|war_id| (site_id, war_id))
+        });
+        if let Some((site_id, war_id)) = war {
+            let war = world.wars.get(&war_id);
+            writer.chat_talk_about_war(&war, &site_id, &self.data.actor);
+
+            let text = &writer.take_text();
+            for line in text.split("\n") {
+                let line = Label::text(&line);
+                self.chat_container.add(line);
+            }
+            return;
+        }
+
         writer.quote_actor("These are times of peace, and I have nothing to ask of you. Perphaps ask in other towns?", &self.data.actor);
             
         let text = &writer.take_text();
@@ -2,7 +2,10 @@ use std::ops::ControlFlow;
 
 use graphics::Transformed;
 
-use crate::{engine::{assets::{assets, Assets}, gui::{button::Button, containers::SimpleContainer, label::Label, layout_component::LayoutComponent, UIEvent, UINode}}, game::codex::{Quest, QuestObjective, QuestStatus}, globals::perf::perf, world::{creature::CreatureId, item::ItemId, site::{SiteId, SiteType}, world::World, writer::Writer}, GameContext, RenderContext};
+use crate::{engine::{assets::{assets, Assets}, gui::{button::Button, containers::SimpleContainer, label::Label, layout_component::LayoutComponent, UIEvent, UINode}}, game::codex::{Quest, QuestObjective, QuestStatus}, globals::perf::perf, world::{creature::CreatureId, item::ItemId, lineage::LineageId, religion::ReligionId, site::SiteId, title::TitleId, world::World, writer::Writer}, GameContext, RenderContext};
+
+/// How many generations of a house are shown below its founder
+const FAMILY_TREE_DEPTH: usize = 3;
 
 pub(crate) struct CodexDialog {
     layout: LayoutComponent,
@@ -10,6 +13,8 @@ pub(crate) struct CodexDialog {
     sites_button: Button,
     artifacts_button: Button,
     quests_button: Button,
+    religions_button: Button,
+    houses_button: Button,
     buttons: Vec<(Selection, Button)>,
     selected: Selection,
     info_container: SimpleContainer
@@ -34,6 +39,12 @@ impl CodexDialog {
         let mut quests_button = Button::text("Quests");
         quests_button.layout_component().anchor_top_left(58., 18.).size([56., 16.]);
 
+        let mut religions_button = Button::text("Religions");
+        religions_button.layout_component().anchor_top_left(0., 36.).size([56., 16.]);
+
+        let mut houses_button = Button::text("Houses");
+        houses_button.layout_component().anchor_top_left(58., 36.).size([56., 16.]);
+
         let mut info_container = SimpleContainer::new();
         info_container.layout_component().anchor_top_left(124., 0.).size([256., 316.]);
 
@@ -43,6 +54,8 @@ impl CodexDialog {
             artifacts_button,
             sites_button,
             quests_button,
+            religions_button,
+            houses_button,
             buttons: Vec::new(),
             selected: Selection::None,
             info_container
@@ -50,7 +63,7 @@ impl CodexDialog {
     }
 
     fn build_creatures(&mut self, state: &World, game_ctx: &mut GameContext) {
-        let mut y = 40.;
+        let mut y = 58.;
         self.buttons.clear();
         for id in state.codex.creatures() {
             let mut button = Button::text(&&self.creature_name(id, state, &game_ctx));
@@ -61,7 +74,7 @@ impl CodexDialog {
     }
 
     fn build_artifacts(&mut self, state: &World, game_ctx: &mut GameContext) {
-        let mut y = 40.;
+        let mut y = 58.;
         self.buttons.clear();
         for id in state.codex.artifacts() {
             let artifact = state.artifacts.get(id);
@@ -73,7 +86,7 @@ impl CodexDialog {
     }
 
     fn build_sites(&mut self, state: &World, _game_ctx: &mut GameContext) {
-        let mut y = 40.;
+        let mut y = 58.;
         self.buttons.clear();
         for id in state.codex.sites() {
             let site = state.sites.get(id);
@@ -85,7 +98,7 @@ impl CodexDialog {
     }
 
     fn build_quests(&mut self, state: &World, _game_ctx: &mut GameContext) {
-        let mut y = 40.;
+        let mut y = 58.;
         self.buttons.clear();
         for quest in state.codex.quests() {
             let mut name = quest_name(quest);
@@ -100,6 +113,30 @@ impl CodexDialog {
         }
     }
 
+    fn build_religions(&mut self, state: &World, _game_ctx: &mut GameContext) {
+        let mut y = 58.;
+        self.buttons.clear();
+        for id in state.codex.religions() {
+            let religion = state.religions.get(id);
+            let mut button = Button::text(&religion.name);
+            button.layout_component().anchor_top_left(0., y).size([114., 16.]);
+            self.buttons.push((Selection::Religion(*id), button));
+            y += 16.;
+        }
+    }
+
+    fn build_houses(&mut self, state: &World, _game_ctx: &mut GameContext) {
+        let mut y = 58.;
+        self.buttons.clear();
+        for id in state.codex.houses() {
+            let lineage = state.lineages.get(id);
+            let mut button = Button::text(&format!("House {}", lineage.name));
+            button.layout_component().anchor_top_left(0., y).size([114., 16.]);
+            self.buttons.push((Selection::House(*id), button));
+            y += 16.;
+        }
+    }
+
     fn update_info(&mut self, world: &World, ctx: &mut GameContext) {
         self.info_container.clear();
         if let Selection::Creature(creature_id) = &self.selected {
@@ -154,7 +191,7 @@ impl CodexDialog {
             for event_i in codex.events() {
                 let event = world.events.get(*event_i).expect("Should not return invalid");
 
-                let event = Label::text(&event.event_text(&ctx.resources, &world));
+                let event = Label::text(&event.list_text(&ctx.resources, &world));
                 self.info_container.add(event);
 
             }
@@ -175,7 +212,7 @@ impl CodexDialog {
             for event_i in codex.events() {
                 let event = world.events.get(*event_i).expect("Should not return invalid");
 
-                let event = Label::text(&event.event_text(&ctx.resources, &world));
+                let event = Label::text(&event.list_text(&ctx.resources, &world));
                 self.info_container.add(event);
 
             }
@@ -210,20 +247,116 @@ impl CodexDialog {
             let name = Label::text(&site.name()).font(Assets::font_heading_asset());
             self.info_container.add(name);
 
-            let text = match &site.site_type {
-                SiteType::Village => String::from("A village."),
-                SiteType::BanditCamp => String::from("A camp of bandits."),
-                SiteType::VarningrLair => String::from("A lair of a Varningr."),
-                SiteType::WolfPack => String::from("A den of wolves."),
-            };
-
-            let description = Label::text(&text);
+            let description = Label::text(site.site_type.description());
             self.info_container.add(description);
         }
 
+        if let Selection::Religion(religion_id) = &self.selected {
+            let codex = world.codex.religion(religion_id).expect("Shouldn't have shown the button");
+            let religion = world.religions.get(religion_id);
+
+            let name = Label::text(&religion.name).font(Assets::font_heading_asset());
+            self.info_container.add(name);
+
+            let patron = world.deities.get(&religion.patron);
+            let patron = Label::text(&format!("Worships {} above all others.", patron.description()));
+            self.info_container.add(patron);
+
+            if let Some(parent_id) = &religion.parent {
+                let parent = world.religions.get(parent_id);
+                let parent = Label::text(&format!("Split from the {}.", parent.name));
+                self.info_container.add(parent);
+            }
+
+            let pantheon = Label::text(&"Pantheon").font(Assets::font_heading_asset());
+            self.info_container.add(pantheon);
+
+            for deity_id in religion.pantheon.iter() {
+                let deity = world.deities.get(deity_id);
+                let deity = Label::text(&deity.description());
+                self.info_container.add(deity);
+            }
+
+            if codex.events().len() > 0 {
+                let event = Label::text(&"Events").font(Assets::font_heading_asset());
+                self.info_container.add(event);
+            }
+
+            for event_i in codex.events() {
+                let event = world.events.get(*event_i).expect("Should not return invalid");
+
+                let event = Label::text(&event.list_text(&ctx.resources, &world));
+                self.info_container.add(event);
+            }
+        }
+
+        if let Selection::House(lineage_id) = &self.selected {
+            let codex = world.codex.house(lineage_id).expect("Shouldn't have shown the button");
+            let lineage = world.lineages.get(lineage_id);
+
+            let name = Label::text(&format!("House {}", lineage.name)).font(Assets::font_heading_asset());
+            self.info_container.add(name);
+
+            if let Some(heraldry) = &lineage.heraldry {
+                let arms = Label::text(&format!("Bears {}.", heraldry.description()));
+                self.info_container.add(arms);
+            }
+            if let Some(head) = &lineage.head {
+                let head = Label::text(&format!("Head: {}", world.creature_desc(head, &ctx.resources)));
+                self.info_container.add(head);
+            }
+
+            for (_, title) in world.titles.iter_id_val::<TitleId>() {
+                let title = title.borrow();
+                for (date, holder) in title.holders.iter() {
+                    if world.creatures.get(holder).lineage != Some(*lineage_id) {
+                        continue;
+                    }
+                    let held = Label::text(&format!("{} held the title of {} since {}", world.creature_desc(holder, &ctx.resources), title.name, world.date_desc(date)));
+                    self.info_container.add(held);
+                }
+            }
+
+            if let Some(founder) = &lineage.founder {
+                let tree = Label::text(&"Family tree").font(Assets::font_heading_asset());
+                self.info_container.add(tree);
+                self.add_family_tree(*founder, *lineage_id, 0, world, ctx);
+            }
+
+            if codex.events().len() > 0 {
+                let event = Label::text(&"Events").font(Assets::font_heading_asset());
+                self.info_container.add(event);
+            }
+
+            for event_i in codex.events() {
+                let event = world.events.get(*event_i).expect("Should not return invalid");
+
+                let event = Label::text(&event.list_text(&ctx.resources, &world));
+                self.info_container.add(event);
+            }
+        }
 
     }
 
+    /// The creature and their descendants that carry the name of the house, a few generations deep
+    fn add_family_tree(&mut self, creature_id: CreatureId, lineage_id: LineageId, depth: usize, world: &World, ctx: &GameContext) {
+        let creature = world.creatures.get(&creature_id);
+        let dead = match creature.death {
+            Some(_) => " +",
+            None => "",
+        };
+        let line = Label::text(&format!("{}{}{}", "  ".repeat(depth), world.creature_desc(&creature_id, &ctx.resources), dead));
+        self.info_container.add(line);
+        if depth >= FAMILY_TREE_DEPTH {
+            return;
+        }
+        for child_id in creature.offspring.iter() {
+            if world.creatures.get(child_id).lineage == Some(lineage_id) {
+                self.add_family_tree(*child_id, lineage_id, depth + 1, world, ctx);
+            }
+        }
+    }
+
     fn creature_name(&self, creature_id: &CreatureId, world: &World, ctx: &GameContext) -> String {
         let codex = world.codex.creature(creature_id).expect("Shouldn't have shown the button");
         let creature = world.creatures.get(creature_id);
@@ -262,6 +395,8 @@ impl UINode for CodexDialog {
         self.sites_button.render(&(), ctx, game_ctx);
         self.artifacts_button.render(&(), ctx, game_ctx);
         self.quests_button.render(&(), ctx, game_ctx);
+        self.religions_button.render(&(), ctx, game_ctx);
+        self.houses_button.render(&(), ctx, game_ctx);
 
         for (_id, button) in self.buttons.iter_mut() {
             button.render(&(), ctx, game_ctx);
@@ -284,6 +419,8 @@ impl UINode for CodexDialog {
             self.creatures_button.set_selected(true);
             self.sites_button.set_selected(false);
             self.quests_button.set_selected(false);
+            self.religions_button.set_selected(false);
+            self.houses_button.set_selected(false);
             return ControlFlow::Break(UIEvent::None)
         }
         if self.artifacts_button.input(&mut (), evt, ctx).is_break() {
@@ -292,6 +429,8 @@ impl UINode for CodexDialog {
             self.artifacts_button.set_selected(true);
             self.sites_button.set_selected(false);
             self.quests_button.set_selected(false);
+            self.religions_button.set_selected(false);
+            self.houses_button.set_selected(false);
             return ControlFlow::Break(UIEvent::None)
 
         }
@@ -301,6 +440,8 @@ impl UINode for CodexDialog {
             self.artifacts_button.set_selected(false);
             self.sites_button.set_selected(true);
             self.quests_button.set_selected(false);
+            self.religions_button.set_selected(false);
+            self.houses_button.set_selected(false);
             return ControlFlow::Break(UIEvent::None)
 
         }
@@ -310,6 +451,30 @@ impl UINode for CodexDialog {
             self.artifacts_button.set_selected(false);
             self.sites_button.set_selected(false);
             self.quests_button.set_selected(true);
+            self.religions_button.set_selected(false);
+            self.houses_button.set_selected(false);
+            return ControlFlow::Break(UIEvent::None)
+
+        }
+        if self.religions_button.input(&mut (), evt, ctx).is_break() {
+            self.build_religions(state, ctx);
+            self.creatures_button.set_selected(false);
+            self.artifacts_button.set_selected(false);
+            self.sites_button.set_selected(false);
+            self.quests_button.set_selected(false);
+            self.religions_button.set_selected(true);
+            self.houses_button.set_selected(false);
+            return ControlFlow::Break(UIEvent::None)
+
+        }
+        if self.houses_button.input(&mut (), evt, ctx).is_break() {
+            self.build_houses(state, ctx);
+            self.creatures_button.set_selected(false);
+            self.artifacts_button.set_selected(false);
+            self.sites_button.set_selected(false);
+            self.quests_button.set_selected(false);
+            self.religions_button.set_selected(false);
+            self.houses_button.set_selected(true);
             return ControlFlow::Break(UIEvent::None)
 
         }
@@ -333,13 +498,15 @@ enum Selection {
     Creature(CreatureId),
     Artifact(ItemId),
     Site(SiteId),
-    Quest(Quest)
+    Quest(Quest),
+    Religion(ReligionId),
+    House(LineageId)
 }
 
 fn quest_name(quest: &Quest) -> String {
     return match quest.objective {
         QuestObjective::KillBandits(_) => String::from("Kill bandits"),
         QuestObjective::KillWolves(_) => String::from("Kill wolves"),
-        QuestObjective::KillVarningr(_) => String::from("Kill monster"),
+        QuestObjective::KillBeast(_) => String::from("Kill monster"),
     };   
 }
\ No newline at end of file
@@ -81,18 +81,14 @@ impl UINode for InspectDialog {
 
         match &self.inspected.tile_metadata {
             Some(TileMetadata::BurialPlace(creature_id)) => {
-                let events = world.events.iter().enumerate().filter(|(_, evt)| {
-                    match evt {
-                        Event::CreatureDeath { date: _, creature_id: _, cause_of_death: _ } | Event::BurriedWithPosessions { date: _, creature_id: _, items_ids: _ } => (),
-                        _ => return false,
-                    };
-                    return evt.relates_to_creature(creature_id)
-                });
-
-                for (i, event) in events.clone() {
+                let events: Vec<(usize, &Event)> = world.events.by_creature(creature_id).filter(|(_, evt)| {
+                    return matches!(evt, Event::CreatureDeath { date: _, creature_id: _, cause_of_death: _ } | Event::BurriedWithPosessions { date: _, creature_id: _, items_ids: _ })
+                }).collect();
+
+                for (i, event) in events.iter() {
                     for artifact in event.related_artifacts() {
                         let codex = world.codex.artifact_mut(&artifact);
-                        codex.add_event(i);
+                        codex.add_event(*i);
                     }
                 }
 
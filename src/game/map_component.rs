@@ -1,8 +1,14 @@
-use crate::{engine::{assets::{assets, ImageSheetAsset}, geometry::{Coord2, Size2D}, gui::{button::Button, layout_component::LayoutComponent, UIEvent, UINode}, layered_dualgrid_tilemap::{LayeredDualgridTilemap, LayeredDualgridTileset}, render::RenderContext, tilemap::{Tile16Subset, TileMap, TileSet, TileSingle}, COLOR_WHITE}, world::{topology::WorldTopology, site::{Site, SiteId, SiteType}, world::World}, GameContext};
+use crate::{engine::{assets::{assets, ImageSheetAsset}, geometry::{Coord2, Size2D}, gui::{button::Button, layout_component::LayoutComponent, UIEvent, UINode}, layered_dualgrid_tilemap::{LayeredDualgridTilemap, LayeredDualgridTileset}, render::RenderContext, tilemap::{Tile16Subset, TileMap, TileSet, TileSingle}, COLOR_WHITE}, world::{topology::{Coastline, Water, WorldTopology}, site::{Site, SiteId, SiteType}, world::World}, GameContext};
+
+/// Dualgrid tile for the sea and lakes
+const TILE_WATER: usize = 2;
+/// Dualgrid tile for beaches
+const TILE_COAST: usize = 3;
 
 pub(crate) struct MapComponent {
     layout: LayoutComponent,
     tilemap: LayeredDualgridTilemap,
+    rivers: TileMap,
     objects: TileMap,
     pub(crate) names: Vec<(Coord2, String, bool)>,
 }
@@ -15,6 +21,14 @@ impl MapComponent {
         dual_tileset.add(4, image);
         let image = ImageSheetAsset::new("map_tiles/forest.png", Size2D(16, 16));
         dual_tileset.add(5, image);
+        let image = ImageSheetAsset::new("map_tiles/ocean.png", Size2D(16, 16));
+        dual_tileset.add(2, image);
+        let image = ImageSheetAsset::new("map_tiles/coast.png", Size2D(16, 16));
+        dual_tileset.add(3, image);
+
+        let mut river_tileset = TileSet::new();
+        let image = ImageSheetAsset::new("map_tiles/river.png", Size2D(16, 16));
+        river_tileset.add(crate::engine::tilemap::Tile::T16Subset(Tile16Subset::new(image)));
 
         let mut tileset = TileSet::new();
         let image = String::from("map_tiles/settlement.png");
@@ -29,6 +43,14 @@ impl MapComponent {
         tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
         let image = String::from("map_tiles/settlement_small.png");
         tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
+        let image = String::from("map_tiles/castle.png");
+        tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
+        let image = String::from("map_tiles/mine.png");
+        tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
+        let image = String::from("map_tiles/monastery.png");
+        tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
+        let image = String::from("map_tiles/city.png");
+        tileset.add(crate::engine::tilemap::Tile::SingleTile(TileSingle::new(image)));
 
         let mut close_button = Button::text("Close");
         close_button.layout_component().anchor_top_right(0., 0.);
@@ -36,16 +58,26 @@ impl MapComponent {
         MapComponent {
             layout: LayoutComponent::new(),
             tilemap: LayeredDualgridTilemap::new(dual_tileset, 256, 256, 16, 16),
+            rivers: TileMap::new(river_tileset, 256, 256, 16, 16),
             objects: TileMap::new(tileset, 256, 256, 16, 16),
             names: Vec::new(),
         }
     }
 
     pub(crate) fn set_topology(&mut self, map: &WorldTopology) {
+        self.rivers.reset();
         for x in 0..map.size.x() {
             for y in 0..map.size.y() {
                 let tile = map.tile(x, y);
-                self.tilemap.set_tile(x, y, tile.region_id as usize);
+                let dualgrid_tile = match (tile.water, tile.coastline) {
+                    (Water::Ocean, _) | (Water::Lake, _) => TILE_WATER,
+                    (_, Coastline::Beach) => TILE_COAST,
+                    _ => tile.region_id as usize,
+                };
+                self.tilemap.set_tile(x, y, dualgrid_tile);
+                if tile.water == Water::River {
+                    self.rivers.set_tile(x, y, 1);
+                }
             }
         }
     }
@@ -53,6 +85,16 @@ impl MapComponent {
     pub(crate) fn update_visible_sites<F>(&mut self, world: &World, predicate: F) where F: Fn(&SiteId, &Site) -> bool {
         self.names.clear();
         self.objects.reset();
+        // Roads are only shown if both of their ends are
+        for road in world.roads.iter() {
+            let road = road.borrow();
+            if !predicate(&road.site_a, &world.sites.get(&road.site_a)) || !predicate(&road.site_b, &world.sites.get(&road.site_b)) {
+                continue;
+            }
+            for xy in road.path.iter() {
+                self.objects.set_tile(xy.x() as usize, xy.y() as usize, 2);
+            }
+        }
         for site_id in world.sites.iter_ids::<SiteId>() {
             let site = world.sites.get(&site_id);
             if !predicate(&site_id, &site) {
@@ -73,7 +115,18 @@ impl MapComponent {
                         4
                     }
                 },
-                SiteType::VarningrLair | SiteType::BanditCamp | SiteType::WolfPack => {
+                SiteType::City => {
+                    major_name = true;
+                    10
+                },
+                SiteType::Castle => {
+                    major_name = true;
+                    7
+                },
+                SiteType::Mine => 8,
+                SiteType::Monastery => 9,
+                SiteType::Ruins => 4,
+                SiteType::Lair | SiteType::BanditCamp | SiteType::WolfPack => {
                     if site.creatures.len() > 0 {
                         3
                     } else {
@@ -98,6 +151,7 @@ impl UINode for MapComponent {
 
     fn render(&mut self, _state: &Self::State, ctx: &mut RenderContext, game_ctx: &mut GameContext) {
         self.tilemap.render(ctx);
+        self.rivers.render(ctx, game_ctx, |_, _, _, _| {});
         self.objects.render(ctx, game_ctx, |_, _, _, _| {});
 
         let mut assets = assets();
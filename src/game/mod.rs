@@ -807,7 +807,7 @@ impl Scene for GameSceneState {
                         continue;
                     }
                     let completed = match &quest.objective {
-                        QuestObjective::KillVarningr(kill_id) => kill_id == creature_id,
+                        QuestObjective::KillBeast(kill_id) => kill_id == creature_id,
                         QuestObjective::KillBandits(site_id) | QuestObjective::KillWolves(site_id) => {
                             let site = self.world.sites.get(site_id);
                             site.creatures.len() == 0
@@ -227,10 +227,10 @@ impl GameState {
         if let Some(site) = site {
             let site = world.sites.get(&site);
             match site.site_type {
-                SiteType::BanditCamp | SiteType::VarningrLair | SiteType::WolfPack => {
+                SiteType::BanditCamp | SiteType::Lair | SiteType::WolfPack => {
                     self.ai_groups.make_hostile(AiGroups::player(), ai_group);
                 },
-                SiteType::Village => {
+                SiteType::Village | SiteType::City | SiteType::Castle | SiteType::Mine | SiteType::Monastery | SiteType::Ruins => {
                     let player_id = world.get_played_creature().unwrap();
                     let any_hostille = site.creatures.iter()
                         .any(|id| {
@@ -0,0 +1,265 @@
+use std::{fs, path::Path, thread, time::Instant};
+
+use crate::{commons::rng::Rng, engine::geometry::Size2D, loadsave::SaveFile, resources::resources::{resources_mut, Resources}, world::{date::Duration, export::{export_world, ExportFormat}, genealogy::{export_genealogy, GenealogyFormat, GenealogyRoot}, history_generator::{GenerationProgress, GenerationState, WorldGenerationParameters, WorldHistoryGenerator}, history_sim::storyteller::StorytellerProfile, world::World}};
+
+const USAGE: &str = "Usage: worldgen [options]
+
+Generates a world without opening the game window, and writes it as a save file.
+
+Options:
+  --config <file>                 TOML file with the generation parameters
+  --seed <n>                      World seed (random if omitted)
+  --world-size <w>x<h>            Size of the world, in chunks
+  --plates <n>                    Number of tectonic plates
+  --history-length <years>        Number of years to simulate
+  --seed-cities <n>               Number of cities at the start of history
+  --seed-cities-population <n>    Starting population of the seed cities
+  --storyteller <profile>         Storyteller profile from assets/storytellers: balanced, peaceful,
+                                  grim or chaotic. Sets the storyteller targets, the --st-* options
+                                  override them
+  --st-strength <0..1>            Storyteller strength
+  --st-city-count <n>             Storyteller target number of cities
+  --st-city-population <n>        Storyteller target city population
+  --st-village-count <n>          Storyteller target number of villages
+  --st-village-population <n>     Storyteller target village population
+  --save <name>                   Name of the save file (a new one if omitted)
+  --stop-at <year>                Stops the history at the year, so it can be resumed later
+  --resume <name>                 Continues the history of a save, instead of generating a new world.
+                                  Saves back to it unless --save is given
+  --dump-events <file>            Also writes the history as text
+  --export <dir>                  Also exports events, creatures, sites, lineages, artifacts
+                                  and statistics
+  --export-format <jsonl|csv>     Format of the export (both if omitted)
+  --genealogy <lineage:N|creature:N>
+                                  Also exports a family tree, to the export directory
+  --genealogy-format <dot|gedcom> Format of the family tree (both if omitted)
+  --threads <n>                   Threads used to simulate the sites (all cores if omitted)
+  --sequential                    Simulates the sites one after the other, each seeing what the
+                                  previous did, as before they were simulated in parallel. The
+                                  history isn't the same as without it
+  --benchmark                     Times the history with --sequential, on one thread and on
+                                  --threads, checks the last two are the same, and saves nothing
+  --help                          Shows this message";
+
+struct HeadlessOptions {
+    parameters: WorldGenerationParameters,
+    save_file: Option<String>,
+    stop_at: Option<i32>,
+    resume: Option<String>,
+    dump_events: Option<String>,
+    export: Option<String>,
+    export_formats: Vec<ExportFormat>,
+    genealogy: Option<String>,
+    genealogy_formats: Vec<GenealogyFormat>,
+    threads: usize,
+    sequential: bool,
+    benchmark: bool,
+}
+
+impl HeadlessOptions {
+
+    fn parse(args: Vec<String>) -> Result<Option<Self>, String> {
+        let mut parameters = WorldGenerationParameters {
+            seed: Rng::rand().rand_u32() as u64,
+            ..Default::default()
+        };
+
+        // The config file is the base, flags override it regardless of their order
+        if let Some(i) = args.iter().position(|arg| arg == "--config") {
+            let path = args.get(i + 1).ok_or("--config expects a file")?;
+            let config = fs::read_to_string(path).map_err(|err| format!("Can't read {path}: {err}"))?;
+            parameters = toml::from_str(&config).map_err(|err| format!("Invalid config {path}: {err}"))?;
+        }
+        if let Some(i) = args.iter().position(|arg| arg == "--storyteller") {
+            let name = args.get(i + 1).ok_or("--storyteller expects a profile")?;
+            let profile = StorytellerProfile::load(name)?;
+            profile.apply_targets(&mut parameters);
+            parameters.storyteller = name.clone();
+        }
+
+        let mut options = Self {
+            parameters,
+            save_file: None,
+            stop_at: None,
+            resume: None,
+            dump_events: None,
+            export: None,
+            export_formats: ExportFormat::ALL.to_vec(),
+            genealogy: None,
+            genealogy_formats: GenealogyFormat::ALL.to_vec(),
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            sequential: false,
+            benchmark: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--help" {
+                return Ok(None);
+            }
+            if arg == "--benchmark" {
+                options.benchmark = true;
+                continue;
+            }
+            if arg == "--sequential" {
+                options.sequential = true;
+                continue;
+            }
+            let value = args.next().ok_or(format!("{arg} expects a value"))?;
+            let params = &mut options.parameters;
+            match arg.as_str() {
+                "--config" | "--storyteller" => (),
+                "--seed" => params.seed = parse_number(&arg, &value)?,
+                "--world-size" => params.world_size = parse_size(&value)?,
+                "--plates" => params.num_plate_tectonics = parse_number(&arg, &value)?,
+                "--history-length" => params.history_length = parse_number(&arg, &value)?,
+                "--seed-cities" => params.number_of_seed_cities = parse_number(&arg, &value)?,
+                "--seed-cities-population" => params.seed_cities_population = parse_number(&arg, &value)?,
+                "--st-strength" => params.st_strength = parse_number(&arg, &value)?,
+                "--st-city-count" => params.st_city_count = parse_number(&arg, &value)?,
+                "--st-city-population" => params.st_city_population = parse_number(&arg, &value)?,
+                "--st-village-count" => params.st_village_count = parse_number(&arg, &value)?,
+                "--st-village-population" => params.st_village_population = parse_number(&arg, &value)?,
+                "--save" => options.save_file = Some(value),
+                "--stop-at" => options.stop_at = Some(parse_number(&arg, &value)?),
+                "--resume" => options.resume = Some(value),
+                "--dump-events" => options.dump_events = Some(value),
+                "--export" => options.export = Some(value),
+                "--export-format" => options.export_formats = vec!(ExportFormat::parse(&value)?),
+                "--genealogy" => options.genealogy = Some(value),
+                "--genealogy-format" => options.genealogy_formats = vec!(GenealogyFormat::parse(&value)?),
+                "--threads" => options.threads = parse_number(&arg, &value)?,
+                other => return Err(format!("Unknown option {other}\n\n{USAGE}")),
+            }
+        }
+        options.parameters.validate()?;
+        return Ok(Some(options));
+    }
+
+}
+
+/// Generates a world from command line arguments, without a window, and saves it.
+/// The save can later be loaded in the game to create a character.
+pub(crate) fn run(args: Vec<String>) -> Result<(), String> {
+    let options = match HeadlessOptions::parse(args)? {
+        Some(options) => options,
+        None => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+    };
+
+    resources_mut().load();
+    let mut resources = Resources::new();
+    resources.load();
+
+    let params = options.parameters;
+    if options.benchmark {
+        println!("Generating world with seed {} ({}x{}, {} years)", params.seed, params.world_size.x(), params.world_size.y(), params.history_length);
+        return benchmark(params, options.threads, &resources);
+    }
+
+    let generator = match &options.resume {
+        Some(name) => {
+            let world = SaveFile::new(name.clone()).load_world().map_err(|err| err.to_string())?;
+            println!("Resuming {name} from year {} ({} years)", world.date.year(), world.generation_parameters.history_length);
+            WorldHistoryGenerator::simulator(world)
+        },
+        None => {
+            println!("Generating world with seed {} ({}x{}, {} years)", params.seed, params.world_size.x(), params.world_size.y(), params.history_length);
+            let profile = StorytellerProfile::load(&params.storyteller)?;
+            println!("Storyteller: {}. {}", profile.name, profile.description);
+            WorldHistoryGenerator::seed_world(params, &resources)
+        }
+    };
+
+    let now = Instant::now();
+    let world = generate(generator, options.threads, options.sequential, options.stop_at, true);
+    println!("History generated in {:.2?}", now.elapsed());
+
+    let save_file = match options.save_file.or(options.resume) {
+        Some(name) => SaveFile::new(name),
+        None => SaveFile::create_new_save_file().map_err(|err| err.to_string())?,
+    };
+    save_file.save_world(&world).map_err(|err| err.to_string())?;
+    let metadata = save_file.load_metadata().map_err(|err| err.to_string())?;
+    println!("World saved as {}", metadata.save_file_name);
+
+    if let Some(path) = options.dump_events {
+        world.dump_events(&path, &resources);
+        println!("Events written to {path}");
+    }
+
+    if let Some(directory) = &options.export {
+        for format in options.export_formats {
+            export_world(&world, &resources, Path::new(directory), format)?;
+        }
+        println!("History exported to {directory}");
+    }
+
+    if let Some(root) = options.genealogy {
+        let root = GenealogyRoot::parse(&root, &world)?;
+        let directory = options.export.unwrap_or(String::from("export"));
+        for format in options.genealogy_formats {
+            let path = export_genealogy(&world, &resources, root, format, Path::new(&directory))?;
+            println!("Family tree written to {}", path.display());
+        }
+    }
+
+    return Ok(());
+}
+
+fn generate(mut generator: WorldHistoryGenerator, threads: usize, sequential: bool, stop_at: Option<i32>, report: bool) -> World {
+    generator.set_threads(threads);
+    generator.set_sequential(sequential);
+    if let Some(year) = stop_at {
+        generator.stop_at_year(year);
+    }
+    if report {
+        let mut last_reported_year = generator.world.date.year();
+        generator.observe(move |progress: &GenerationProgress| {
+            if progress.year - last_reported_year >= 100 || progress.state == GenerationState::Finished {
+                last_reported_year = progress.year;
+                println!("Year {}/{}: population {}, {} sites, {} events a year", progress.year, progress.target_year, progress.population, progress.sites, progress.events_per_year);
+            }
+        });
+    }
+    generator.run(Duration::months(3), None);
+    return generator.world;
+}
+
+/// Generates the same world with the sequential step, and with the passes on one thread and on the given threads.
+/// Compares the times, and checks the passes make the same history regardless of the threads
+fn benchmark(params: WorldGenerationParameters, threads: usize, resources: &Resources) -> Result<(), String> {
+    let mut results = Vec::new();
+    for (name, threads, sequential) in [("Sequential", 1, true), ("1 thread", 1, false), ("Threads", threads, false)] {
+        let now = Instant::now();
+        let world = generate(WorldHistoryGenerator::seed_world(params.clone(), resources), threads, sequential, None, false);
+        let elapsed = now.elapsed();
+        let population: usize = world.sites.iter().map(|site| site.borrow().creatures.len()).sum();
+        // The sequential history isn't the same, but shouldn't be too far off
+        println!("{name} ({threads}): {elapsed:.2?} (population {population}, {} sites, {} events)", world.sites.len(), world.events.len());
+        let mut buffer = Vec::new();
+        ciborium::into_writer(&world, &mut buffer).map_err(|err| err.to_string())?;
+        results.push((elapsed, buffer));
+    }
+    let (sequential, _) = &results[0];
+    let (single_thread, single_thread_world) = &results[1];
+    let (parallel, parallel_world) = &results[2];
+    println!("Speedup over the sequential step: {:.2}x", sequential.as_secs_f64() / parallel.as_secs_f64().max(f64::EPSILON));
+    println!("Speedup over one thread: {:.2}x", single_thread.as_secs_f64() / parallel.as_secs_f64().max(f64::EPSILON));
+    if single_thread_world != parallel_world {
+        return Err(String::from("The history on several threads differs from the one on a single thread"));
+    }
+    println!("The histories on one thread and on {threads} threads are identical");
+    return Ok(());
+}
+
+fn parse_number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{arg} expects a number, got {value}"))
+}
+
+fn parse_size(value: &str) -> Result<Size2D, String> {
+    let (x, y) = value.split_once('x').ok_or(format!("--world-size expects <w>x<h>, got {value}"))?;
+    Ok(Size2D(parse_number("--world-size", x)?, parse_number("--world-size", y)?))
+}
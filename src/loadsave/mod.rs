@@ -81,7 +81,8 @@ impl SaveFile {
         let timing = Instant::now();
 
         let buffer = File::open(self.path("world")?)?;
-        let world = ciborium::from_reader(buffer)?;
+        let mut world: World = ciborium::from_reader(buffer)?;
+        world.map.fill_missing_layers();
 
         info!("load_world took {:.2?}", timing.elapsed());
 
@@ -3,7 +3,7 @@ use std::{fs::File, io::Read, sync::{LazyLock, Mutex, MutexGuard}};
 use chrono::{DateTime, Local};
 use unic_langid::LanguageIdentifier;
 
-use fluent::{concurrent::FluentBundle, FluentResource};
+use fluent::{concurrent::FluentBundle, FluentArgs, FluentResource};
 
 static LOCALIZATION: LazyLock<Mutex<Localization>> = LazyLock::new(|| Mutex::new(Localization::new()));
 
@@ -34,10 +34,27 @@ impl Localization {
     }
 
     pub(crate) fn try_localize(&self, message: &str) -> Option<String> {
+        return self.try_format(message, None, None);
+    }
+
+    /// Localizes a message with placeables, eg. `{ $name } was born`
+    pub(crate) fn localize_args(&self, message: &str, args: &FluentArgs) -> String {
+        return self.try_format(message, None, Some(args)).unwrap_or(message.to_string());
+    }
+
+    /// Localizes an attribute of a message, eg. `.short` in `event-CreatureBirth`
+    pub(crate) fn localize_attribute(&self, message: &str, attribute: &str, args: &FluentArgs) -> String {
+        return self.try_format(message, Some(attribute), Some(args)).unwrap_or(format!("{message}.{attribute}"));
+    }
+
+    fn try_format(&self, message: &str, attribute: Option<&str>, args: Option<&FluentArgs>) -> Option<String> {
         let msg = self.bundle.get_message(message)?;
         let mut errors = vec![];
-        let pattern = msg.value()?;
-        let value = self.bundle.format_pattern(&pattern, None, &mut errors);
+        let pattern = match attribute {
+            Some(attribute) => msg.get_attribute(attribute)?.value(),
+            None => msg.value()?,
+        };
+        let value = self.bundle.format_pattern(pattern, args, &mut errors);
         return Some(value.to_string());
     }
 
@@ -56,6 +73,8 @@ fn load_bundle(langid: LanguageIdentifier) -> FluentBundle<FluentResource> {
         .expect("Failed to parse an FTL string.");
 
     let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Isolation marks around placeables show up as garbage with the bitmap fonts
+    bundle.set_use_isolating(false);
 
     bundle
         .add_resource(res)
@@ -1,4 +1,4 @@
-use crate::commons::resource_map::{IdentifiedResource, ResourceMap};
+use crate::{commons::resource_map::{IdentifiedResource, ResourceMap}, resources::material::MaterialId};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq)]
 pub(crate) struct BiomeId(usize);
@@ -27,4 +27,6 @@ pub(crate) struct Biome {
     pub(crate) temperature: (u8, u8),
     pub(crate) vegetation: (f32, f32),
     pub(crate) soil_fertility_range: (f32, f32),
+    /// Materials that can be gathered in the biome, and how much a worker gathers in a year
+    pub(crate) materials: Vec<(MaterialId, f32)>,
 }
\ No newline at end of file
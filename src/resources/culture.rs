@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::commons::{markovchains::MarkovChainSingleWordModel, resource_map::ResourceMap};
+use crate::{commons::{markovchains::MarkovChainSingleWordModel, resource_map::ResourceMap, rng::Rng}, resources::biome::BiomeId, world::creature::Profession};
 
 // TODO(0xtBbih5): Should serialize the string id, not the internal id
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
@@ -14,21 +14,125 @@ impl crate::commons::id_vec::Id for CultureId {
     }
 }
 
+impl Default for CultureId {
+    /// culture:default, the first culture loaded
+    fn default() -> Self {
+        CultureId(0)
+    }
+}
+
 pub(crate) type Cultures = ResourceMap<CultureId, Culture>;
 
 impl Cultures {
 
-    pub(crate) fn random(&self) -> CultureId {
-        // TODO:
-        return CultureId(0);
+    pub(crate) fn random(&self, rng: &mut Rng) -> CultureId {
+        return CultureId(rng.randu_range(0, self.iter().len()));
+    }
+
+    /// A random culture among the ones that prefer the biome, or any culture if none does
+    pub(crate) fn fitting_biome(&self, biome_id: &BiomeId, rng: &mut Rng) -> CultureId {
+        let candidates: Vec<CultureId> = self.iter().enumerate()
+            .filter(|(_, culture)| culture.preferred_biomes.contains(biome_id))
+            .map(|(i, _)| CultureId(i))
+            .collect();
+        match rng.item(&candidates) {
+            Some(culture_id) => *culture_id,
+            None => self.random(rng),
+        }
+    }
+
+    /// How much the cultures dislike each other, from 0 to 1
+    pub(crate) fn hostility(&self, a: &CultureId, b: &CultureId) -> f32 {
+        if a == b {
+            return 0.
+        }
+        let a_to_b = self.get(a).hostility_towards(b);
+        let b_to_a = self.get(b).hostility_towards(a);
+        return a_to_b.max(b_to_a)
     }
 
 }
 
 #[derive(Clone)]
 pub(crate) struct Culture {
+    pub(crate) name: String,
     pub(crate) first_name_male_model: MarkovChainSingleWordModel,
     pub(crate) first_name_female_model: MarkovChainSingleWordModel,
     pub(crate) last_name_model: MarkovChainSingleWordModel,
     pub(crate) city_name_model: MarkovChainSingleWordModel,
+    /// Biomes where the culture founds its villages
+    pub(crate) preferred_biomes: Vec<BiomeId>,
+    /// Relative weight of each profession in the culture's villages
+    pub(crate) profession_weights: Vec<(Profession, f32)>,
+    /// Words used to name the artifacts made by the culture's artisans
+    pub(crate) artifact_name_prefixes: Vec<&'static str>,
+    /// How much the culture dislikes other cultures, from 0 to 1
+    pub(crate) hostility: Vec<(CultureId, f32)>,
+    /// What the rulers of the culture's villages are called
+    pub(crate) ruler_title: String,
+    /// Who takes the place of a ruler when they die
+    pub(crate) succession: SuccessionLaw,
+}
+
+impl Culture {
+
+    pub(crate) fn hostility_towards(&self, culture_id: &CultureId) -> f32 {
+        return self.hostility.iter().find(|(id, _)| id == culture_id).map(|(_, hostility)| *hostility).unwrap_or(0.)
+    }
+
+    pub(crate) fn random_profession(&self, rng: &mut Rng) -> Profession {
+        let total: f32 = self.profession_weights.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.randf() * total;
+        for (profession, weight) in self.profession_weights.iter() {
+            if roll < *weight {
+                return *profession
+            }
+            roll -= weight;
+        }
+        return Profession::Peasant
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SuccessionLaw {
+    /// The eldest child of the ruler inherits
+    Primogeniture,
+    /// The people choose a new ruler among themselves
+    Elective,
+    /// The ablest adult of the ruler's family inherits
+    Tanistry,
+}
+
+#[cfg(test)]
+mod tests_culture {
+    use crate::resources::resources::Resources;
+
+    use super::*;
+
+    #[test]
+    fn test_fitting_biome() {
+        let mut resources = Resources::new();
+        resources.load();
+        let forest = resources.biomes.id_of("biome:forest");
+        let mut rng = Rng::seeded("culture");
+
+        for _ in 0..10 {
+            let culture_id = resources.cultures.fitting_biome(&forest, &mut rng);
+            assert!(resources.cultures.get(&culture_id).preferred_biomes.contains(&forest));
+        }
+    }
+
+    #[test]
+    fn test_hostility_is_mutual() {
+        let mut resources = Resources::new();
+        resources.load();
+        let a = resources.cultures.id_of("culture:default");
+        let b = resources.cultures.id_of("culture:khutai");
+
+        assert_eq!(resources.cultures.hostility(&a, &a), 0.);
+        assert_eq!(resources.cultures.hostility(&a, &b), resources.cultures.hostility(&b, &a));
+        assert!(resources.cultures.hostility(&a, &b) > 0.);
+    }
+
 }
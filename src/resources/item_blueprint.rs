@@ -53,6 +53,7 @@ impl<'_a> ItemMaker for IdentifiedResource<'_a, ItemBlueprintId, ItemBlueprint>
             mellee_damage: self.mellee_damage.as_ref().map(|mellee_blueprint| mellee_blueprint.make(&arguments, &resources)),
             armor: self.armor.as_ref().map(|armor| armor.make(&arguments, &resources)),
             artwork_scene: self.artwork_scene.as_ref().map(|artwork_scene| artwork_scene.make(&arguments)),
+            dedicated_to: None,
             cached_inventory_texture: RefCell::new(None),
             cached_placed_texture: RefCell::new(None)
         }
@@ -73,7 +73,7 @@ impl Material {
             sharpness: 0.1,
             strength: 0.7,
             color_pallete: [Color::from_hex("413028"), Color::from_hex("604332"), Color::from_hex("8d634a"), Color::from_hex("604332")],
-            tags_bitmask: MAT_TAG_BONE,
+            tags_bitmask: MAT_TAG_LEATHER,
             extra_damage: DamageRoll::empty(),
         }
     }
@@ -84,7 +84,7 @@ impl Material {
             sharpness: 0.,
             strength: 0.2,
             color_pallete: [Color::from_hex("524235"), Color::from_hex("6e6750"), Color::from_hex("92825c"), Color::from_hex("92825c")],
-            tags_bitmask: MAT_TAG_BONE,
+            tags_bitmask: MAT_TAG_CLOTH,
             extra_damage: DamageRoll::empty(),
         }
     }
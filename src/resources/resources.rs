@@ -2,8 +2,8 @@ use std::{sync::{LazyLock, RwLock, RwLockReadGuard, RwLockWriteGuard}, time::Ins
 
 use image::ImageReader;
 
-use crate::{commons::{damage_model::{DamageModel, DamageRoll}, resource_map::ResourceMap}, engine::{assets::ImageSheetAsset, audio::SoundEffect, geometry::Size2D, pallete_sprite::PalleteSprite, tilemap::{Tile16Subset, TileRandom, TileSingle}, Color}, game::{actor::health_component::BodyPart, inventory::inventory::EquipmentType}, info, resources::{action::{ActionArea, ActionEffect, ActionProjectile, ActionTarget, ImpactPosition, SpellProjectileType, FILTER_CAN_DIG, FILTER_CAN_HARVEST, FILTER_CAN_OCCUPY, FILTER_CAN_SLEEP, FILTER_CAN_VIEW, FILTER_ITEM, FILTER_NOT_HOSTILE}, item_blueprint::{ArmorBlueprintComponent, ConsumableComponent, EquippableComponent}, material::{MAT_TAG_BONE, MAT_TAG_CLOTH, MAT_TAG_LEATHER, MAT_TAG_METAL, MAT_TAG_WOOD}, species::SpeciesAppearance}, world::{attributes::Attributes, item::ActionProviderComponent}, MarkovChainSingleWordModel};
-use super::{action::{Action, Actions, Affliction}, biome::{Biome, Biomes}, culture::{Culture, Cultures}, item_blueprint::{ArtworkSceneBlueprintComponent, ItemBlueprint, ItemBlueprints, MaterialBlueprintComponent, MelleeDamageBlueprintComponent, NameBlueprintComponent, QualityBlueprintComponent}, material::{Material, Materials}, object_tile::{ObjectTile, ObjectTileId}, species::{Species, SpeciesIntelligence, SpeciesMap}, tile::{Tile, TileId}};
+use crate::{commons::{damage_model::{DamageModel, DamageRoll}, resource_map::ResourceMap}, engine::{assets::ImageSheetAsset, audio::SoundEffect, geometry::Size2D, pallete_sprite::PalleteSprite, tilemap::{Tile16Subset, TileRandom, TileSingle}, Color}, game::{actor::health_component::BodyPart, inventory::inventory::EquipmentType}, info, resources::{action::{ActionArea, ActionEffect, ActionProjectile, ActionTarget, ImpactPosition, SpellProjectileType, FILTER_CAN_DIG, FILTER_CAN_HARVEST, FILTER_CAN_OCCUPY, FILTER_CAN_SLEEP, FILTER_CAN_VIEW, FILTER_ITEM, FILTER_NOT_HOSTILE}, item_blueprint::{ArmorBlueprintComponent, ConsumableComponent, EquippableComponent}, material::{MAT_TAG_BONE, MAT_TAG_CLOTH, MAT_TAG_LEATHER, MAT_TAG_METAL, MAT_TAG_WOOD}, species::SpeciesAppearance}, world::{attributes::Attributes, creature::Profession, item::ActionProviderComponent}, MarkovChainSingleWordModel};
+use super::{action::{Action, Actions, Affliction}, biome::{Biome, Biomes}, culture::{Culture, Cultures, SuccessionLaw}, item_blueprint::{ArtworkSceneBlueprintComponent, ItemBlueprint, ItemBlueprints, MaterialBlueprintComponent, MelleeDamageBlueprintComponent, NameBlueprintComponent, QualityBlueprintComponent}, material::{Material, Materials}, object_tile::{ObjectTile, ObjectTileId}, species::{BeastEcology, Species, SpeciesIntelligence, SpeciesMap}, tile::{Tile, TileId}};
 
 static RESOURCES: LazyLock<RwLock<Resources>> = LazyLock::new(|| RwLock::new(Resources::new()));
 
@@ -49,9 +49,9 @@ impl Resources {
         self.load_tiles();
         self.load_object_tiles();
         self.load_actions();
+        self.load_biomes();
         self.load_species();
         self.load_cultures();
-        self.load_biomes();
         self.load_item_blueprints();
         // SMELL: Circular dependency
         self.load_object_tiles_late();
@@ -121,12 +121,21 @@ impl Resources {
             temperature: (0, 255),
             vegetation: (0.5, 1.),
             soil_fertility_range: (1.0, 1.4),
+            materials: vec!(
+                (self.materials.id_of("mat:copper"), 1.),
+                (self.materials.id_of("mat:iron"), 0.5),
+            ),
         });
         self.biomes.add("biome:forest", Biome {
             elevation: (-255, 255),
             temperature: (0, 255),
             vegetation: (0.5, 1.),
             soil_fertility_range: (1.0, 1.4),
+            materials: vec!(
+                (self.materials.id_of("mat:oak"), 1.5),
+                (self.materials.id_of("mat:birch"), 1.5),
+                (self.materials.id_of("mat:iron"), 0.2),
+            ),
         });
     }
 
@@ -573,18 +582,106 @@ impl Resources {
             .hurt_sound(SoundEffect::new(vec!("sfx/wolf_hurt-01.mp3", "sfx/wolf_hurt-02.mp3")))
         );
 
+        let grasslands = self.biomes.id_of("biome:grasslands");
+        let forest = self.biomes.id_of("biome:forest");
+
         self.species.add("species:varningr", Species::new("varningr", SpeciesAppearance::Single("species/varningr/varningr.png".to_string()))
             .intelligence(SpeciesIntelligence::Instinctive)
             .attributes(Attributes { strength: 5, agility: 12, constitution: 10, unallocated: 0 })
             .drops(vec!(self.materials.id_of("mat:varningr_bone")))
             .max_hp(250.)
             .innate_actions(vec!(self.actions.id_of("act:bite_varningr"), self.actions.id_of("act:deafening_howl")))
+            .ecology(BeastEcology {
+                biomes: vec!(forest),
+                spawn_chance: 0.05,
+                level: 3,
+                levels_per_century: 2.,
+                hunting_radius: 5.,
+                lair: String::from("varningr"),
+                minions: Some(self.species.id_of("species:wolf")),
+                brood_chance: 0.01,
+                max_brood: 2,
+                lifespan: 300,
+                name_model: MarkovChainSingleWordModel::train(vec!(
+                    "Hrimgrar", "Skollvar", "Varg", "Ulfrun", "Grimmask", "Hatigr", "Fenrak", "Morgul", "Vornak", "Skaldr", "Garmr", "Ylgrim", "Hrokk", "Draugvar", "Nidvarg", "Sveltr", "Kvarn", "Blodtand", "Isvarg", "Gjallr"
+                ), 3),
+            })
+        );
+
+        self.species.add("species:dragon", Species::new("dragon", SpeciesAppearance::Single("species/dragon/dragon.png".to_string()))
+            .intelligence(SpeciesIntelligence::Instinctive)
+            .attributes(Attributes { strength: 20, agility: 10, constitution: 20, unallocated: 0 })
+            .max_hp(400.)
+            .innate_actions(vec!(self.actions.id_of("act:bite_varningr"), self.actions.id_of("act:firebolt")))
+            .ecology(BeastEcology {
+                biomes: vec!(grasslands),
+                spawn_chance: 0.01,
+                level: 6,
+                levels_per_century: 4.,
+                hunting_radius: 8.,
+                lair: String::from("dragon"),
+                minions: None,
+                brood_chance: 0.002,
+                max_brood: 1,
+                lifespan: 1000,
+                name_model: MarkovChainSingleWordModel::train(vec!(
+                    "Ancalagon", "Glaurung", "Smaug", "Scatha", "Fafnir", "Nidhogg", "Ladon", "Vermithrax", "Ashardalon", "Tiamat", "Klauth", "Iymrith", "Themberchaud", "Chatulio", "Abraxas", "Balagos", "Saryndalaghlothtor", "Morueme", "Arveiaturace", "Claugiyliamatar", "Vorgansharax", "Sapphiratrix", "Halaster", "Valamaradace"
+                ), 3),
+            })
+        );
+
+        self.species.add("species:giant_spider", Species::new("giant spider", SpeciesAppearance::Single("species/spider.png".to_string()))
+            .intelligence(SpeciesIntelligence::Instinctive)
+            .attributes(Attributes { strength: 10, agility: 16, constitution: 12, unallocated: 0 })
+            .max_hp(150.)
+            .innate_actions(vec!(self.actions.id_of("act:spider_bite")))
+            .ecology(BeastEcology {
+                biomes: vec!(forest),
+                spawn_chance: 0.03,
+                level: 2,
+                levels_per_century: 1.,
+                hunting_radius: 4.,
+                lair: String::from("spider"),
+                minions: Some(self.species.id_of("species:spider")),
+                brood_chance: 0.05,
+                max_brood: 4,
+                lifespan: 150,
+                name_model: MarkovChainSingleWordModel::train(vec!(
+                    "Ungoliant", "Shelob", "Lolth", "Arachne", "Selvetarm", "Zaltys", "Kiaransalee", "Ixitxachitl", "Ssilith", "Vhaeraun", "Zesh", "Thiss", "Akkarix", "Sszarn", "Ithriss", "Xizzik", "Nessith", "Chitra", "Sylthris", "Vesskra"
+                ), 3),
+            })
+        );
+
+        self.species.add("species:troll", Species::new("troll", SpeciesAppearance::Single("species/troll/troll.png".to_string()))
+            .intelligence(SpeciesIntelligence::Instinctive)
+            .attributes(Attributes { strength: 18, agility: 8, constitution: 18, unallocated: 0 })
+            .max_hp(300.)
+            .innate_actions(vec!(self.actions.id_of("act:punch"), self.actions.id_of("act:bite_varningr")))
+            .ecology(BeastEcology {
+                biomes: vec!(grasslands, forest),
+                spawn_chance: 0.02,
+                level: 4,
+                levels_per_century: 2.,
+                hunting_radius: 6.,
+                lair: String::from("troll"),
+                minions: None,
+                brood_chance: 0.01,
+                max_brood: 3,
+                lifespan: 400,
+                name_model: MarkovChainSingleWordModel::train(vec!(
+                    "Grumbak", "Ogrok", "Thrugg", "Bolgur", "Skrag", "Gorlag", "Hurgol", "Mokk", "Brunk", "Durgash", "Ugluk", "Grishnak", "Trogg", "Snaga", "Lugdush", "Gromm", "Krunk", "Burzag", "Gnarl", "Haggar"
+                ), 3),
+            })
         );
     }
 
     fn load_cultures(&mut self) {
 
-        self.cultures.add("culture:default", Culture {
+        let grasslands = self.biomes.id_of("biome:grasslands");
+        let forest = self.biomes.id_of("biome:forest");
+
+        let kathayan = self.cultures.add("culture:default", Culture {
+            name: String::from("Kathayan"),
             first_name_male_model: MarkovChainSingleWordModel::train(vec!(
                 "Aawlynson", "Abbathor", "Marcus", "Acuakvacaesin", "Bert", "Adarbron", "Adarl", "Adon", "Adreim", "Adrian", "Adrik", "Ganrahast", "Aelioth", "Aereld", "Aesgareth", "Agnar", "Agni", "Savyels", "Akhlaur", "Akila", "Akimatsu", "Rusty", "Alash", "Alavaernith", "Ali", "Emri", "Saerghon", "Alledec", "Corkitron", "Alnyskawer", "Alobal", "Alsevir", "Dauphran", "Alvarro", "Erilon", "Amalzen", "Amandarn", "Amanthan", "Amaunator", "Petric", "Ambrival", "Ambrose", "Teclel", "Amhar", "Noro", "Naralis", "Anauviir", "Astarion", "Naeryk", "Andras", "Andromalius", "Orluth", "Gyldro", "Anndoquat", "Ansel", "Ansur", "Antaerl", "Antarn", "Anthraxus", "Anubis", "Apep", "Reldyk", "Aptoryx", "Aquilan", "Araithe", "Araleth", "Arash", "Architrave", "Ardalis", "Argathakul", "Arghel", "Argon", "Arkwright", "Danzo", "Ayo", "Tym", "Ildur", "Vicben", "Arthius", "Arthlach", "Arvas", "Arvoreen", "Asglyn", "Ashardalon", "Ashen", "Ashni", "Askell", "Askepel", "Aszhgruzz", "Athlar", "Atson", "Aumvor", "Aunsiber", "Irbryth", "Avarilous", "Banister", "Odezzt", "Orgallryd", "Phargred", "Guldryx", "Axerock", "Azazabus", "Azena'ar", "Azmyrandyr", "Azuth", "Babadul", "Hugo", "Bad", "Badmuddin", "Julani", "Baeraden", "Baerdagh", "Baerengard", "Orbrin", "Baerimgrim", "Rungo", "Baerold", "Bagdog", "Bahamut", "Bahgtru", "Bailey", "Balagos", "Hasheth", "Augustin", "Grykk", "Bantu", "Baphomet", "Barachiel", "Nildon", "Barcus", "Montror", "Barent", "Aldon", "Feston", "Barnabas", "Baron", "Xorthaul", "Bartley", "Barundryn", "Barze", "Bruenor", "Gandalug", "Batu", "Phaernos", "Arls", "Yintras", "Bedi", "Begoas", "Beherit", "Zhuang", "Belarian", "Belathin", "Beldar", "Beldrune", "Kregos", "Bellard", "Anwhar", "Belub-Zi", "Belundrar", "Belvyn", "Belym", "Ben-hadar", "Benevolent", "Beornegar", "Tim", "Berkthgar", "Bertio", "Torin", "Bexendral", "Bezantur", "Bheloris", "Hastar", "Bile-Tooth", "Silas", "Bim", "Birtron", "Drongo", "Florean", "Black", "Dedryk", "Branson", "Blackclaw", "Blackcut", "Klaern", "Korlar", "Othkyn", "Yarjack", "Nets", "Blau", "Bliggerillo", "Sivinil", "Tharl", "Parespur", "Skyrim", "Bug"
             ), 3),
@@ -596,8 +693,89 @@ impl Resources {
             ), 3),
             city_name_model: MarkovChainSingleWordModel::train(vec!(
                 "Luskan", "Mirabar", "Neverwinter", "Waterdeep", "Harrowdale", "Mythdrannor", "Ordulin", "Scardale", "Procampur", "Thultanthar", "Tilverton", "Zhentil", "Bildoobaris", "Immilmar", "Lyrabar", "Telflamm", "Athkatla", "Beregost", "Calimport", "Darromar", "Iriaebor", "Alaghon", "Arrabar", "Ordulin", "Ormath", "Suzail", "Westgate", "Wheloon", "Airspur", "Cimbar", "Djerad", "Thymar", "Eltabbar", "Gheldaneth", "Messemprar", "Ghaast", "Skyclave", "Skuld", "Veltalar", "Mezro", "Narubel", "Tashluta", "Alamontyr", "Cathyr", "Derlusk", "Halarahh", "Rethmar", "Beluir", "Chavyondat", "Vaelan", "Palevash", "Tomyris", "Ausa", "Banang", "Durkon", "Hachoni", "Karatin", "Kirin", "Linshung", "Shangtou", "To'ming", "Tsingtao", "Wai", "Yenching", "Aru", "Chozawa", "Dojyu", "Fochu", "Jasuga", "Masakado", "Nakamaru", "Tupe", "Uwaji", "Hafayah", "Hawa", "Liham", "Muluk", "Qadib", "Qudra", "Umara", "Utaqa", "Halwa", "Hiyal", "Huzuz", "Wasat", "Dihliz", "Kadarasto", "Rog'osto", "Ajayib", "Gana", "Jumlat", "Sikak", "Tajar", "Fahhas", "Hilm", "Hudid", "I'tiraf", "Mahabba", "Talab"
-            ), 3)
+            ), 3),
+            preferred_biomes: vec!(grasslands),
+            profession_weights: vec!(
+                (Profession::Peasant, 0.70),
+                (Profession::Miner, 0.04),
+                (Profession::Woodcutter, 0.04),
+                (Profession::Farmer, 0.08),
+                (Profession::Sculptor, 0.02),
+                (Profession::Blacksmith, 0.05),
+                (Profession::Guard, 0.05),
+                (Profession::Priest, 0.02),
+            ),
+            artifact_name_prefixes: vec!("whisper", "storm", "fire", "moon", "sun", "ice", "raven", "thunder", "flame", "frost", "ember"),
+            hostility: Vec::new(),
+            ruler_title: String::from("Lord"),
+            succession: SuccessionLaw::Primogeniture,
         });
+
+        let vaerdal = self.cultures.add("culture:vaerdal", Culture {
+            name: String::from("Vaerdal"),
+            first_name_male_model: MarkovChainSingleWordModel::train(vec!(
+                "Alvard", "Arnulf", "Asgrim", "Bjarki", "Bolverk", "Brandr", "Dagfinn", "Egil", "Einar", "Eirik", "Eyvind", "Finnbogi", "Frodi", "Geirmund", "Gisli", "Gorm", "Grettir", "Gunnar", "Hakon", "Halfdan", "Hallbjorn", "Harald", "Hauk", "Helgi", "Hjalmar", "Hrafn", "Hrolf", "Ingvar", "Ivar", "Jorund", "Ketil", "Kjartan", "Leif", "Ljot", "Magnar", "Njal", "Odd", "Olvir", "Orm", "Ottar", "Ragnar", "Rolvald", "Runolf", "Sigurd", "Skeggi", "Snorri", "Solvi", "Steinar", "Styrbjorn", "Svein", "Thorald", "Thorbrand", "Thorgest", "Thorvald", "Tyrfing", "Ulf", "Valgard", "Vemund", "Vidar", "Yngvar"
+            ), 3),
+            first_name_female_model: MarkovChainSingleWordModel::train(vec!(
+                "Alfhild", "Asa", "Aslaug", "Astrid", "Bergljot", "Bodil", "Borghild", "Brynja", "Dagny", "Eydis", "Freydis", "Frida", "Gerd", "Gudrun", "Gunnhild", "Gyda", "Halldora", "Hallgerd", "Helga", "Herdis", "Hervor", "Hild", "Hjordis", "Ingrid", "Ingunn", "Jorunn", "Ketilrid", "Liv", "Ragna", "Ragnhild", "Rannveig", "Runa", "Sigrid", "Sigrun", "Signy", "Solveig", "Steinunn", "Svanhild", "Thora", "Thordis", "Thorgerd", "Thorunn", "Thyra", "Tove", "Turid", "Ulfhild", "Unn", "Valdis", "Vigdis", "Yrsa"
+            ), 3),
+            last_name_model: MarkovChainSingleWordModel::train(vec!(
+                "Ashgrove", "Bearhide", "Birchmoor", "Blackfell", "Bloodaxe", "Elkhorn", "Fairhair", "Fellborn", "Frosthelm", "Greycloak", "Greyfjord", "Hardrada", "Hollowtree", "Ironside", "Longbeard", "Mossback", "Oakenshield", "Pinecrest", "Ravenswood", "Redbeard", "Runecarver", "Shieldbreaker", "Skullsplitter", "Snowfell", "Stonefist", "Stormborn", "Strongarm", "Thornwood", "Treefeller", "Wolfsbane", "Wolfskin", "Wyrmbane", "Yewbow", "Barkhall", "Coldwater", "Deepwood", "Elmsong", "Hearthguard", "Mistvale", "Rimeheart"
+            ), 3),
+            city_name_model: MarkovChainSingleWordModel::train(vec!(
+                "Aldeigja", "Birka", "Borgund", "Dalsfjord", "Eidsvoll", "Fjellheim", "Gardarike", "Hedeby", "Hladir", "Holmgard", "Jomsborg", "Kaupang", "Kvenland", "Lindholm", "Lodose", "Nidaros", "Ribe", "Roskilde", "Sigtuna", "Skiringssal", "Skuldelev", "Tingvoll", "Trelleborg", "Tunsberg", "Uppsala", "Vestfold", "Viborg", "Vindland", "Ymsborg", "Alvheim", "Bjarkey", "Grenland", "Hamarr", "Ringerike", "Solund", "Trondheim", "Valdres", "Vingulmark"
+            ), 3),
+            preferred_biomes: vec!(forest),
+            profession_weights: vec!(
+                (Profession::Peasant, 0.58),
+                (Profession::Miner, 0.02),
+                (Profession::Woodcutter, 0.14),
+                (Profession::Farmer, 0.06),
+                (Profession::Sculptor, 0.05),
+                (Profession::Blacksmith, 0.05),
+                (Profession::Guard, 0.08),
+                (Profession::Priest, 0.02),
+            ),
+            artifact_name_prefixes: vec!("wolf", "oak", "frost", "raven", "elk", "rune", "bear", "mist", "ash", "pine", "wyrm"),
+            hostility: Vec::new(),
+            ruler_title: String::from("Jarl"),
+            succession: SuccessionLaw::Elective,
+        });
+
+        let khutai = self.cultures.add("culture:khutai", Culture {
+            name: String::from("Khutai"),
+            first_name_male_model: MarkovChainSingleWordModel::train(vec!(
+                "Altan", "Arslan", "Batbayar", "Bayan", "Bekzat", "Boroldai", "Bukha", "Chagatai", "Chilaun", "Dorji", "Erdene", "Ganbold", "Ganzorig", "Jebe", "Jochi", "Kaidu", "Khasar", "Kublai", "Mergen", "Mongke", "Mukhali", "Nogai", "Ogedei", "Orda", "Qasim", "Sartaq", "Subutai", "Sukhbaatar", "Temur", "Temujin", "Toghrul", "Tolui", "Toqta", "Tumen", "Uzbeg", "Yesugei", "Baatar", "Khulan", "Berke", "Buqa", "Esen", "Janibeg", "Kuchlug", "Naiman", "Oyun", "Sorghan", "Tarkhan", "Tengis", "Ulagchi", "Yalavach"
+            ), 3),
+            first_name_female_model: MarkovChainSingleWordModel::train(vec!(
+                "Alaqai", "Altani", "Anbarci", "Borte", "Checheyigen", "Doquz", "Erdenechimeg", "Gegeen", "Hoelun", "Ibaqa", "Khaidu", "Khojin", "Khutulun", "Mandukhai", "Naraa", "Nomin", "Oghul", "Orqina", "Oyuun", "Sarangerel", "Sorghaghtani", "Toregene", "Tsetseg", "Tuyaa", "Yesui", "Yesugen", "Zaya", "Altantsetseg", "Bolormaa", "Chimeg", "Enkhjin", "Gerel", "Khongorzul", "Narantsetseg", "Odval", "Saran", "Solongo", "Tsolmon", "Urantsetseg", "Delgermaa"
+            ), 3),
+            last_name_model: MarkovChainSingleWordModel::train(vec!(
+                "Borjigin", "Jalayir", "Kereit", "Khongirad", "Merkit", "Naiman", "Olkhunut", "Onggirat", "Tayichiud", "Uriankhai", "Arulat", "Baarin", "Besud", "Dughlat", "Jurkin", "Khatagin", "Khorchin", "Manghud", "Oirat", "Saljiut", "Sunit", "Tumed", "Uighur", "Urud", "Kipchak", "Kangli", "Karluk", "Barlas", "Qunggirat", "Choros", "Dorbet", "Torghut", "Khoshut", "Chahar", "Ordos", "Abaga"
+            ), 3),
+            city_name_model: MarkovChainSingleWordModel::train(vec!(
+                "Karakorum", "Saray", "Khanbaliq", "Almaliq", "Otrar", "Samarkand", "Bukhara", "Urgench", "Balasagun", "Kashgar", "Turfan", "Khotan", "Sighnaq", "Ordubaliq", "Shangdu", "Avarga", "Kharkhorin", "Erdenet", "Bulgan", "Darkhan", "Khovd", "Uliastai", "Zavkhan", "Tsetserleg", "Mandalgovi", "Dalanzadgad", "Baruun", "Altai", "Tamir", "Orkhon", "Selenge", "Kherlen", "Tuul", "Onon"
+            ), 3),
+            preferred_biomes: vec!(grasslands),
+            profession_weights: vec!(
+                (Profession::Peasant, 0.61),
+                (Profession::Miner, 0.06),
+                (Profession::Woodcutter, 0.02),
+                (Profession::Farmer, 0.04),
+                (Profession::Sculptor, 0.01),
+                (Profession::Blacksmith, 0.08),
+                (Profession::Guard, 0.17),
+                (Profession::Priest, 0.01),
+            ),
+            artifact_name_prefixes: vec!("wind", "sky", "horse", "dust", "falcon", "steppe", "blood", "gold", "bone", "dune", "sun"),
+            hostility: Vec::new(),
+            ruler_title: String::from("Khan"),
+            succession: SuccessionLaw::Tanistry,
+        });
+
+        self.cultures.get_mut(&kathayan).hostility = vec!((khutai, 0.6), (vaerdal, 0.2));
+        self.cultures.get_mut(&vaerdal).hostility = vec!((khutai, 0.4));
+        self.cultures.get_mut(&khutai).hostility = vec!((kathayan, 0.5));
     }
 
     fn load_tiles(&mut self) {
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::{resource_map::ResourceMap, rng::Rng}, engine::{assets::{assets, GetSprite, ImageSheetSprite}, audio::SoundEffect, geometry::Size2D}, resources::material::MaterialId, world::{attributes::Attributes, creature::CreatureGender}};
+use crate::{commons::{resource_map::ResourceMap, rng::Rng}, engine::{assets::{assets, GetSprite, ImageSheetSprite}, audio::SoundEffect, geometry::Size2D}, resources::{biome::BiomeId, material::MaterialId}, world::{attributes::Attributes, creature::CreatureGender}, MarkovChainSingleWordModel};
 
 use super::action::ActionId;
 
@@ -27,7 +27,9 @@ pub(crate) struct Species {
     pub(crate) attributes: Attributes,
     pub(crate) innate_actions: Vec<ActionId>,
     pub(crate) drops: Vec<MaterialId>,
-    pub(crate) hurt_sound: Option<SoundEffect>
+    pub(crate) hurt_sound: Option<SoundEffect>,
+    /// Set for great beasts, that make lairs and terrorise the world
+    pub(crate) ecology: Option<BeastEcology>,
 }
 
 impl Species {
@@ -42,6 +44,7 @@ impl Species {
             innate_actions: Vec::new(),
             drops: Vec::new(),
             hurt_sound: None,
+            ecology: None,
         }
     }
 
@@ -75,6 +78,38 @@ impl Species {
         self
     }
 
+    pub(crate) fn ecology(mut self, ecology: BeastEcology) -> Self {
+        self.ecology = Some(ecology);
+        self
+    }
+
+}
+
+/// Where a great beast lives, how it hunts and breeds, and how it grows over the centuries
+#[derive(Debug, Clone)]
+pub(crate) struct BeastEcology {
+    /// Biomes it makes its lairs in
+    pub(crate) biomes: Vec<BiomeId>,
+    /// Chance of a new one appearing in a year
+    pub(crate) spawn_chance: f32,
+    /// Level it appears at
+    pub(crate) level: u16,
+    /// Levels it gains for every century it lives
+    pub(crate) levels_per_century: f32,
+    /// How far from its lair it hunts, in world tiles
+    pub(crate) hunting_radius: f32,
+    /// Prefix of the structure pools of the lair: `{lair}_surface`, `{lair}_lair`, `{lair}_room` and `{lair}_entrance`
+    pub(crate) lair: String,
+    /// Lesser creatures lurking around the lair
+    pub(crate) minions: Option<SpeciesId>,
+    /// Chance of it breeding in a year
+    pub(crate) brood_chance: f32,
+    /// How many can share a lair
+    pub(crate) max_brood: usize,
+    /// Years it lives before old age catches up with it
+    pub(crate) lifespan: i32,
+    /// Names the people it terrorises give it
+    pub(crate) name_model: MarkovChainSingleWordModel,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq)]
@@ -0,0 +1,164 @@
+use math::Vec2i;
+use serde::{Deserialize, Serialize};
+
+use crate::world::{date::WorldDate, topology::WorldTopology};
+
+/// Temperatures at or below this are cold, with long winters
+const COLD_TEMPERATURE: u8 = 0;
+/// Temperatures at or above this are warm, with mild winters
+const WARM_TEMPERATURE: u8 = 4;
+/// Regions drier than this are prone to droughts
+const DRY_PRECIPITATION: u8 = 85;
+/// Regions wetter than this are prone to floods
+const WET_PRECIPITATION: u8 = 170;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Autumn,
+}
+
+impl Season {
+
+    pub(crate) fn of(date: &WorldDate) -> Season {
+        // Month 0 is December
+        match date.month() {
+            0 | 1 | 2 => Season::Winter,
+            3 | 4 | 5 => Season::Spring,
+            6 | 7 | 8 => Season::Summer,
+            _ => Season::Autumn,
+        }
+    }
+
+}
+
+/// The weather a tile usually has through the year
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Climate {
+    pub(crate) temperature: u8,
+    pub(crate) precipitation: u8,
+}
+
+impl Climate {
+
+    pub(crate) fn of(map: &WorldTopology, xy: &Vec2i) -> Climate {
+        let tile = map.tile(xy.x() as usize, xy.y() as usize);
+        return Climate { temperature: tile.temperature, precipitation: tile.precipitation }
+    }
+
+    /// How much the fields yield in the season, compared to an average season
+    pub(crate) fn harvest_multiplier(&self, season: Season) -> f32 {
+        match season {
+            Season::Winter => {
+                if self.temperature <= COLD_TEMPERATURE {
+                    return 0.2
+                }
+                if self.temperature >= WARM_TEMPERATURE {
+                    return 0.8
+                }
+                return 0.5
+            },
+            Season::Spring => 1.,
+            Season::Summer => 1.2,
+            // Harvest time
+            Season::Autumn => 1.3,
+        }
+    }
+
+    /// How much food each creature needs in the season, compared to an average season
+    pub(crate) fn food_consumption(&self, season: Season) -> f32 {
+        match season {
+            Season::Winter => {
+                if self.temperature <= COLD_TEMPERATURE {
+                    return 1.2
+                }
+                if self.temperature >= WARM_TEMPERATURE {
+                    return 1.
+                }
+                return 1.1
+            },
+            _ => 1.,
+        }
+    }
+
+    /// Chance per year that the region suffers a disaster of the kind
+    pub(crate) fn disaster_chance(&self, kind: DisasterKind) -> f32 {
+        match kind {
+            DisasterKind::Drought => match self.precipitation < DRY_PRECIPITATION {
+                true => 0.05,
+                false => 0.01,
+            },
+            DisasterKind::Flood => match self.precipitation > WET_PRECIPITATION {
+                true => 0.05,
+                false => 0.005,
+            },
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum DisasterKind {
+    Drought,
+    Flood,
+}
+
+impl DisasterKind {
+
+    pub(crate) const ALL: [DisasterKind; 2] = [DisasterKind::Drought, DisasterKind::Flood];
+
+}
+
+/// A drought or flood that ruins the harvests of a region for a while
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Disaster {
+    pub(crate) kind: DisasterKind,
+    pub(crate) center: Vec2i,
+    pub(crate) radius: f32,
+    pub(crate) start: WorldDate,
+    pub(crate) end: WorldDate,
+}
+
+impl Disaster {
+
+    pub(crate) fn is_active(&self, date: &WorldDate) -> bool {
+        return date >= &self.start && date < &self.end
+    }
+
+    pub(crate) fn affects(&self, xy: &Vec2i) -> bool {
+        return self.center.dist_squared(xy) <= self.radius * self.radius
+    }
+
+    pub(crate) fn harvest_multiplier(&self) -> f32 {
+        match self.kind {
+            DisasterKind::Drought => 0.3,
+            DisasterKind::Flood => 0.5,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests_climate {
+    use super::*;
+
+    #[test]
+    fn test_seasons() {
+        assert_eq!(Season::of(&WorldDate::new(1, 0, 1)), Season::Winter);
+        assert_eq!(Season::of(&WorldDate::new(1, 4, 1)), Season::Spring);
+        assert_eq!(Season::of(&WorldDate::new(1, 7, 1)), Season::Summer);
+        assert_eq!(Season::of(&WorldDate::new(1, 10, 1)), Season::Autumn);
+    }
+
+    #[test]
+    fn test_cold_winters_are_harsher() {
+        let cold = Climate { temperature: 0, precipitation: 100 };
+        let warm = Climate { temperature: 5, precipitation: 100 };
+        assert!(cold.harvest_multiplier(Season::Winter) < warm.harvest_multiplier(Season::Winter));
+        assert!(cold.food_consumption(Season::Winter) > warm.food_consumption(Season::Winter));
+        assert_eq!(cold.harvest_multiplier(Season::Autumn), warm.harvest_multiplier(Season::Autumn));
+    }
+
+}
@@ -3,9 +3,9 @@ use std::usize;
 use serde::{Deserialize, Serialize};
 use text::capitalize;
 
-use crate::{commons::{bitmask::bitmask_get, id_vec::{Id, IdVec}, rng::Rng}, resources::species::SpeciesId, world::plot::{PlotGoal, PlotId}, Resources};
+use crate::{commons::{bitmask::bitmask_get, id_vec::{Id, IdVec}, rng::Rng}, localization::localization, resources::{material::{MAT_TAG_BONE, MAT_TAG_METAL, MAT_TAG_WOOD}, species::SpeciesId}, world::{plot::{PlotGoal, PlotId}, religion::ReligionId}, Resources};
 
-use super::{date::WorldDate, item::ItemId, lineage::LineageId, site::SiteResources, world::World};
+use super::{date::WorldDate, epidemic::EpidemicId, item::ItemId, lineage::LineageId, site::{SiteId, SiteResources}, world::World};
 
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Serialize, Deserialize)]
@@ -47,6 +47,8 @@ pub(crate) struct Creature {
     pub(crate) sim_flags: u8,
     pub(crate) relationships: Vec<Relationship>,
     pub(crate) supports_plot: Option<PlotId>,
+    #[serde(default)]
+    pub(crate) faith: Option<ReligionId>,
     // TODO(IhlgIYVA): Set maybe?
     pub(crate) goals: Vec<Goal>,
 }
@@ -76,6 +78,13 @@ impl Creature {
         }
 
         let species = resources.species.get(&self.species);
+        // Great beasts get named by the people they terrorise
+        if let Some(ecology) = &species.ecology {
+            if self.sim_flag_is_great_beast() {
+                let name = ecology.name_model.generate(&Rng::seeded(id.as_usize()), 4, 10);
+                return format!("{} the {}", capitalize(&name), species.name);
+            }
+        }
         return format!("the {}", species.name);
     }
 
@@ -102,6 +111,12 @@ impl Creature {
         }
     }
 
+    pub(crate) fn add_goal(&mut self, goal: Goal) {
+        if !self.goals.contains(&goal) {
+            self.goals.push(goal);
+        }
+    }
+
     pub(crate) fn relationship_find(&self, other_creature_id: &CreatureId) -> Option<&Relationship> {
         let pos = self.relationships.binary_search_by(|r| r.creature_id.cmp(other_creature_id));
         match pos {
@@ -131,15 +146,7 @@ impl CreatureGender {
         return false
     }
 
-    pub(crate) fn random() -> Self {
-        if Rng::rand().rand_chance(0.5) {
-            return Self::Male;
-        } else {
-            return Self::Female;
-        }
-    }
-
-    pub(crate) fn random_det(rng: &mut Rng) -> Self {
+    pub(crate) fn random(rng: &mut Rng) -> Self {
         if rng.rand_chance(0.5) {
             return Self::Male;
         } else {
@@ -153,6 +160,7 @@ impl CreatureGender {
 pub(crate) enum CauseOfDeath {
     OldAge,
     Disease,
+    Plague(EpidemicId),
     KilledInBattle(CreatureId, Option<ItemId>)
 }
 
@@ -168,34 +176,66 @@ pub(crate) enum Profession {
     // A peasant is someone trying to make the ends meet. Usually poor, they produce enough food to feed themselves and maybe a child, and pay a little in taxes.
     Peasant,
     Farmer,
+    Miner,
+    Woodcutter,
     // Military
     Guard,
     // Artisans
     Blacksmith,
     Sculptor,
+    // Religious
+    Priest,
     // Political
     Ruler
 }
 
 impl Profession {
 
+    pub(crate) const ALL: [Profession; 12] = [Profession::None, Profession::Beast, Profession::Bandit, Profession::Peasant, Profession::Farmer, Profession::Miner, Profession::Woodcutter, Profession::Guard, Profession::Blacksmith, Profession::Sculptor, Profession::Priest, Profession::Ruler];
+
+    /// Readable name of the profession, eg. "blacksmith"
+    pub(crate) fn name(&self) -> String {
+        return localization().localize(&format!("profession-{:?}", self))
+    }
+
     pub(crate) fn base_resource_production(&self) -> SiteResources {
         match self {
             Profession::None => SiteResources { food: 0. },
             Profession::Peasant => SiteResources { food: 1.5 },
             Profession::Farmer => SiteResources { food: 3.0 },
+            Profession::Miner => SiteResources { food: 0. },
+            Profession::Woodcutter => SiteResources { food: 0. },
             Profession::Bandit => SiteResources { food: 0.8 },
             Profession::Guard => SiteResources { food: 0. },
             Profession::Blacksmith => SiteResources { food: 0. },
             Profession::Sculptor => SiteResources { food: 0. },
+            Profession::Priest => SiteResources { food: 0. },
             Profession::Ruler => SiteResources { food: 0. },
             Profession::Beast => SiteResources { food: 1.5 },
         }
     }
 
+    /// Tags of the materials this profession gathers from the land around the site
+    pub(crate) fn gathered_materials(&self) -> u8 {
+        match self {
+            Profession::Miner => MAT_TAG_METAL,
+            Profession::Woodcutter => MAT_TAG_WOOD,
+            _ => 0,
+        }
+    }
+
+    /// Tags of the materials this profession needs for its craft
+    pub(crate) fn crafting_materials(&self) -> u8 {
+        match self {
+            Profession::Blacksmith => MAT_TAG_METAL | MAT_TAG_WOOD | MAT_TAG_BONE,
+            Profession::Sculptor => MAT_TAG_METAL,
+            _ => 0,
+        }
+    }
+
     pub(crate) fn is_for_life(&self) -> bool {
         match self {
-            Profession::None | Profession::Peasant | Profession::Farmer  | Profession::Guard | Profession::Blacksmith | Profession::Sculptor => false,
+            Profession::None | Profession::Peasant | Profession::Farmer | Profession::Miner | Profession::Woodcutter | Profession::Guard | Profession::Blacksmith | Profession::Sculptor | Profession::Priest => false,
             Profession::Bandit => true,
             Profession::Ruler => true,
             Profession::Beast => true,
@@ -207,10 +247,13 @@ impl Profession {
             Profession::None => [0, 0],
             Profession::Peasant => [1, 10],
             Profession::Farmer => [3, 15],
+            Profession::Miner => [2, 12],
+            Profession::Woodcutter => [2, 12],
             Profession::Bandit => [3, 15],
             Profession::Guard => [5, 20],
             Profession::Blacksmith =>  [5, 20],
             Profession::Sculptor =>  [5, 20],
+            Profession::Priest =>  [3, 15],
             Profession::Ruler =>  [50, 100],
             Profession::Beast => [0, 0],
         }
@@ -328,25 +371,38 @@ mod tests_relationship {
 
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Goal {
     /// Wants a creature dead, by any means necessary
-    KillBeast(CreatureId)
+    KillBeast(CreatureId),
+    /// Wants the murderer of a relative dead
+    Avenge { target: CreatureId, victim: CreatureId },
+    /// Wants to rule in place of the leader of a site
+    OverthrowLeader { site_id: SiteId, leader_id: CreatureId },
+    /// Covets an artifact owned by someone else
+    StealArtifact(ItemId),
+    /// Wants to lead their own village
+    FoundSettlement,
+    /// Wants to marry into a powerful lineage
+    MarryInto(LineageId),
 }
 
 impl Goal {
 
     pub(crate) fn as_plot_goal(&self) -> Option<PlotGoal> {
         match self {
-            Self::KillBeast(id) => Some(PlotGoal::KillBeast(*id))
+            Self::KillBeast(id) => Some(PlotGoal::KillBeast(*id)),
+            Self::Avenge { target, victim } => Some(PlotGoal::Avenge { target: *target, victim: *victim }),
+            Self::OverthrowLeader { site_id, leader_id } => Some(PlotGoal::OverthrowLeader { site_id: *site_id, leader_id: *leader_id }),
+            Self::StealArtifact(item_id) => Some(PlotGoal::StealArtifact(*item_id)),
+            Self::FoundSettlement => Some(PlotGoal::FoundSettlement),
+            Self::MarryInto(lineage_id) => Some(PlotGoal::MarryInto(*lineage_id)),
         }
     }
 
-
-    pub(crate) fn check_completed(&self, world: &World) -> bool {
-        match self {
-            Self::KillBeast(id) => world.creatures.get(id).death.is_some()
-        }
+    /// If the goal was fulfilled, or there's no point in pursuing it anymore. The same as the plot for it would tell
+    pub(crate) fn check_completed(&self, creature_id: &CreatureId, world: &World) -> bool {
+        return self.as_plot_goal().is_some_and(|goal| goal.is_achieved(creature_id, world) || goal.is_lost(creature_id, world))
     }
 
-}
\ No newline at end of file
+}
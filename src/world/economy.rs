@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use crate::{commons::{bitmask::bitmask_get, id_vec::Id, rng::Rng}, resources::{material::{MaterialId, MAT_TAG_BONE, MAT_TAG_METAL}, resources::Resources}, world::{site::{Site, SiteId}, world::World}};
+
+/// How many units of a material each artisan wants to keep in stock
+const ARTISAN_MATERIAL_RESERVE: f32 = 3.;
+/// How many years of food a site wants to keep in stock
+const FOOD_RESERVE_YEARS: f32 = 1.;
+/// Prices never go further than this from the base price
+const MAX_PRICE_MULTIPLIER: f32 = 4.;
+
+/// Something that is produced, stored and traded by a settlement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Good {
+    Food,
+    Material(MaterialId),
+}
+
+impl Good {
+
+    /// Price of one unit when supply meets demand
+    pub(crate) fn base_price(&self, resources: &Resources) -> f32 {
+        match self {
+            Good::Food => 1.,
+            Good::Material(material_id) => {
+                let material = resources.materials.get(material_id);
+                if bitmask_get(material.tags_bitmask, MAT_TAG_BONE) {
+                    return 6.
+                }
+                if bitmask_get(material.tags_bitmask, MAT_TAG_METAL) {
+                    return 4.
+                }
+                return 1.5
+            }
+        }
+    }
+
+}
+
+/// Supply and demand of every good in a site
+pub(crate) struct Market {
+    entries: Vec<MarketEntry>,
+}
+
+struct MarketEntry {
+    good: Good,
+    supply: f32,
+    demand: f32,
+    price: f32,
+}
+
+impl Market {
+
+    pub(crate) fn of_site(site: &Site, world: &World, resources: &Resources) -> Self {
+        let mut market = Market { entries: Vec::new() };
+
+        let food_demand = site.creatures.len() as f32 * FOOD_RESERVE_YEARS;
+        market.add(Good::Food, site.resources.food.max(0.), food_demand, resources);
+
+        let mut crafting_materials = Vec::new();
+        for creature_id in site.creatures.iter() {
+            let creature = world.creatures.get(creature_id);
+            let tags = creature.profession.crafting_materials();
+            if tags != 0 {
+                crafting_materials.push(tags);
+            }
+        }
+
+        for (i, material) in resources.materials.iter().enumerate() {
+            let material_id = MaterialId::new(i);
+            let artisans = crafting_materials.iter().filter(|tags| bitmask_get(material.tags_bitmask, **tags)).count();
+            let supply = match &site.settlement {
+                Some(settlement) => settlement.material_count(&material_id) as f32,
+                None => 0.,
+            };
+            if artisans == 0 && supply == 0. {
+                continue;
+            }
+            market.add(Good::Material(material_id), supply, artisans as f32 * ARTISAN_MATERIAL_RESERVE, resources);
+        }
+
+        return market
+    }
+
+    fn add(&mut self, good: Good, supply: f32, demand: f32, resources: &Resources) {
+        let multiplier = ((demand + 1.) / (supply + 1.)).clamp(1. / MAX_PRICE_MULTIPLIER, MAX_PRICE_MULTIPLIER);
+        self.entries.push(MarketEntry { good, supply, demand, price: good.base_price(resources) * multiplier });
+    }
+
+    pub(crate) fn goods(&self) -> impl Iterator<Item = &Good> {
+        return self.entries.iter().map(|entry| &entry.good)
+    }
+
+    pub(crate) fn price(&self, good: &Good, resources: &Resources) -> f32 {
+        match self.entries.iter().find(|entry| &entry.good == good) {
+            Some(entry) => entry.price,
+            // Nobody here wants it
+            None => good.base_price(resources) / MAX_PRICE_MULTIPLIER,
+        }
+    }
+
+    /// How much of the good the site can spare
+    pub(crate) fn surplus(&self, good: &Good) -> f32 {
+        match self.entries.iter().find(|entry| &entry.good == good) {
+            Some(entry) => (entry.supply - entry.demand).max(0.),
+            None => 0.,
+        }
+    }
+
+    /// How much of the good the site is missing
+    pub(crate) fn shortage(&self, good: &Good) -> f32 {
+        match self.entries.iter().find(|entry| &entry.good == good) {
+            Some(entry) => (entry.demand - entry.supply).max(0.),
+            None => 0.,
+        }
+    }
+
+}
+
+/// Materials gathered by a worker over a period, from the materials available in the biome
+pub(crate) fn gather_materials(biome_materials: &Vec<(MaterialId, f32)>, tags: u8, years: f32, rng: &mut Rng, resources: &Resources) -> Vec<(MaterialId, usize)> {
+    let mut gathered = Vec::new();
+    for (material_id, per_year) in biome_materials.iter() {
+        let material = resources.materials.get(material_id);
+        if !bitmask_get(material.tags_bitmask, tags) {
+            continue;
+        }
+        let amount = per_year * years;
+        let mut count = amount.floor() as usize;
+        if rng.rand_chance(amount.fract()) {
+            count += 1;
+        }
+        if count > 0 {
+            gathered.push((*material_id, count));
+        }
+    }
+    return gathered
+}
+
+/// Travel costs between sites, calculated over the world topology as they are needed.
+/// Sites never move, so a route never has to be calculated twice.
+#[derive(Default)]
+pub(crate) struct TradeRoutes {
+    routes: HashMap<(SiteId, SiteId), Option<f32>>,
+}
+
+impl TradeRoutes {
+
+    /// The cached cost, or None if it wasn't calculated yet
+    pub(crate) fn get(&self, a: &SiteId, b: &SiteId) -> Option<Option<f32>> {
+        return self.routes.get(&Self::key(a, b)).copied()
+    }
+
+    pub(crate) fn insert(&mut self, a: &SiteId, b: &SiteId, cost: Option<f32>) {
+        self.routes.insert(Self::key(a, b), cost);
+    }
+
+    fn key(a: &SiteId, b: &SiteId) -> (SiteId, SiteId) {
+        match a.as_usize() < b.as_usize() {
+            true => (*a, *b),
+            false => (*b, *a),
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests_economy {
+    use crate::world::site::SiteType;
+    use crate::world::world::fixture::{SiteBuilder, WorldFixture};
+
+    use super::*;
+
+    #[test]
+    fn test_prices_follow_supply_and_demand() {
+        let fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let iron = resources.materials.id_of("mat:iron");
+
+        let mut site = SiteBuilder::new(SiteType::Village)
+            .creatures(vec!(fixture.creature_a1, fixture.creature_a2))
+            .settlement(resources.cultures.id_of("culture:default"))
+            .material_stock(vec!((iron, 10)))
+            .build();
+
+        let starving = Market::of_site(&site, &fixture.world, &resources);
+        assert_eq!(starving.shortage(&Good::Food), 2.);
+        assert_eq!(starving.surplus(&Good::Material(iron)), 10.);
+        assert!(starving.price(&Good::Food, &resources) > Good::Food.base_price(&resources));
+        assert!(starving.price(&Good::Material(iron), &resources) < Good::Material(iron).base_price(&resources));
+
+        site.resources.food = 20.;
+        let fed = Market::of_site(&site, &fixture.world, &resources);
+        assert_eq!(fed.surplus(&Good::Food), 18.);
+        assert!(fed.price(&Good::Food, &resources) < starving.price(&Good::Food, &resources));
+    }
+
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{commons::{id_vec::IdVec, rng::Rng}, world::{date::WorldDate, site::SiteId}};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+pub(crate) struct EpidemicId(usize);
+impl crate::commons::id_vec::Id for EpidemicId {
+    fn new(id: usize) -> Self {
+        EpidemicId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Epidemics = IdVec<Epidemic>;
+
+const NAME_ADJECTIVES: [&str; 8] = ["Red", "Grey", "Black", "Weeping", "Shaking", "Burning", "Pale", "Rotting"];
+const NAME_NOUNS: [&str; 7] = ["Fever", "Pox", "Cough", "Sickness", "Plague", "Flux", "Blight"];
+
+/// A disease that spreads from site to site
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Epidemic {
+    pub(crate) name: String,
+    pub(crate) origin: SiteId,
+    pub(crate) start: WorldDate,
+    pub(crate) end: Option<WorldDate>,
+    /// How fast the disease spreads between the sick and the healthy, per year
+    pub(crate) virulence: f32,
+    /// Chance per year that someone sick dies of it
+    pub(crate) lethality: f32,
+    /// Every site the disease reached. A site is never struck twice by the same disease
+    pub(crate) outbreaks: Vec<Outbreak>,
+}
+
+impl Epidemic {
+
+    pub(crate) fn new(origin: SiteId, origin_name: &str, start: WorldDate, rng: &mut Rng) -> Self {
+        let name = match rng.rand_chance(0.3) {
+            true => format!("{} {}", origin_name, NAME_NOUNS[rng.randu_range(0, NAME_NOUNS.len())].to_lowercase()),
+            false => format!("{} {}", NAME_ADJECTIVES[rng.randu_range(0, NAME_ADJECTIVES.len())], NAME_NOUNS[rng.randu_range(0, NAME_NOUNS.len())]),
+        };
+        Self {
+            name,
+            origin,
+            start,
+            end: None,
+            virulence: rng.randf_range(4., 9.),
+            lethality: rng.randf_range(0.6, 1.8),
+            outbreaks: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        return self.end.is_none()
+    }
+
+    pub(crate) fn outbreak_at(&self, site_id: &SiteId) -> Option<&Outbreak> {
+        return self.outbreaks.iter().find(|outbreak| &outbreak.site_id == site_id)
+    }
+
+    pub(crate) fn deaths(&self) -> u32 {
+        return self.outbreaks.iter().map(|outbreak| outbreak.deaths).sum()
+    }
+
+}
+
+/// The disease in a single site. The people are either healthy, sick, or have recovered and can't fall sick again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Outbreak {
+    pub(crate) site_id: SiteId,
+    pub(crate) start: WorldDate,
+    pub(crate) end: Option<WorldDate>,
+    /// Part of the population that is sick
+    pub(crate) infected: f32,
+    /// Part of the population that recovered
+    pub(crate) recovered: f32,
+    pub(crate) deaths: u32,
+}
+
+impl Outbreak {
+
+    pub(crate) fn new(site_id: SiteId, start: WorldDate, infected: f32) -> Self {
+        Self {
+            site_id,
+            start,
+            end: None,
+            infected,
+            recovered: 0.,
+            deaths: 0,
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        return self.end.is_none()
+    }
+
+    /// Advances the disease by a part of a year. The sick infect the healthy, and some of them recover.
+    pub(crate) fn progress(&mut self, virulence: f32, recovery: f32, factor: f32) {
+        let healthy = (1. - self.infected - self.recovered).max(0.);
+        let infections = (virulence * self.infected * healthy * factor).min(healthy);
+        let recoveries = (recovery * self.infected * factor).min(self.infected);
+        self.infected += infections - recoveries;
+        self.recovered += recoveries;
+    }
+
+}
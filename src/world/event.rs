@@ -1,8 +1,9 @@
+use fluent::FluentArgs;
 use serde::{Deserialize, Serialize};
 
-use crate::{resources::resources::Resources, world::world::World};
+use crate::{localization::localization, resources::resources::Resources, world::world::World};
 
-use super::{creature::{CauseOfDeath, CreatureId, Profession}, date::WorldDate, item::ItemId, site::SiteId};
+use super::{climate::DisasterKind, creature::{CauseOfDeath, CreatureGender, CreatureId, Profession}, date::WorldDate, epidemic::EpidemicId, item::ItemId, lineage::LineageId, religion::ReligionId, site::{MigrationCause, SiteId, SiteType, StructureType}, war::{CasusBelli, WarId}};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) enum Event {
@@ -17,10 +18,183 @@ pub(crate) enum Event {
     NewLeaderElected { date: WorldDate, site_id: SiteId, creature_id: CreatureId },
     JoinBanditCamp { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId },
     CreateBanditCamp { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId },
+    WarDeclared { date: WorldDate, war_id: WarId, creature_id: CreatureId, attacker: SiteId, defender: SiteId, cause: CasusBelli },
+    BattleFought { date: WorldDate, war_id: WarId, attacker: SiteId, defender: SiteId, victor: SiteId, attacker_casualties: u32, defender_casualties: u32 },
+    SiteConquered { date: WorldDate, war_id: WarId, site_id: SiteId, conqueror: SiteId },
+    PeaceSigned { date: WorldDate, war_id: WarId, attacker: SiteId, defender: SiteId },
+    TempleBuilt { date: WorldDate, site_id: SiteId, religion_id: ReligionId },
+    CreatureConverted { date: WorldDate, creature_id: CreatureId, priest_id: CreatureId, religion_id: ReligionId },
+    ReligiousSchism { date: WorldDate, creature_id: CreatureId, religion_id: ReligionId, new_religion_id: ReligionId },
+    HeresyDeclared { date: WorldDate, creature_id: CreatureId, heretic_id: CreatureId, religion_id: ReligionId },
+    LeaderOverthrown { date: WorldDate, site_id: SiteId, creature_id: CreatureId, leader_id: CreatureId },
+    ArtifactStolen { date: WorldDate, creature_id: CreatureId, from: CreatureId, item: ItemId },
+    SettlementFounded { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId },
+    PlotFoiled { date: WorldDate, creature_id: CreatureId, victim_id: CreatureId },
+    NobleHouseFounded { date: WorldDate, lineage_id: LineageId, creature_id: CreatureId },
+    TitleInherited { date: WorldDate, site_id: SiteId, creature_id: CreatureId, from: CreatureId },
+    SuccessionCrisis { date: WorldDate, site_id: SiteId, creature_id: CreatureId, claimants: Vec<CreatureId> },
+    PlagueStarted { date: WorldDate, epidemic_id: EpidemicId, site_id: SiteId },
+    PlagueSpread { date: WorldDate, epidemic_id: EpidemicId, site_id: SiteId, from: SiteId },
+    PlagueSubsided { date: WorldDate, epidemic_id: EpidemicId, site_id: SiteId, deaths: u32, abandoned: u32 },
+    Migrated { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId, cause: MigrationCause, household: Vec<CreatureId> },
+    NaturalDisaster { date: WorldDate, kind: DisasterKind, site_id: SiteId },
+    Famine { date: WorldDate, site_id: SiteId },
+    RoadBuilt { date: WorldDate, site_id: SiteId, to: SiteId },
+    RoadPaved { date: WorldDate, site_id: SiteId, to: SiteId },
+    BecameCity { date: WorldDate, site_id: SiteId },
+    DistrictBuilt { date: WorldDate, site_id: SiteId, district: StructureType },
+    SiteFounded { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId, site_type: SiteType },
+    SiteRuined { date: WorldDate, site_id: SiteId },
+    RuinsResettled { date: WorldDate, creature_id: CreatureId, site_id: SiteId, new_site_id: SiteId },
+    RuinsLooted { date: WorldDate, site_id: SiteId, looters: SiteId, item: ItemId },
+    BeastAppeared { date: WorldDate, creature_id: CreatureId, site_id: SiteId },
+    BeastBred { date: WorldDate, creature_id: CreatureId, parent_id: CreatureId, site_id: SiteId },
 }
 
 impl Event {
 
+    pub(crate) fn date(&self) -> &WorldDate {
+        match self {
+            Self::CreatureDeath { date, creature_id: _, cause_of_death: _ } => date,
+            Self::CreatureBirth { date, creature_id: _ } => date,
+            Self::CreatureMarriage { date, creature_id: _, spouse_id: _ } => date,
+            Self::CreatureProfessionChange { date, creature_id: _, new_profession: _ } => date,
+            Self::ArtifactCreated { date, artifact: _, creator: _, site_id: _ } => date,
+            Self::InheritedArtifact { date, creature_id: _, from: _, item: _ } => date,
+            Self::BurriedWithPosessions { date, creature_id: _, items_ids: _ } => date,
+            Self::ArtifactComission { date, creature_id: _, creator_id: _, item_id: _ } => date,
+            Self::NewLeaderElected { date, site_id: _, creature_id: _ } => date,
+            Self::JoinBanditCamp { date, creature_id: _, site_id: _, new_site_id: _ } => date,
+            Self::CreateBanditCamp { date, creature_id: _, site_id: _, new_site_id: _ } => date,
+            Self::WarDeclared { date, war_id: _, creature_id: _, attacker: _, defender: _, cause: _ } => date,
+            Self::BattleFought { date, war_id: _, attacker: _, defender: _, victor: _, attacker_casualties: _, defender_casualties: _ } => date,
+            Self::SiteConquered { date, war_id: _, site_id: _, conqueror: _ } => date,
+            Self::PeaceSigned { date, war_id: _, attacker: _, defender: _ } => date,
+            Self::TempleBuilt { date, site_id: _, religion_id: _ } => date,
+            Self::CreatureConverted { date, creature_id: _, priest_id: _, religion_id: _ } => date,
+            Self::ReligiousSchism { date, creature_id: _, religion_id: _, new_religion_id: _ } => date,
+            Self::HeresyDeclared { date, creature_id: _, heretic_id: _, religion_id: _ } => date,
+            Self::LeaderOverthrown { date, site_id: _, creature_id: _, leader_id: _ } => date,
+            Self::ArtifactStolen { date, creature_id: _, from: _, item: _ } => date,
+            Self::SettlementFounded { date, creature_id: _, site_id: _, new_site_id: _ } => date,
+            Self::PlotFoiled { date, creature_id: _, victim_id: _ } => date,
+            Self::NobleHouseFounded { date, lineage_id: _, creature_id: _ } => date,
+            Self::TitleInherited { date, site_id: _, creature_id: _, from: _ } => date,
+            Self::SuccessionCrisis { date, site_id: _, creature_id: _, claimants: _ } => date,
+            Self::PlagueStarted { date, epidemic_id: _, site_id: _ } => date,
+            Self::PlagueSpread { date, epidemic_id: _, site_id: _, from: _ } => date,
+            Self::PlagueSubsided { date, epidemic_id: _, site_id: _, deaths: _, abandoned: _ } => date,
+            Self::Migrated { date, creature_id: _, site_id: _, new_site_id: _, cause: _, household: _ } => date,
+            Self::NaturalDisaster { date, kind: _, site_id: _ } => date,
+            Self::Famine { date, site_id: _ } => date,
+            Self::RoadBuilt { date, site_id: _, to: _ } => date,
+            Self::RoadPaved { date, site_id: _, to: _ } => date,
+            Self::BecameCity { date, site_id: _ } => date,
+            Self::DistrictBuilt { date, site_id: _, district: _ } => date,
+            Self::SiteFounded { date, creature_id: _, site_id: _, new_site_id: _, site_type: _ } => date,
+            Self::SiteRuined { date, site_id: _ } => date,
+            Self::RuinsResettled { date, creature_id: _, site_id: _, new_site_id: _ } => date,
+            Self::RuinsLooted { date, site_id: _, looters: _, item: _ } => date,
+            Self::BeastAppeared { date, creature_id: _, site_id: _ } => date,
+            Self::BeastBred { date, creature_id: _, parent_id: _, site_id: _ } => date,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> EventKind {
+        match self {
+            Self::CreatureDeath { date: _, creature_id: _, cause_of_death: _ } => EventKind::Death,
+            Self::CreatureBirth { date: _, creature_id: _ } => EventKind::Birth,
+            Self::CreatureMarriage { date: _, creature_id: _, spouse_id: _ } => EventKind::Marriage,
+            Self::CreatureProfessionChange { date: _, creature_id: _, new_profession: _ } => EventKind::Profession,
+            Self::ArtifactCreated { date: _, artifact: _, creator: _, site_id: _ } => EventKind::Artifact,
+            Self::InheritedArtifact { date: _, creature_id: _, from: _, item: _ } => EventKind::Artifact,
+            Self::BurriedWithPosessions { date: _, creature_id: _, items_ids: _ } => EventKind::Artifact,
+            Self::ArtifactComission { date: _, creature_id: _, creator_id: _, item_id: _ } => EventKind::Artifact,
+            Self::NewLeaderElected { date: _, site_id: _, creature_id: _ } => EventKind::Politics,
+            Self::JoinBanditCamp { date: _, creature_id: _, site_id: _, new_site_id: _ } => EventKind::Outlaws,
+            Self::CreateBanditCamp { date: _, creature_id: _, site_id: _, new_site_id: _ } => EventKind::Outlaws,
+            Self::WarDeclared { date: _, war_id: _, creature_id: _, attacker: _, defender: _, cause: _ } => EventKind::War,
+            Self::BattleFought { date: _, war_id: _, attacker: _, defender: _, victor: _, attacker_casualties: _, defender_casualties: _ } => EventKind::War,
+            Self::SiteConquered { date: _, war_id: _, site_id: _, conqueror: _ } => EventKind::War,
+            Self::PeaceSigned { date: _, war_id: _, attacker: _, defender: _ } => EventKind::War,
+            Self::TempleBuilt { date: _, site_id: _, religion_id: _ } => EventKind::Religion,
+            Self::CreatureConverted { date: _, creature_id: _, priest_id: _, religion_id: _ } => EventKind::Religion,
+            Self::ReligiousSchism { date: _, creature_id: _, religion_id: _, new_religion_id: _ } => EventKind::Religion,
+            Self::HeresyDeclared { date: _, creature_id: _, heretic_id: _, religion_id: _ } => EventKind::Religion,
+            Self::LeaderOverthrown { date: _, site_id: _, creature_id: _, leader_id: _ } => EventKind::Politics,
+            Self::ArtifactStolen { date: _, creature_id: _, from: _, item: _ } => EventKind::Artifact,
+            Self::SettlementFounded { date: _, creature_id: _, site_id: _, new_site_id: _ } => EventKind::Settlement,
+            Self::PlotFoiled { date: _, creature_id: _, victim_id: _ } => EventKind::Politics,
+            Self::NobleHouseFounded { date: _, lineage_id: _, creature_id: _ } => EventKind::Politics,
+            Self::TitleInherited { date: _, site_id: _, creature_id: _, from: _ } => EventKind::Politics,
+            Self::SuccessionCrisis { date: _, site_id: _, creature_id: _, claimants: _ } => EventKind::Politics,
+            Self::PlagueStarted { date: _, epidemic_id: _, site_id: _ } => EventKind::Plague,
+            Self::PlagueSpread { date: _, epidemic_id: _, site_id: _, from: _ } => EventKind::Plague,
+            Self::PlagueSubsided { date: _, epidemic_id: _, site_id: _, deaths: _, abandoned: _ } => EventKind::Plague,
+            Self::Migrated { date: _, creature_id: _, site_id: _, new_site_id: _, cause: _, household: _ } => EventKind::Migration,
+            Self::NaturalDisaster { date: _, kind: _, site_id: _ } => EventKind::Disaster,
+            Self::Famine { date: _, site_id: _ } => EventKind::Disaster,
+            Self::RoadBuilt { date: _, site_id: _, to: _ } => EventKind::Settlement,
+            Self::RoadPaved { date: _, site_id: _, to: _ } => EventKind::Settlement,
+            Self::BecameCity { date: _, site_id: _ } => EventKind::Settlement,
+            Self::DistrictBuilt { date: _, site_id: _, district: _ } => EventKind::Settlement,
+            Self::SiteFounded { date: _, creature_id: _, site_id: _, new_site_id: _, site_type: _ } => EventKind::Settlement,
+            Self::SiteRuined { date: _, site_id: _ } => EventKind::Settlement,
+            Self::RuinsResettled { date: _, creature_id: _, site_id: _, new_site_id: _ } => EventKind::Settlement,
+            Self::RuinsLooted { date: _, site_id: _, looters: _, item: _ } => EventKind::Outlaws,
+            Self::BeastAppeared { date: _, creature_id: _, site_id: _ } => EventKind::Beast,
+            Self::BeastBred { date: _, creature_id: _, parent_id: _, site_id: _ } => EventKind::Beast,
+        }
+    }
+
+    /// Sites where the event took place, or that took part in it
+    pub(crate) fn related_sites(&self) -> Vec<SiteId> {
+        match self {
+            Self::CreatureDeath { date: _, creature_id: _, cause_of_death: _ } => vec!(),
+            Self::CreatureBirth { date: _, creature_id: _ } => vec!(),
+            Self::CreatureMarriage { date: _, creature_id: _, spouse_id: _ } => vec!(),
+            Self::CreatureProfessionChange { date: _, creature_id: _, new_profession: _ } => vec!(),
+            Self::ArtifactCreated { date: _, artifact: _, creator: _, site_id } => vec!(*site_id),
+            Self::InheritedArtifact { date: _, creature_id: _, from: _, item: _ } => vec!(),
+            Self::BurriedWithPosessions { date: _, creature_id: _, items_ids: _ } => vec!(),
+            Self::ArtifactComission { date: _, creature_id: _, creator_id: _, item_id: _ } => vec!(),
+            Self::NewLeaderElected { date: _, site_id, creature_id: _ } => vec!(*site_id),
+            Self::JoinBanditCamp { date: _, creature_id: _, site_id, new_site_id } => vec!(*site_id, *new_site_id),
+            Self::CreateBanditCamp { date: _, creature_id: _, site_id, new_site_id } => vec!(*site_id, *new_site_id),
+            Self::WarDeclared { date: _, war_id: _, creature_id: _, attacker, defender, cause: _ } => vec!(*attacker, *defender),
+            Self::BattleFought { date: _, war_id: _, attacker, defender, victor: _, attacker_casualties: _, defender_casualties: _ } => vec!(*attacker, *defender),
+            Self::SiteConquered { date: _, war_id: _, site_id, conqueror } => vec!(*site_id, *conqueror),
+            Self::PeaceSigned { date: _, war_id: _, attacker, defender } => vec!(*attacker, *defender),
+            Self::TempleBuilt { date: _, site_id, religion_id: _ } => vec!(*site_id),
+            Self::CreatureConverted { date: _, creature_id: _, priest_id: _, religion_id: _ } => vec!(),
+            Self::ReligiousSchism { date: _, creature_id: _, religion_id: _, new_religion_id: _ } => vec!(),
+            Self::HeresyDeclared { date: _, creature_id: _, heretic_id: _, religion_id: _ } => vec!(),
+            Self::LeaderOverthrown { date: _, site_id, creature_id: _, leader_id: _ } => vec!(*site_id),
+            Self::ArtifactStolen { date: _, creature_id: _, from: _, item: _ } => vec!(),
+            Self::SettlementFounded { date: _, creature_id: _, site_id, new_site_id } => vec!(*site_id, *new_site_id),
+            Self::PlotFoiled { date: _, creature_id: _, victim_id: _ } => vec!(),
+            Self::NobleHouseFounded { date: _, lineage_id: _, creature_id: _ } => vec!(),
+            Self::TitleInherited { date: _, site_id, creature_id: _, from: _ } => vec!(*site_id),
+            Self::SuccessionCrisis { date: _, site_id, creature_id: _, claimants: _ } => vec!(*site_id),
+            Self::PlagueStarted { date: _, epidemic_id: _, site_id } => vec!(*site_id),
+            Self::PlagueSpread { date: _, epidemic_id: _, site_id, from } => vec!(*site_id, *from),
+            Self::PlagueSubsided { date: _, epidemic_id: _, site_id, deaths: _, abandoned: _ } => vec!(*site_id),
+            Self::Migrated { date: _, creature_id: _, site_id, new_site_id, cause: _, household: _ } => vec!(*site_id, *new_site_id),
+            Self::NaturalDisaster { date: _, kind: _, site_id } => vec!(*site_id),
+            Self::Famine { date: _, site_id } => vec!(*site_id),
+            Self::RoadBuilt { date: _, site_id, to } => vec!(*site_id, *to),
+            Self::RoadPaved { date: _, site_id, to } => vec!(*site_id, *to),
+            Self::BecameCity { date: _, site_id } => vec!(*site_id),
+            Self::DistrictBuilt { date: _, site_id, district: _ } => vec!(*site_id),
+            Self::SiteFounded { date: _, creature_id: _, site_id, new_site_id, site_type: _ } => vec!(*site_id, *new_site_id),
+            Self::SiteRuined { date: _, site_id } => vec!(*site_id),
+            Self::RuinsResettled { date: _, creature_id: _, site_id, new_site_id } => vec!(*site_id, *new_site_id),
+            Self::RuinsLooted { date: _, site_id, looters, item: _ } => vec!(*site_id, *looters),
+            Self::BeastAppeared { date: _, creature_id: _, site_id } => vec!(*site_id),
+            Self::BeastBred { date: _, creature_id: _, parent_id: _, site_id } => vec!(*site_id),
+        }
+    }
+
     pub(crate) fn related_creatures(&self) -> Vec<CreatureId> {
         match self {
             Self::CreatureDeath { date: _, creature_id, cause_of_death } => {
@@ -41,6 +215,43 @@ impl Event {
             Self::NewLeaderElected { date: _, site_id: _, creature_id } => vec!(*creature_id),
             Self::JoinBanditCamp { date: _, creature_id, site_id: _, new_site_id: _ } => vec!(*creature_id),
             Self::CreateBanditCamp { date: _, creature_id, site_id: _, new_site_id: _ } => vec!(*creature_id),
+            Self::WarDeclared { date: _, war_id: _, creature_id, attacker: _, defender: _, cause } => {
+                let mut vec = vec!(*creature_id);
+                if let CasusBelli::Grudge(target_id) = cause {
+                    vec.push(*target_id);
+                }
+                return vec;
+            },
+            Self::BattleFought { date: _, war_id: _, attacker: _, defender: _, victor: _, attacker_casualties: _, defender_casualties: _ } => vec!(),
+            Self::SiteConquered { date: _, war_id: _, site_id: _, conqueror: _ } => vec!(),
+            Self::PeaceSigned { date: _, war_id: _, attacker: _, defender: _ } => vec!(),
+            Self::TempleBuilt { date: _, site_id: _, religion_id: _ } => vec!(),
+            Self::CreatureConverted { date: _, creature_id, priest_id, religion_id: _ } => vec!(*creature_id, *priest_id),
+            Self::ReligiousSchism { date: _, creature_id, religion_id: _, new_religion_id: _ } => vec!(*creature_id),
+            Self::HeresyDeclared { date: _, creature_id, heretic_id, religion_id: _ } => vec!(*creature_id, *heretic_id),
+            Self::LeaderOverthrown { date: _, site_id: _, creature_id, leader_id } => vec!(*creature_id, *leader_id),
+            Self::ArtifactStolen { date: _, creature_id, from, item: _ } => vec!(*creature_id, *from),
+            Self::SettlementFounded { date: _, creature_id, site_id: _, new_site_id: _ } => vec!(*creature_id),
+            Self::PlotFoiled { date: _, creature_id, victim_id } => vec!(*creature_id, *victim_id),
+            Self::NobleHouseFounded { date: _, lineage_id: _, creature_id } => vec!(*creature_id),
+            Self::TitleInherited { date: _, site_id: _, creature_id, from } => vec!(*creature_id, *from),
+            Self::SuccessionCrisis { date: _, site_id: _, creature_id: _, claimants } => claimants.clone(),
+            Self::PlagueStarted { date: _, epidemic_id: _, site_id: _ } => vec!(),
+            Self::PlagueSpread { date: _, epidemic_id: _, site_id: _, from: _ } => vec!(),
+            Self::PlagueSubsided { date: _, epidemic_id: _, site_id: _, deaths: _, abandoned: _ } => vec!(),
+            Self::Migrated { date: _, creature_id: _, site_id: _, new_site_id: _, cause: _, household } => household.clone(),
+            Self::NaturalDisaster { date: _, kind: _, site_id: _ } => vec!(),
+            Self::Famine { date: _, site_id: _ } => vec!(),
+            Self::RoadBuilt { date: _, site_id: _, to: _ } => vec!(),
+            Self::RoadPaved { date: _, site_id: _, to: _ } => vec!(),
+            Self::BecameCity { date: _, site_id: _ } => vec!(),
+            Self::DistrictBuilt { date: _, site_id: _, district: _ } => vec!(),
+            Self::SiteFounded { date: _, creature_id, site_id: _, new_site_id: _, site_type: _ } => vec!(*creature_id),
+            Self::SiteRuined { date: _, site_id: _ } => vec!(),
+            Self::RuinsResettled { date: _, creature_id, site_id: _, new_site_id: _ } => vec!(*creature_id),
+            Self::RuinsLooted { date: _, site_id: _, looters: _, item: _ } => vec!(),
+            Self::BeastAppeared { date: _, creature_id, site_id: _ } => vec!(*creature_id),
+            Self::BeastBred { date: _, creature_id, parent_id, site_id: _ } => vec!(*creature_id, *parent_id),
         }
     }
 
@@ -66,69 +277,502 @@ impl Event {
             Self::NewLeaderElected { date: _, site_id: _, creature_id: _ } => vec!(),
             Self::JoinBanditCamp { date: _, creature_id: _, site_id: _, new_site_id: _ } => vec!(),
             Self::CreateBanditCamp { date: _, creature_id: _, site_id: _, new_site_id: _ } => vec!(),
+            Self::WarDeclared { date: _, war_id: _, creature_id: _, attacker: _, defender: _, cause: _ } => vec!(),
+            Self::BattleFought { date: _, war_id: _, attacker: _, defender: _, victor: _, attacker_casualties: _, defender_casualties: _ } => vec!(),
+            Self::SiteConquered { date: _, war_id: _, site_id: _, conqueror: _ } => vec!(),
+            Self::PeaceSigned { date: _, war_id: _, attacker: _, defender: _ } => vec!(),
+            Self::TempleBuilt { date: _, site_id: _, religion_id: _ } => vec!(),
+            Self::CreatureConverted { date: _, creature_id: _, priest_id: _, religion_id: _ } => vec!(),
+            Self::ReligiousSchism { date: _, creature_id: _, religion_id: _, new_religion_id: _ } => vec!(),
+            Self::HeresyDeclared { date: _, creature_id: _, heretic_id: _, religion_id: _ } => vec!(),
+            Self::LeaderOverthrown { date: _, site_id: _, creature_id: _, leader_id: _ } => vec!(),
+            Self::ArtifactStolen { date: _, creature_id: _, from: _, item } => vec!(*item),
+            Self::SettlementFounded { date: _, creature_id: _, site_id: _, new_site_id: _ } => vec!(),
+            Self::PlotFoiled { date: _, creature_id: _, victim_id: _ } => vec!(),
+            Self::NobleHouseFounded { date: _, lineage_id: _, creature_id: _ } => vec!(),
+            Self::TitleInherited { date: _, site_id: _, creature_id: _, from: _ } => vec!(),
+            Self::SuccessionCrisis { date: _, site_id: _, creature_id: _, claimants: _ } => vec!(),
+            Self::PlagueStarted { date: _, epidemic_id: _, site_id: _ } => vec!(),
+            Self::PlagueSpread { date: _, epidemic_id: _, site_id: _, from: _ } => vec!(),
+            Self::PlagueSubsided { date: _, epidemic_id: _, site_id: _, deaths: _, abandoned: _ } => vec!(),
+            Self::Migrated { date: _, creature_id: _, site_id: _, new_site_id: _, cause: _, household: _ } => vec!(),
+            Self::NaturalDisaster { date: _, kind: _, site_id: _ } => vec!(),
+            Self::Famine { date: _, site_id: _ } => vec!(),
+            Self::RoadBuilt { date: _, site_id: _, to: _ } => vec!(),
+            Self::RoadPaved { date: _, site_id: _, to: _ } => vec!(),
+            Self::BecameCity { date: _, site_id: _ } => vec!(),
+            Self::DistrictBuilt { date: _, site_id: _, district: _ } => vec!(),
+            Self::SiteFounded { date: _, creature_id: _, site_id: _, new_site_id: _, site_type: _ } => vec!(),
+            Self::SiteRuined { date: _, site_id: _ } => vec!(),
+            Self::RuinsResettled { date: _, creature_id: _, site_id: _, new_site_id: _ } => vec!(),
+            Self::RuinsLooted { date: _, site_id: _, looters: _, item } => vec!(*item),
+            Self::BeastAppeared { date: _, creature_id: _, site_id: _ } => vec!(),
+            Self::BeastBred { date: _, creature_id: _, parent_id: _, site_id: _ } => vec!(),
         }
     }
 
-    pub(crate) fn event_text(&self, resources: &Resources, world: &World) -> String {
+    /// Religions whose lore includes the event. Conversions are part of the converted's story, not of the religion's
+    pub(crate) fn related_religions(&self) -> Vec<ReligionId> {
         match self {
-            Event::CreatureBirth { date, creature_id } => {
+            Self::TempleBuilt { date: _, site_id: _, religion_id } => vec!(*religion_id),
+            Self::ReligiousSchism { date: _, creature_id: _, religion_id, new_religion_id } => vec!(*religion_id, *new_religion_id),
+            Self::HeresyDeclared { date: _, creature_id: _, heretic_id: _, religion_id } => vec!(*religion_id),
+            _ => vec!(),
+        }
+    }
+
+    /// Noble houses whose chronicle includes the event: its founding, the rise and fall of its members, and the marriages into it
+    pub(crate) fn related_lineages(&self, world: &World) -> Vec<LineageId> {
+        let creatures = match self {
+            Self::NobleHouseFounded { date: _, lineage_id, creature_id: _ } => return vec!(*lineage_id),
+            Self::CreatureBirth { date: _, creature_id } => vec!(*creature_id),
+            Self::CreatureDeath { date: _, creature_id, cause_of_death: _ } => vec!(*creature_id),
+            Self::CreatureMarriage { date: _, creature_id, spouse_id } => vec!(*creature_id, *spouse_id),
+            Self::NewLeaderElected { date: _, site_id: _, creature_id } => vec!(*creature_id),
+            Self::LeaderOverthrown { date: _, site_id: _, creature_id, leader_id } => vec!(*creature_id, *leader_id),
+            Self::TitleInherited { date: _, site_id: _, creature_id, from } => vec!(*creature_id, *from),
+            Self::SuccessionCrisis { date: _, site_id: _, creature_id: _, claimants } => claimants.clone(),
+            _ => return vec!(),
+        };
+        let mut lineages = Vec::new();
+        for creature_id in creatures {
+            if let Some(lineage_id) = world.creatures.get(&creature_id).lineage {
+                if world.lineages.get(&lineage_id).is_noble() && !lineages.contains(&lineage_id) {
+                    lineages.push(lineage_id);
+                }
+            }
+        }
+        return lineages
+    }
+
+    /// The full account of the event, dated
+    pub(crate) fn event_text(&self, resources: &Resources, world: &World) -> String {
+        let (message, args) = self.narration(resources, world);
+        let text = localization().localize_args(message, &args);
+        let mut args = FluentArgs::new();
+        args.set("date", self.date().fmt_long());
+        args.set("text", text);
+        return localization().localize_args("event-long", &args);
+    }
+
+    /// A line about the event, for lists and logs
+    pub(crate) fn short_text(&self, resources: &Resources, world: &World) -> String {
+        let (message, args) = self.narration(resources, world);
+        return localization().localize_attribute(message, "short", &args);
+    }
+
+    /// The short line with the year, for lists of events
+    pub(crate) fn list_text(&self, resources: &Resources, world: &World) -> String {
+        let mut args = FluentArgs::new();
+        args.set("year", self.date().year().to_string());
+        args.set("text", self.short_text(resources, world));
+        return localization().localize_args("event-list", &args);
+    }
+
+    /// The message that tells the event, and the names, places and numbers that go into it
+    fn narration(&self, resources: &Resources, world: &World) -> (&'static str, FluentArgs<'static>) {
+        let name = |creature_id: &CreatureId| world.creature_desc(creature_id, resources);
+        let gender = |creature_id: &CreatureId| match world.creatures.get(creature_id).gender {
+            CreatureGender::Male => "male",
+            CreatureGender::Female => "female",
+        };
+        let site = |site_id: &SiteId| String::from(world.sites.get(site_id).name());
+        let artifact = |item_id: &ItemId| world.artifacts.get(item_id).name(&resources.materials);
+
+        let mut args = FluentArgs::new();
+        let message = match self {
+            Event::CreatureDeath { date: _, creature_id, cause_of_death } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                match cause_of_death {
+                    CauseOfDeath::OldAge => args.set("cause", "OldAge"),
+                    CauseOfDeath::Disease => args.set("cause", "Disease"),
+                    CauseOfDeath::Plague(epidemic_id) => {
+                        args.set("cause", "Plague");
+                        args.set("epidemic", world.epidemics.get(epidemic_id).name.clone());
+                    },
+                    CauseOfDeath::KilledInBattle(killer_id, weapon) => {
+                        args.set("killer", name(killer_id));
+                        match weapon {
+                            Some(item_id) => {
+                                args.set("cause", "KilledWithWeapon");
+                                args.set("weapon", artifact(item_id));
+                            },
+                            None => args.set("cause", "KilledInBattle"),
+                        }
+                    },
+                }
+                "event-CreatureDeath"
+            },
+            Event::CreatureBirth { date: _, creature_id } => {
                 let creature = world.creatures.get(creature_id);
-                let name = world.creature_desc(creature_id, resources);
-                let father = world.creature_desc(&creature.father, resources);
-                let mother = world.creature_desc(&creature.mother, resources);
-                return format!("> {}, {} was born. Father: {}, Mother: {}", world.date_desc(date), name, father, mother);
-            },
-            Event::CreatureDeath { date, creature_id, cause_of_death } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} died of {:?}", world.date_desc(date), name, cause_of_death);
-            },
-            Event::CreatureMarriage { date, creature_id, spouse_id } => {
-                let name_a = world.creature_desc(creature_id, resources);
-                let name_b = world.creature_desc(spouse_id, resources);
-                return format!("> {}, {} and {} married", world.date_desc(date), name_a, name_b);
-            },
-            Event::CreatureProfessionChange { date, creature_id, new_profession } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} became a {:?}", world.date_desc(date), name, new_profession);
-            },
-            Event::ArtifactCreated { date, artifact, creator, site_id } => {
-                let name = world.creature_desc(creator, resources);
-                let artifact = world.artifacts.get(artifact);
-                return format!("> {}, {} created {:?} in {:?}", world.date_desc(date), name, artifact.name(&resources.materials), site_id);
-            },
-            Event::BurriedWithPosessions { date, creature_id, items_ids: _ } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} was buried with their possessions", world.date_desc(date), name);
-            },
-            Event::InheritedArtifact { date, creature_id, from, item } => {
-                let name = world.creature_desc(creature_id, resources);
-                let name_b = world.creature_desc(from, resources);
-                let artifact = world.artifacts.get(item);
-                return format!("> {}, {} inherited {} from {}", world.date_desc(date), name, artifact.name(&resources.materials), name_b);
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("father", name(&creature.father));
+                args.set("mother", name(&creature.mother));
+                "event-CreatureBirth"
+            },
+            Event::CreatureMarriage { date: _, creature_id, spouse_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(spouse_id));
+                "event-CreatureMarriage"
+            },
+            Event::CreatureProfessionChange { date: _, creature_id, new_profession } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("profession", format!("{:?}", new_profession));
+                args.set("profession_name", new_profession.name());
+                "event-CreatureProfessionChange"
+            },
+            Event::ArtifactCreated { date: _, artifact: item_id, creator, site_id } => {
+                args.set("creator", name(creator));
+                args.set("artifact", artifact(item_id));
+                args.set("site", site(site_id));
+                "event-ArtifactCreated"
+            },
+            Event::BurriedWithPosessions { date: _, creature_id, items_ids: _ } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                "event-BurriedWithPosessions"
+            },
+            Event::InheritedArtifact { date: _, creature_id, from, item } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(from));
+                args.set("artifact", artifact(item));
+                "event-InheritedArtifact"
             },
             Event::ArtifactComission { date, creature_id, creator_id, item_id } => {
-                let name = world.creature_desc(creature_id, resources);
-                let name_b = world.creature_desc(creator_id, resources);
-                let artifact = world.artifacts.get(item_id);
-                let creature = world.creatures.get(creature_id);
-                let age = (*date - creature.birth).get_years();
-                return format!("> {}, {} commissioned {} from {} for his {}th birthday", world.date_desc(date), name, artifact.name(&resources.materials), name_b, age);
+                let age = (*date - world.creatures.get(creature_id).birth).get_years();
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(creator_id));
+                args.set("artifact", artifact(item_id));
+                args.set("age", age);
+                "event-ArtifactComission"
             },
-            Event::NewLeaderElected { date, site_id, creature_id } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} was elected new leader of {:?}", world.date_desc(date), name, *site_id);
+            Event::NewLeaderElected { date: _, site_id, creature_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                "event-NewLeaderElected"
             },
-            Event::JoinBanditCamp { date, creature_id, site_id, new_site_id } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} left {:?} and joined the bandits at {:?}", world.date_desc(date), name, *site_id, *new_site_id);
+            Event::JoinBanditCamp { date: _, creature_id, site_id, new_site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                "event-JoinBanditCamp"
+            },
+            Event::CreateBanditCamp { date: _, creature_id, site_id, new_site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                "event-CreateBanditCamp"
+            },
+            Event::WarDeclared { date: _, war_id: _, creature_id, attacker, defender, cause } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("attacker", site(attacker));
+                args.set("defender", site(defender));
+                match cause {
+                    CasusBelli::FoodShortage => args.set("cause", "FoodShortage"),
+                    CasusBelli::Grudge(target_id) => {
+                        args.set("cause", "Grudge");
+                        args.set("target", name(target_id));
+                    },
+                    CasusBelli::Hostility(culture_id) => {
+                        args.set("cause", "Hostility");
+                        args.set("culture", resources.cultures.get(culture_id).name.clone());
+                    },
+                }
+                "event-WarDeclared"
             },
-            Event::CreateBanditCamp { date, creature_id, site_id, new_site_id } => {
-                let name = world.creature_desc(creature_id, resources);
-                return format!("> {}, {} left {:?} and started a bandit camp at {:?}", world.date_desc(date), name, *site_id, *new_site_id);
+            Event::BattleFought { date: _, war_id: _, attacker, defender, victor, attacker_casualties, defender_casualties } => {
+                args.set("attacker", site(attacker));
+                args.set("defender", site(defender));
+                args.set("victor", site(victor));
+                args.set("attacker_casualties", *attacker_casualties);
+                args.set("defender_casualties", *defender_casualties);
+                "event-BattleFought"
             },
+            Event::SiteConquered { date: _, war_id: _, site_id, conqueror } => {
+                args.set("site", site(site_id));
+                args.set("conqueror", site(conqueror));
+                "event-SiteConquered"
+            },
+            Event::PeaceSigned { date: _, war_id: _, attacker, defender } => {
+                args.set("attacker", site(attacker));
+                args.set("defender", site(defender));
+                "event-PeaceSigned"
+            },
+            Event::TempleBuilt { date: _, site_id, religion_id } => {
+                let religion = world.religions.get(religion_id);
+                args.set("site", site(site_id));
+                args.set("deity", world.deities.get(&religion.patron).description());
+                "event-TempleBuilt"
+            },
+            Event::CreatureConverted { date: _, creature_id, priest_id, religion_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(priest_id));
+                args.set("religion", world.religions.get(religion_id).name.clone());
+                "event-CreatureConverted"
+            },
+            Event::ReligiousSchism { date: _, creature_id, religion_id, new_religion_id } => {
+                let new_religion = world.religions.get(new_religion_id);
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("religion", world.religions.get(religion_id).name.clone());
+                args.set("new_religion", new_religion.name.clone());
+                args.set("deity", world.deities.get(&new_religion.patron).description());
+                "event-ReligiousSchism"
+            },
+            Event::HeresyDeclared { date: _, creature_id, heretic_id, religion_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(heretic_id));
+                args.set("religion", world.religions.get(religion_id).name.clone());
+                "event-HeresyDeclared"
+            },
+            Event::LeaderOverthrown { date: _, site_id, creature_id, leader_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(leader_id));
+                args.set("site", site(site_id));
+                "event-LeaderOverthrown"
+            },
+            Event::ArtifactStolen { date: _, creature_id, from, item } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(from));
+                args.set("artifact", artifact(item));
+                "event-ArtifactStolen"
+            },
+            Event::SettlementFounded { date: _, creature_id, site_id, new_site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                "event-SettlementFounded"
+            },
+            Event::PlotFoiled { date: _, creature_id, victim_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(victim_id));
+                "event-PlotFoiled"
+            },
+            Event::NobleHouseFounded { date: _, lineage_id, creature_id } => {
+                let lineage = world.lineages.get(lineage_id);
+                let arms = match &lineage.heraldry {
+                    Some(heraldry) => heraldry.description(),
+                    None => localization().localize("event-no-arms"),
+                };
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("house", lineage.name.clone());
+                args.set("arms", arms);
+                "event-NobleHouseFounded"
+            },
+            Event::TitleInherited { date: _, site_id, creature_id, from } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("other", name(from));
+                args.set("site", site(site_id));
+                "event-TitleInherited"
+            },
+            Event::SuccessionCrisis { date: _, site_id, creature_id, claimants } => {
+                let rivals: Vec<String> = claimants.iter().filter(|id| *id != creature_id).map(name).collect();
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("rivals", rivals.len());
+                args.set("claimants", rivals.join(", "));
+                "event-SuccessionCrisis"
+            },
+            Event::PlagueStarted { date: _, epidemic_id, site_id } => {
+                args.set("epidemic", world.epidemics.get(epidemic_id).name.clone());
+                args.set("site", site(site_id));
+                "event-PlagueStarted"
+            },
+            Event::PlagueSpread { date: _, epidemic_id, site_id, from } => {
+                args.set("epidemic", world.epidemics.get(epidemic_id).name.clone());
+                args.set("site", site(site_id));
+                args.set("from", site(from));
+                "event-PlagueSpread"
+            },
+            Event::PlagueSubsided { date: _, epidemic_id, site_id, deaths, abandoned } => {
+                args.set("epidemic", world.epidemics.get(epidemic_id).name.clone());
+                args.set("site", site(site_id));
+                args.set("deaths", *deaths);
+                args.set("abandoned", *abandoned);
+                "event-PlagueSubsided"
+            },
+            Event::Migrated { date: _, creature_id, site_id, new_site_id, cause, household } => {
+                let reason = localization().localize(&format!("migration-cause-{:?}", cause));
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                args.set("company", household.len().saturating_sub(1));
+                args.set("reason", reason);
+                "event-Migrated"
+            },
+            Event::NaturalDisaster { date: _, kind, site_id } => {
+                args.set("disaster", format!("{:?}", kind));
+                args.set("site", site(site_id));
+                "event-NaturalDisaster"
+            },
+            Event::Famine { date: _, site_id } => {
+                args.set("site", site(site_id));
+                "event-Famine"
+            },
+            Event::RoadBuilt { date: _, site_id, to } => {
+                args.set("site", site(site_id));
+                args.set("to", site(to));
+                "event-RoadBuilt"
+            },
+            Event::RoadPaved { date: _, site_id, to } => {
+                args.set("site", site(site_id));
+                args.set("to", site(to));
+                "event-RoadPaved"
+            },
+            Event::BecameCity { date: _, site_id } => {
+                args.set("site", site(site_id));
+                "event-BecameCity"
+            },
+            Event::DistrictBuilt { date: _, site_id, district } => {
+                args.set("site", site(site_id));
+                args.set("district", format!("{:?}", district));
+                "event-DistrictBuilt"
+            },
+            Event::SiteFounded { date: _, creature_id, site_id, new_site_id, site_type } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                args.set("site_type", format!("{:?}", site_type));
+                "event-SiteFounded"
+            },
+            Event::SiteRuined { date: _, site_id } => {
+                args.set("site", site(site_id));
+                "event-SiteRuined"
+            },
+            Event::RuinsResettled { date: _, creature_id, site_id, new_site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                args.set("new_site", site(new_site_id));
+                "event-RuinsResettled"
+            },
+            Event::RuinsLooted { date: _, site_id, looters, item } => {
+                args.set("site", site(site_id));
+                args.set("looters", site(looters));
+                args.set("artifact", artifact(item));
+                "event-RuinsLooted"
+            },
+            Event::BeastAppeared { date: _, creature_id, site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("site", site(site_id));
+                "event-BeastAppeared"
+            },
+            Event::BeastBred { date: _, creature_id, parent_id, site_id } => {
+                args.set("name", name(creature_id));
+                args.set("gender", gender(creature_id));
+                args.set("parent", name(parent_id));
+                args.set("site", site(site_id));
+                "event-BeastBred"
+            },
+        };
+        return (message, args);
+    }
+
+}
+
+/// Broad categories of events, to filter history by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum EventKind {
+    Birth,
+    Death,
+    Marriage,
+    Profession,
+    Artifact,
+    Politics,
+    Outlaws,
+    War,
+    Religion,
+    Plague,
+    Migration,
+    Disaster,
+    Settlement,
+    Beast,
+}
+
+impl EventKind {
+
+    pub(crate) const ALL: [EventKind; 14] = [EventKind::Birth, EventKind::Death, EventKind::Marriage, EventKind::Profession, EventKind::Artifact, EventKind::Politics, EventKind::Outlaws, EventKind::War, EventKind::Religion, EventKind::Plague, EventKind::Migration, EventKind::Disaster, EventKind::Settlement, EventKind::Beast];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            EventKind::Birth => "Birth",
+            EventKind::Death => "Death",
+            EventKind::Marriage => "Marriage",
+            EventKind::Profession => "Profession",
+            EventKind::Artifact => "Artifact",
+            EventKind::Politics => "Politics",
+            EventKind::Outlaws => "Outlaws",
+            EventKind::War => "War",
+            EventKind::Religion => "Religion",
+            EventKind::Plague => "Plague",
+            EventKind::Migration => "Migration",
+            EventKind::Disaster => "Disaster",
+            EventKind::Settlement => "Settlement",
+            EventKind::Beast => "Great beast",
         }
-            
+    }
+
+}
+
+#[cfg(test)]
+mod tests_event {
+    use crate::{commons::id_vec::Id, world::{site::SiteId, world::fixture::WorldFixture}};
+
+    use super::*;
 
+    #[test]
+    fn test_event_text() {
+        let fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let world = &fixture.world;
+        let date = WorldDate::new(12, 3, 2);
+        let husband = world.creature_desc(&fixture.creature_a1, &resources);
+        let wife = world.creature_desc(&fixture.creature_a2, &resources);
+        let site_id = SiteId::new(0);
+        let site = String::from(world.sites.get(&site_id).name());
+
+        let event = Event::BurriedWithPosessions { date, creature_id: fixture.creature_a2, items_ids: Vec::new() };
+        assert_eq!(event.event_text(&resources, world), format!("On the 2nd of Mar., 12, {wife} was buried with her possessions."));
+        assert_eq!(event.short_text(&resources, world), format!("{wife} was buried"));
+
+        let event = Event::CreatureDeath { date, creature_id: fixture.creature_a1, cause_of_death: CauseOfDeath::OldAge };
+        assert_eq!(event.short_text(&resources, world), format!("{husband} died"));
+        assert!(event.event_text(&resources, world).ends_with(&format!("{husband} died of old age.")));
+
+        let event = Event::CreatureProfessionChange { date, creature_id: fixture.creature_a1, new_profession: Profession::Blacksmith };
+        assert!(event.event_text(&resources, world).ends_with(&format!("{husband} became a blacksmith.")));
+        let event = Event::CreatureProfessionChange { date, creature_id: fixture.creature_a1, new_profession: Profession::None };
+        assert_eq!(event.short_text(&resources, world), format!("{husband} gave up working"));
+        assert_eq!(event.list_text(&resources, world), format!("12: {husband} gave up working"));
+
+        let event = Event::Migrated { date, creature_id: fixture.creature_a1, site_id, new_site_id: site_id, cause: MigrationCause::Famine, household: vec!(fixture.creature_a1, fixture.creature_a2) };
+        assert!(event.event_text(&resources, world).ends_with(&format!("{husband} left {site} with 1 of his family for {site}, fleeing famine.")));
+
+        let event = Event::Famine { date, site_id };
+        assert_eq!(event.short_text(&resources, world), format!("Famine struck {site}"));
     }
 
-}
\ No newline at end of file
+}
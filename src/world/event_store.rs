@@ -0,0 +1,304 @@
+use std::{collections::HashMap, hash::Hash, ops::Index};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::{creature::CreatureId, date::WorldDate, event::{Event, EventKind}, item::ItemId, site::SiteId};
+
+/// The history of the world, indexed by creature, site, artifact and kind.
+/// Events are identified by their position, the same ids the codex keeps.
+/// Only the events are saved, the indexes are rebuilt on load.
+#[derive(Deserialize)]
+#[serde(from = "Vec<Event>")]
+pub(crate) struct EventStore {
+    events: Vec<Event>,
+    by_creature: HashMap<CreatureId, Vec<usize>>,
+    by_site: HashMap<SiteId, Vec<usize>>,
+    by_artifact: HashMap<ItemId, Vec<usize>>,
+    by_kind: HashMap<EventKind, Vec<usize>>,
+    /// Whether the events were recorded in date order, so date ranges can be binary searched
+    chronological: bool,
+}
+
+impl EventStore {
+
+    pub(crate) fn new() -> Self {
+        return Self {
+            events: Vec::new(),
+            by_creature: HashMap::new(),
+            by_site: HashMap::new(),
+            by_artifact: HashMap::new(),
+            by_kind: HashMap::new(),
+            chronological: true,
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: Event) {
+        let i = self.events.len();
+        if let Some(last) = self.events.last() {
+            self.chronological = self.chronological && last.date() <= event.date();
+        }
+        for creature_id in event.related_creatures() {
+            add_to_index(&mut self.by_creature, creature_id, i);
+        }
+        for site_id in event.related_sites() {
+            add_to_index(&mut self.by_site, site_id, i);
+        }
+        for item_id in event.related_artifacts() {
+            add_to_index(&mut self.by_artifact, item_id, i);
+        }
+        add_to_index(&mut self.by_kind, event.kind(), i);
+        self.events.push(event);
+    }
+
+    pub(crate) fn get(&self, i: usize) -> Option<&Event> {
+        return self.events.get(i)
+    }
+
+    pub(crate) fn last(&self) -> Option<&Event> {
+        return self.events.last()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        return self.events.len()
+    }
+
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Event> {
+        return self.events.iter()
+    }
+
+    /// Starts a query over every event. Filters are combined, and the results come in the order they happened.
+    pub(crate) fn query(&self) -> EventQuery<'_> {
+        return EventQuery { store: self, creature: None, site: None, artifact: None, kind: None, since: None, before: None }
+    }
+
+    pub(crate) fn by_creature(&self, creature_id: &CreatureId) -> impl Iterator<Item = (usize, &Event)> {
+        return self.query().creature(creature_id).iter()
+    }
+
+    pub(crate) fn by_site(&self, site_id: &SiteId) -> impl Iterator<Item = (usize, &Event)> {
+        return self.query().site(site_id).iter()
+    }
+
+    pub(crate) fn by_artifact(&self, item_id: &ItemId) -> impl Iterator<Item = (usize, &Event)> {
+        return self.query().artifact(item_id).iter()
+    }
+
+    pub(crate) fn by_kind(&self, kind: EventKind) -> impl Iterator<Item = (usize, &Event)> {
+        return self.query().kind(kind).iter()
+    }
+
+    /// Events from `since` up to, but not including, `before`
+    pub(crate) fn between(&self, since: WorldDate, before: WorldDate) -> impl Iterator<Item = (usize, &Event)> {
+        return self.query().since(since).before(before).iter()
+    }
+
+    /// First event that happened on or after the date
+    fn first_on_or_after(&self, date: &WorldDate) -> usize {
+        return self.events.partition_point(|event| event.date() < date)
+    }
+
+}
+
+fn add_to_index<K: Eq + Hash>(index: &mut HashMap<K, Vec<usize>>, key: K, i: usize) {
+    let ids = index.entry(key).or_default();
+    // An event can mention the same creature twice, eg. a creature inheriting from itself
+    if ids.last() != Some(&i) {
+        ids.push(i);
+    }
+}
+
+fn index_of<'a, K: Eq + Hash>(index: &'a HashMap<K, Vec<usize>>, key: &K) -> &'a [usize] {
+    return index.get(key).map(|ids| ids.as_slice()).unwrap_or(&[])
+}
+
+impl From<Vec<Event>> for EventStore {
+    fn from(events: Vec<Event>) -> Self {
+        let mut store = EventStore::new();
+        for event in events {
+            store.push(event);
+        }
+        return store
+    }
+}
+
+impl Serialize for EventStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return self.events.serialize(serializer)
+    }
+}
+
+impl Default for EventStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for EventStore {
+    type Output = Event;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        return &self.events[i]
+    }
+}
+
+/// A filter over the events. Walks the shortest matching index, and checks the others.
+pub(crate) struct EventQuery<'a> {
+    store: &'a EventStore,
+    creature: Option<CreatureId>,
+    site: Option<SiteId>,
+    artifact: Option<ItemId>,
+    kind: Option<EventKind>,
+    since: Option<WorldDate>,
+    before: Option<WorldDate>,
+}
+
+impl<'a> EventQuery<'a> {
+
+    pub(crate) fn creature(mut self, creature_id: &CreatureId) -> Self {
+        self.creature = Some(*creature_id);
+        return self
+    }
+
+    pub(crate) fn site(mut self, site_id: &SiteId) -> Self {
+        self.site = Some(*site_id);
+        return self
+    }
+
+    pub(crate) fn artifact(mut self, item_id: &ItemId) -> Self {
+        self.artifact = Some(*item_id);
+        return self
+    }
+
+    pub(crate) fn kind(mut self, kind: EventKind) -> Self {
+        self.kind = Some(kind);
+        return self
+    }
+
+    /// Only events on or after the date
+    pub(crate) fn since(mut self, date: WorldDate) -> Self {
+        self.since = Some(date);
+        return self
+    }
+
+    /// Only events strictly before the date
+    pub(crate) fn before(mut self, date: WorldDate) -> Self {
+        self.before = Some(date);
+        return self
+    }
+
+    fn indexes(&self) -> Vec<&'a [usize]> {
+        let store = self.store;
+        let mut indexes = Vec::new();
+        if let Some(creature_id) = &self.creature {
+            indexes.push(index_of(&store.by_creature, creature_id));
+        }
+        if let Some(site_id) = &self.site {
+            indexes.push(index_of(&store.by_site, site_id));
+        }
+        if let Some(item_id) = &self.artifact {
+            indexes.push(index_of(&store.by_artifact, item_id));
+        }
+        if let Some(kind) = &self.kind {
+            indexes.push(index_of(&store.by_kind, kind));
+        }
+        return indexes
+    }
+
+    /// Ids of the matching events, oldest first
+    pub(crate) fn ids(self) -> impl Iterator<Item = usize> + 'a {
+        let store = self.store;
+        let mut indexes = self.indexes();
+        indexes.sort_by_key(|ids| ids.len());
+
+        let candidates: Box<dyn Iterator<Item = usize> + 'a> = match indexes.first().copied() {
+            Some(shortest) => Box::new(shortest.iter().copied()),
+            None => {
+                let mut start = 0;
+                let mut end = store.len();
+                if store.chronological {
+                    if let Some(since) = &self.since {
+                        start = store.first_on_or_after(since);
+                    }
+                    if let Some(before) = &self.before {
+                        end = store.first_on_or_after(before).max(start);
+                    }
+                }
+                Box::new(start..end)
+            }
+        };
+        let since = self.since;
+        let before = self.before;
+        return candidates.filter(move |i| {
+            let date = store.events[*i].date();
+            if since.is_some_and(|since| *date < since) || before.is_some_and(|before| *date >= before) {
+                return false;
+            }
+            return indexes.iter().skip(1).all(|ids| ids.binary_search(i).is_ok())
+        })
+    }
+
+    /// The matching events with their ids, oldest first
+    pub(crate) fn iter(self) -> impl Iterator<Item = (usize, &'a Event)> + 'a {
+        let store = self.store;
+        return self.ids().map(move |i| (i, &store.events[i]))
+    }
+
+}
+
+#[cfg(test)]
+mod tests_event_store {
+    use crate::{commons::id_vec::Id, world::{creature::CauseOfDeath, date::Duration}};
+
+    use super::*;
+
+    fn store() -> EventStore {
+        let mut store = EventStore::new();
+        let a = CreatureId::mock(1);
+        let b = CreatureId::mock(2);
+        let site = SiteId::mock(1);
+        let item = ItemId::mock(1);
+        store.push(Event::CreatureBirth { date: WorldDate::new(1, 1, 1), creature_id: a });
+        store.push(Event::CreatureBirth { date: WorldDate::new(3, 1, 1), creature_id: b });
+        store.push(Event::ArtifactCreated { date: WorldDate::new(20, 1, 1), artifact: item, creator: a, site_id: site });
+        store.push(Event::InheritedArtifact { date: WorldDate::new(40, 1, 1), creature_id: b, from: a, item });
+        store.push(Event::CreatureDeath { date: WorldDate::new(40, 1, 1), creature_id: a, cause_of_death: CauseOfDeath::OldAge });
+        store.push(Event::Famine { date: WorldDate::new(60, 1, 1), site_id: site });
+        return store
+    }
+
+    fn ids(query: EventQuery<'_>) -> Vec<usize> {
+        return query.ids().collect()
+    }
+
+    #[test]
+    fn test_query_by_index() {
+        let store = store();
+        assert_eq!(store.by_creature(&CreatureId::mock(1)).map(|(i, _)| i).collect::<Vec<usize>>(), vec!(0, 2, 3, 4));
+        assert_eq!(store.by_artifact(&ItemId::mock(1)).map(|(i, _)| i).collect::<Vec<usize>>(), vec!(2, 3));
+        assert_eq!(store.by_kind(EventKind::Death).count(), 1);
+        assert_eq!(store.by_site(&SiteId::mock(2)).count(), 0);
+        assert_eq!(ids(store.query().creature(&CreatureId::mock(2)).artifact(&ItemId::mock(1))), vec!(3));
+    }
+
+    #[test]
+    fn test_query_by_date() {
+        let store = store();
+        let now = WorldDate::new(65, 1, 1);
+        assert_eq!(ids(store.query().since(now - Duration::years(50))), vec!(2, 3, 4, 5));
+        assert_eq!(store.between(WorldDate::new(3, 1, 1), WorldDate::new(40, 1, 1)).map(|(i, _)| i).collect::<Vec<usize>>(), vec!(1, 2));
+        assert_eq!(ids(store.query().site(&SiteId::mock(1)).since(WorldDate::new(30, 1, 1))), vec!(5));
+        assert_eq!(ids(store.query().since(WorldDate::new(70, 1, 1))), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_index_rebuilt_on_load() {
+        let store = store();
+        let saved = serde_json::to_string(&store).unwrap();
+        assert!(saved.starts_with('['));
+        let loaded: EventStore = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.len(), store.len());
+        assert_eq!(loaded.by_creature(&CreatureId::mock(1)).count(), 4);
+        assert_eq!(loaded.by_kind(EventKind::Birth).count(), 2);
+    }
+
+}
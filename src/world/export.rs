@@ -0,0 +1,363 @@
+use std::{fs::{self, File}, io::{BufWriter, Write}, path::{Path, PathBuf}};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{commons::id_vec::Id, resources::resources::Resources, world::{creature::CreatureId, item::ItemId, lineage::LineageId, metrics::WorldMetrics, site::SiteId, world::World, writer::Writer}};
+
+/// File formats the history can be exported to, for analysis outside the game
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ExportFormat {
+    /// One JSON object per line
+    JsonLines,
+    /// Comma separated values, with a header. Lists are joined with ';'
+    Csv,
+}
+
+impl ExportFormat {
+
+    pub(crate) const ALL: [ExportFormat; 2] = [ExportFormat::JsonLines, ExportFormat::Csv];
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "jsonl" => Ok(ExportFormat::JsonLines),
+            "csv" => Ok(ExportFormat::Csv),
+            other => Err(format!("Unknown export format {other}, expected jsonl or csv")),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+}
+
+#[derive(Serialize)]
+struct EventRecord {
+    id: usize,
+    date: String,
+    year: i32,
+    kind: String,
+    event_type: String,
+    summary: String,
+    text: String,
+    creatures: Vec<usize>,
+    sites: Vec<usize>,
+    artifacts: Vec<usize>,
+    lineages: Vec<usize>,
+    /// The event exactly as it's saved
+    details: Value,
+}
+
+#[derive(Serialize)]
+struct CreatureRecord {
+    id: usize,
+    name: String,
+    species: String,
+    gender: String,
+    profession: String,
+    birth: String,
+    birth_year: i32,
+    death: Option<String>,
+    death_year: Option<i32>,
+    cause_of_death: Option<String>,
+    father: Option<usize>,
+    mother: Option<usize>,
+    spouse: Option<usize>,
+    children: Vec<usize>,
+    lineage: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct SiteRecord {
+    id: usize,
+    name: String,
+    site_type: String,
+    x: i32,
+    y: i32,
+    culture: Option<String>,
+    leader: Option<usize>,
+    population: usize,
+    peak_population: u32,
+    peak_year: i32,
+}
+
+#[derive(Serialize)]
+struct LineageRecord {
+    id: usize,
+    name: String,
+    culture: String,
+    noble: bool,
+    head: Option<usize>,
+    founder: Option<usize>,
+    heraldry: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ArtifactRecord {
+    id: usize,
+    name: String,
+    owner: Option<usize>,
+    description: String,
+}
+
+/// Writes the events, creatures, sites, lineages, artifacts and statistics of the world to a file each, inside the directory.
+/// Returns the files written.
+pub(crate) fn export_world(world: &World, resources: &Resources, directory: &Path, format: ExportFormat) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(directory).map_err(|err| format!("Can't create {}: {err}", directory.display()))?;
+    let mut files = Vec::new();
+    files.push(write_records(directory, "events", format, &event_records(world, resources))?);
+    files.push(write_records(directory, "creatures", format, &creature_records(world, resources))?);
+    files.push(write_records(directory, "sites", format, &site_records(world, resources))?);
+    files.push(write_records(directory, "lineages", format, &lineage_records(world, resources))?);
+    files.push(write_records(directory, "artifacts", format, &artifact_records(world, resources))?);
+    files.push(write_metrics(directory, format, &world.metrics)?);
+    return Ok(files);
+}
+
+/// The statistics as a table, with a row per sample and a column per statistic
+fn write_metrics(directory: &Path, format: ExportFormat, metrics: &WorldMetrics) -> Result<PathBuf, String> {
+    let path = directory.join(format!("metrics.{}", format.extension()));
+    let file = File::create(&path).map_err(|err| format!("Can't create {}: {err}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    let series = metrics.series();
+    let years: Vec<i32> = metrics.samples.iter().map(|sample| sample.date.year()).collect();
+    let result = match format {
+        ExportFormat::JsonLines => {
+            let mut rows = Vec::new();
+            for (i, year) in years.iter().enumerate() {
+                let mut row = Map::new();
+                row.insert(String::from("year"), Value::from(*year));
+                for metric in series.iter() {
+                    row.insert(metric.key.clone(), Value::from(metric.points[i].1));
+                }
+                rows.push(row);
+            }
+            write_json_lines(&mut out, &rows)
+        },
+        ExportFormat::Csv => {
+            let mut lines = vec!(std::iter::once(String::from("year")).chain(series.iter().map(|metric| csv_escape(&metric.key))).collect::<Vec<String>>().join(","));
+            for (i, year) in years.iter().enumerate() {
+                let cells: Vec<String> = series.iter().map(|metric| metric.points[i].1.to_string()).collect();
+                lines.push(format!("{year},{}", cells.join(",")));
+            }
+            lines.iter().try_for_each(|line| writeln!(out, "{line}").map_err(|err| err.to_string()))
+        },
+    };
+    result.and_then(|_| out.flush().map_err(|err| err.to_string())).map_err(|err| format!("Can't write {}: {err}", path.display()))?;
+    return Ok(path);
+}
+
+fn event_records(world: &World, resources: &Resources) -> Vec<EventRecord> {
+    let mut records = Vec::new();
+    for (id, event) in world.events.iter().enumerate() {
+        // Events are saved externally tagged, as { "Variant": { ...fields } }
+        let (event_type, details) = match serde_json::to_value(event) {
+            Ok(Value::Object(map)) => map.into_iter().next().unwrap_or((String::new(), Value::Null)),
+            _ => (String::new(), Value::Null),
+        };
+        records.push(EventRecord {
+            id,
+            date: world.date_desc(event.date()),
+            year: event.date().year(),
+            kind: format!("{:?}", event.kind()),
+            event_type,
+            summary: event.short_text(resources, world),
+            text: event.event_text(resources, world),
+            creatures: event.related_creatures().iter().map(|id| id.as_usize()).collect(),
+            sites: event.related_sites().iter().map(|id| id.as_usize()).collect(),
+            artifacts: event.related_artifacts().iter().map(|id| id.as_usize()).collect(),
+            lineages: event.related_lineages(world).iter().map(|id| id.as_usize()).collect(),
+            details,
+        });
+    }
+    return records;
+}
+
+fn creature_records(world: &World, resources: &Resources) -> Vec<CreatureRecord> {
+    let parent = |id: CreatureId| if id == CreatureId::ancients() { None } else { Some(id.as_usize()) };
+    let mut records = Vec::new();
+    for (id, creature) in world.creatures.iter_id_val::<CreatureId>() {
+        let creature = creature.borrow();
+        records.push(CreatureRecord {
+            id: id.as_usize(),
+            name: creature.name(&id, world, resources),
+            species: resources.species.get(&creature.species).name.clone(),
+            gender: format!("{:?}", creature.gender),
+            profession: format!("{:?}", creature.profession),
+            birth: world.date_desc(&creature.birth),
+            birth_year: creature.birth.year(),
+            death: creature.death.map(|(date, _)| world.date_desc(&date)),
+            death_year: creature.death.map(|(date, _)| date.year()),
+            cause_of_death: creature.death.map(|(_, cause)| format!("{:?}", cause)),
+            father: parent(creature.father),
+            mother: parent(creature.mother),
+            spouse: creature.spouse.map(|id| id.as_usize()),
+            children: creature.offspring.iter().map(|id| id.as_usize()).collect(),
+            lineage: creature.lineage.map(|id| id.as_usize()),
+        });
+    }
+    return records;
+}
+
+fn site_records(world: &World, resources: &Resources) -> Vec<SiteRecord> {
+    let mut records = Vec::new();
+    for (id, site) in world.sites.iter_id_val::<SiteId>() {
+        let site = site.borrow();
+        records.push(SiteRecord {
+            id: id.as_usize(),
+            name: String::from(site.name()),
+            site_type: format!("{:?}", site.site_type),
+            x: site.xy.x(),
+            y: site.xy.y(),
+            culture: site.culture().map(|culture| resources.cultures.get(&culture).name.clone()),
+            leader: site.settlement.as_ref().and_then(|settlement| settlement.leader).map(|id| id.as_usize()),
+            population: site.creatures.len(),
+            peak_population: site.population_peak.1,
+            peak_year: site.population_peak.0,
+        });
+    }
+    return records;
+}
+
+fn lineage_records(world: &World, resources: &Resources) -> Vec<LineageRecord> {
+    let mut records = Vec::new();
+    for (id, lineage) in world.lineages.iter_id_val::<LineageId>() {
+        let lineage = lineage.borrow();
+        records.push(LineageRecord {
+            id: id.as_usize(),
+            name: lineage.name.clone(),
+            culture: resources.cultures.get(&lineage.culture).name.clone(),
+            noble: lineage.is_noble(),
+            head: lineage.head.map(|id| id.as_usize()),
+            founder: lineage.founder.map(|id| id.as_usize()),
+            heraldry: lineage.heraldry.as_ref().map(|heraldry| heraldry.description()),
+        });
+    }
+    return records;
+}
+
+fn artifact_records(world: &World, resources: &Resources) -> Vec<ArtifactRecord> {
+    let mut records = Vec::new();
+    for (id, item) in world.artifacts.iter_id_val::<ItemId>() {
+        let item = item.borrow();
+        let mut writer = Writer::new(world, resources);
+        writer.describe_item(&item);
+        records.push(ArtifactRecord {
+            id: id.as_usize(),
+            name: item.name(&resources.materials),
+            owner: item.owner.map(|id| id.as_usize()),
+            description: writer.take_text(),
+        });
+    }
+    return records;
+}
+
+fn write_records<R: Serialize>(directory: &Path, name: &str, format: ExportFormat, records: &[R]) -> Result<PathBuf, String> {
+    let path = directory.join(format!("{name}.{}", format.extension()));
+    let file = File::create(&path).map_err(|err| format!("Can't create {}: {err}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    let result = match format {
+        ExportFormat::JsonLines => write_json_lines(&mut out, records),
+        ExportFormat::Csv => write_csv(&mut out, records),
+    };
+    result.and_then(|_| out.flush().map_err(|err| err.to_string())).map_err(|err| format!("Can't write {}: {err}", path.display()))?;
+    return Ok(path);
+}
+
+fn write_json_lines<W: Write, R: Serialize>(out: &mut W, records: &[R]) -> Result<(), String> {
+    for record in records {
+        serde_json::to_writer(&mut *out, record).map_err(|err| err.to_string())?;
+        writeln!(out).map_err(|err| err.to_string())?;
+    }
+    return Ok(());
+}
+
+fn write_csv<W: Write, R: Serialize>(out: &mut W, records: &[R]) -> Result<(), String> {
+    let mut rows = Vec::new();
+    for record in records {
+        match serde_json::to_value(record).map_err(|err| err.to_string())? {
+            Value::Object(row) => rows.push(row),
+            _ => return Err(String::from("Records must be structs")),
+        }
+    }
+    let first = match rows.first() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    // The id goes first, so the file reads like a table
+    let mut columns: Vec<String> = first.keys().filter(|key| *key != "id").cloned().collect();
+    columns.insert(0, String::from("id"));
+
+    let header: Vec<String> = columns.iter().map(|column| csv_escape(column)).collect();
+    writeln!(out, "{}", header.join(",")).map_err(|err| err.to_string())?;
+    for row in rows.iter() {
+        let cells: Vec<String> = columns.iter().map(|column| csv_escape(&csv_cell(row.get(column).unwrap_or(&Value::Null)))).collect();
+        writeln!(out, "{}", cells.join(",")).map_err(|err| err.to_string())?;
+    }
+    return Ok(());
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        Value::Array(values) => values.iter().map(csv_cell).collect::<Vec<String>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", cell.replace('"', "\"\""));
+    }
+    return String::from(cell);
+}
+
+#[cfg(test)]
+mod tests_export {
+    use crate::world::{event::Event, world::fixture::WorldFixture};
+
+    use super::*;
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("Aldric"), "Aldric");
+        assert_eq!(csv_escape("Aldric, the bold"), "\"Aldric, the bold\"");
+        assert_eq!(csv_escape("the \"bold\""), "\"the \"\"bold\"\"\"");
+        assert_eq!(csv_cell(&serde_json::json!([1, 2, 3])), "1;2;3");
+    }
+
+    #[test]
+    fn test_export_world() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        fixture.world.events.push(Event::CreatureBirth { date: fixture.world.date, creature_id: fixture.creature_a1 });
+
+        let directory = std::env::temp_dir().join("kathay_test_export_world");
+        for format in ExportFormat::ALL {
+            let files = export_world(&fixture.world, &resources, &directory, format).unwrap();
+            assert_eq!(files.len(), 6);
+        }
+
+        let events = fs::read_to_string(directory.join("events.csv")).unwrap();
+        let mut lines = events.lines();
+        assert!(lines.next().unwrap().starts_with("id,"));
+        assert!(lines.next().unwrap().starts_with("0,"));
+        assert_eq!(lines.next(), None);
+
+        let creatures = fs::read_to_string(directory.join("creatures.jsonl")).unwrap();
+        assert_eq!(creatures.lines().count(), fixture.world.creatures.len());
+        let first: Value = serde_json::from_str(creatures.lines().next().unwrap()).unwrap();
+        assert_eq!(first["id"], 0);
+        assert!(first["name"].is_string());
+
+        let metrics = fs::read_to_string(directory.join("metrics.csv")).unwrap();
+        assert!(metrics.starts_with("year,population,"));
+    }
+
+}
@@ -0,0 +1,327 @@
+use std::{collections::{BTreeMap, BTreeSet}, fs, path::{Path, PathBuf}};
+
+use crate::{commons::id_vec::Id, resources::resources::Resources, world::{creature::{CauseOfDeath, CreatureGender, CreatureId}, date::WorldDate, lineage::LineageId, world::World}};
+
+/// Generations above and below a creature included in their family tree
+const GENERATIONS: usize = 3;
+/// GEDCOM only knows the gregorian months, so the twelve months of the world map to them in order
+const GEDCOM_MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+/// Whose family tree to export
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GenealogyRoot {
+    /// Everyone born into the lineage, and whoever married into it
+    Lineage(LineageId),
+    /// The ancestors and descendants of a creature, a few generations each way
+    Creature(CreatureId),
+}
+
+impl GenealogyRoot {
+
+    /// Parses `lineage:<id>` or `creature:<id>`
+    pub(crate) fn parse(value: &str, world: &World) -> Result<Self, String> {
+        let (kind, id) = value.split_once(':').ok_or(format!("Expected lineage:<id> or creature:<id>, got {value}"))?;
+        let id: usize = id.parse().map_err(|_| format!("Invalid id {id}"))?;
+        match kind {
+            "lineage" if id < world.lineages.len() => Ok(GenealogyRoot::Lineage(LineageId::new(id))),
+            "creature" if id < world.creatures.len() => Ok(GenealogyRoot::Creature(CreatureId::new(id))),
+            "lineage" | "creature" => Err(format!("No {kind} with id {id}")),
+            other => Err(format!("Expected lineage or creature, got {other}")),
+        }
+    }
+
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GenealogyFormat {
+    /// Graphviz graph, to render with `dot -Tsvg`
+    Dot,
+    /// GEDCOM 5.5.1, to open in genealogy software
+    Gedcom,
+}
+
+impl GenealogyFormat {
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "dot" => Ok(GenealogyFormat::Dot),
+            "gedcom" | "ged" => Ok(GenealogyFormat::Gedcom),
+            other => Err(format!("Unknown genealogy format {other}, expected dot or gedcom")),
+        }
+    }
+
+    pub(crate) const ALL: [GenealogyFormat; 2] = [GenealogyFormat::Dot, GenealogyFormat::Gedcom];
+
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            GenealogyFormat::Dot => "dot",
+            GenealogyFormat::Gedcom => "ged",
+        }
+    }
+
+}
+
+/// A couple and the children they had together. Either parent might be unknown
+struct Family {
+    father: Option<CreatureId>,
+    mother: Option<CreatureId>,
+    children: Vec<CreatureId>,
+}
+
+/// The people in a family tree, and the families that link them
+struct Genealogy {
+    title: String,
+    people: BTreeSet<CreatureId>,
+    families: Vec<Family>,
+}
+
+impl Genealogy {
+
+    fn new(world: &World, root: GenealogyRoot) -> Self {
+        let mut people = BTreeSet::new();
+        let title = match root {
+            GenealogyRoot::Lineage(lineage_id) => {
+                for (id, creature) in world.creatures.iter_id_val::<CreatureId>() {
+                    if creature.borrow().lineage == Some(lineage_id) {
+                        people.insert(id);
+                    }
+                }
+                let lineage = world.lineages.get(&lineage_id);
+                match lineage.is_noble() {
+                    true => format!("House {}", lineage.name),
+                    false => format!("The {} family", lineage.name),
+                }
+            },
+            GenealogyRoot::Creature(creature_id) => {
+                add_ancestors(world, creature_id, GENERATIONS, &mut people);
+                add_descendants(world, creature_id, GENERATIONS, &mut people);
+                format!("Family of creature {}", creature_id.as_usize())
+            },
+        };
+
+        // Spouses marry into the tree
+        let spouses: Vec<CreatureId> = people.iter().filter_map(|id| world.creatures.get(id).spouse).collect();
+        people.extend(spouses);
+
+        let mut families: BTreeMap<(Option<CreatureId>, Option<CreatureId>), Vec<CreatureId>> = BTreeMap::new();
+        for id in people.iter() {
+            let creature = world.creatures.get(id);
+            let father = Some(creature.father).filter(|father| people.contains(father));
+            let mother = Some(creature.mother).filter(|mother| people.contains(mother));
+            if father.is_some() || mother.is_some() {
+                families.entry((father, mother)).or_default().push(*id);
+            }
+            // Couples that never had children are still a family
+            if let Some(spouse_id) = creature.spouse {
+                let couple = match creature.gender {
+                    CreatureGender::Male => (Some(*id), Some(spouse_id)),
+                    CreatureGender::Female => (Some(spouse_id), Some(*id)),
+                };
+                families.entry(couple).or_default();
+            }
+        }
+        let families = families.into_iter().map(|((father, mother), children)| Family { father, mother, children }).collect();
+
+        return Self { title, people, families };
+    }
+
+    fn to_dot(&self, world: &World, resources: &Resources) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", dot_escape(&self.title));
+        dot.push_str("    rankdir=TB;\n");
+        dot.push_str("    node [fontname=\"Helvetica\", fontsize=10];\n");
+        for id in self.people.iter() {
+            let creature = world.creatures.get(id);
+            let mut label = creature.name(id, world, resources);
+            label.push_str(&format!("\n* {}", world.date_desc(&creature.birth)));
+            if let Some((date, cause)) = &creature.death {
+                label.push_str(&format!("\n+ {} ({})", world.date_desc(date), cause_of_death(world, resources, cause)));
+            }
+            let shape = match creature.gender {
+                CreatureGender::Male => "box",
+                CreatureGender::Female => "ellipse",
+            };
+            let style = match creature.death {
+                Some(_) => "dashed",
+                None => "solid",
+            };
+            dot.push_str(&format!("    c{} [label=\"{}\", shape={shape}, style={style}];\n", id.as_usize(), dot_escape(&label)));
+        }
+        for (i, family) in self.families.iter().enumerate() {
+            dot.push_str(&format!("    f{i} [shape=point];\n"));
+            for parent in [family.father, family.mother].iter().flatten() {
+                dot.push_str(&format!("    c{} -> f{i} [dir=none];\n", parent.as_usize()));
+            }
+            for child in family.children.iter() {
+                dot.push_str(&format!("    f{i} -> c{};\n", child.as_usize()));
+            }
+        }
+        dot.push_str("}\n");
+        return dot;
+    }
+
+    fn to_gedcom(&self, world: &World, resources: &Resources) -> String {
+        let mut lines = vec!(
+            String::from("0 HEAD"),
+            String::from("1 SOUR TALES_OF_KATHAY"),
+            String::from("1 GEDC"),
+            String::from("2 VERS 5.5.1"),
+            String::from("2 FORM LINEAGE-LINKED"),
+            String::from("1 CHAR UTF-8"),
+            format!("1 NOTE {}", self.title),
+        );
+        for id in self.people.iter() {
+            let creature = world.creatures.get(id);
+            lines.push(format!("0 @I{}@ INDI", id.as_usize()));
+            let name = creature.name(id, world, resources);
+            let name = match (creature.lineage, name.rsplit_once(' ')) {
+                (Some(_), Some((given, surname))) => format!("{given} /{surname}/"),
+                _ => name.clone(),
+            };
+            lines.push(format!("1 NAME {name}"));
+            lines.push(format!("1 SEX {}", match creature.gender {
+                CreatureGender::Male => "M",
+                CreatureGender::Female => "F",
+            }));
+            lines.push(String::from("1 BIRT"));
+            lines.push(format!("2 DATE {}", gedcom_date(&creature.birth)));
+            if let Some((date, cause)) = &creature.death {
+                lines.push(String::from("1 DEAT"));
+                lines.push(format!("2 DATE {}", gedcom_date(date)));
+                lines.push(format!("2 CAUS {}", cause_of_death(world, resources, cause)));
+            }
+            for (i, family) in self.families.iter().enumerate() {
+                if family.children.contains(id) {
+                    lines.push(format!("1 FAMC @F{i}@"));
+                }
+                if family.father == Some(*id) || family.mother == Some(*id) {
+                    lines.push(format!("1 FAMS @F{i}@"));
+                }
+            }
+        }
+        for (i, family) in self.families.iter().enumerate() {
+            lines.push(format!("0 @F{i}@ FAM"));
+            if let Some(father) = family.father {
+                lines.push(format!("1 HUSB @I{}@", father.as_usize()));
+            }
+            if let Some(mother) = family.mother {
+                lines.push(format!("1 WIFE @I{}@", mother.as_usize()));
+            }
+            for child in family.children.iter() {
+                lines.push(format!("1 CHIL @I{}@", child.as_usize()));
+            }
+        }
+        lines.push(String::from("0 TRLR"));
+        return lines.join("\n") + "\n";
+    }
+
+}
+
+/// Writes the family tree of a lineage or creature to a file in the directory, named after the root.
+/// Returns the file written.
+pub(crate) fn export_genealogy(world: &World, resources: &Resources, root: GenealogyRoot, format: GenealogyFormat, directory: &Path) -> Result<PathBuf, String> {
+    let genealogy = Genealogy::new(world, root);
+    let contents = match format {
+        GenealogyFormat::Dot => genealogy.to_dot(world, resources),
+        GenealogyFormat::Gedcom => genealogy.to_gedcom(world, resources),
+    };
+    let name = match root {
+        GenealogyRoot::Lineage(lineage_id) => format!("genealogy_lineage_{}", lineage_id.as_usize()),
+        GenealogyRoot::Creature(creature_id) => format!("genealogy_creature_{}", creature_id.as_usize()),
+    };
+    fs::create_dir_all(directory).map_err(|err| format!("Can't create {}: {err}", directory.display()))?;
+    let path = directory.join(format!("{name}.{}", format.extension()));
+    fs::write(&path, contents).map_err(|err| format!("Can't write {}: {err}", path.display()))?;
+    return Ok(path);
+}
+
+fn add_ancestors(world: &World, creature_id: CreatureId, generations: usize, people: &mut BTreeSet<CreatureId>) {
+    if creature_id == CreatureId::ancients() {
+        return;
+    }
+    people.insert(creature_id);
+    if generations == 0 {
+        return;
+    }
+    let (father, mother) = {
+        let creature = world.creatures.get(&creature_id);
+        (creature.father, creature.mother)
+    };
+    add_ancestors(world, father, generations - 1, people);
+    add_ancestors(world, mother, generations - 1, people);
+}
+
+fn add_descendants(world: &World, creature_id: CreatureId, generations: usize, people: &mut BTreeSet<CreatureId>) {
+    people.insert(creature_id);
+    if generations == 0 {
+        return;
+    }
+    let offspring = world.creatures.get(&creature_id).offspring.clone();
+    for child_id in offspring {
+        add_descendants(world, child_id, generations - 1, people);
+    }
+}
+
+fn cause_of_death(world: &World, resources: &Resources, cause: &CauseOfDeath) -> String {
+    match cause {
+        CauseOfDeath::OldAge => String::from("Old age"),
+        CauseOfDeath::Disease => String::from("Disease"),
+        CauseOfDeath::Plague(epidemic_id) => format!("Plague ({})", world.epidemics.get(epidemic_id).name),
+        CauseOfDeath::KilledInBattle(killer_id, _) => format!("Killed by {}", world.creature_desc(killer_id, resources)),
+    }
+}
+
+/// GEDCOM years have at least 3 digits
+/// Months count from 0, which is December like in `WorldDate::fmt_long`, and days from 0
+fn gedcom_date(date: &WorldDate) -> String {
+    return format!("{} {} {:03}", date.day() + 1, GEDCOM_MONTHS[((date.month() + 11) % 12) as usize], date.year());
+}
+
+fn dot_escape(text: &str) -> String {
+    return text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+}
+
+#[cfg(test)]
+mod tests_genealogy {
+    use crate::world::world::fixture::WorldFixture;
+
+    use super::*;
+
+    #[test]
+    fn test_family_links_parents_and_children() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let (father, mother, child) = (fixture.creature_a1, fixture.creature_a2, fixture.creature_a3);
+        {
+            let world = &mut fixture.world;
+            world.creatures.get_mut(&father).spouse = Some(mother);
+            world.creatures.get_mut(&mother).spouse = Some(father);
+            world.creatures.get_mut(&father).offspring = vec!(child);
+            world.creatures.get_mut(&mother).offspring = vec!(child);
+            world.creatures.get_mut(&child).father = father;
+            world.creatures.get_mut(&child).mother = mother;
+        }
+
+        let genealogy = Genealogy::new(&fixture.world, GenealogyRoot::Creature(child));
+        assert_eq!(genealogy.people.len(), 3);
+        assert_eq!(genealogy.families.len(), 1);
+        assert_eq!(genealogy.families[0].father, Some(father));
+        assert_eq!(genealogy.families[0].mother, Some(mother));
+        assert_eq!(genealogy.families[0].children, vec!(child));
+
+        let gedcom = genealogy.to_gedcom(&fixture.world, &resources);
+        assert!(gedcom.contains(&format!("0 @F0@ FAM\n1 HUSB @I{}@\n1 WIFE @I{}@\n1 CHIL @I{}@", father.as_usize(), mother.as_usize(), child.as_usize())));
+        assert!(gedcom.ends_with("0 TRLR\n"));
+
+        let dot = genealogy.to_dot(&fixture.world, &resources);
+        assert!(dot.contains(&format!("f0 -> c{};", child.as_usize())));
+    }
+
+    #[test]
+    fn test_gedcom_date() {
+        assert_eq!(gedcom_date(&WorldDate::new(7, 3, 12)), "13 MAR 007");
+        assert_eq!(gedcom_date(&WorldDate::new(1250, 11, 1)), "2 NOV 1250");
+        assert_eq!(gedcom_date(&WorldDate::new(30, 0, 0)), "1 DEC 030");
+    }
+
+}
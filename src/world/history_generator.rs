@@ -8,7 +8,7 @@ use crate::{commons::rng::Rng as OldRng, engine::geometry::Size2D, info, resourc
 use super::world::World;
 
 #[derive(Clone, Serialize, Deserialize)]
-
+#[serde(default)]
 pub(crate) struct WorldGenerationParameters {
     pub(crate) seed: u64,
     // Terain
@@ -21,6 +21,8 @@ pub(crate) struct WorldGenerationParameters {
     pub(crate) number_of_seed_cities: u16,
     pub(crate) seed_cities_population: u32,
     // Storyteller settings
+    /// Storyteller profile, the name of a file in assets/storytellers
+    pub(crate) storyteller: String,
     /// Storyteller strength, from 0. to 1.
     pub(crate) st_strength: f32,
     /// Target number of cities
@@ -33,19 +35,109 @@ pub(crate) struct WorldGenerationParameters {
     pub(crate) st_village_population: u16,
 }
 
+impl Default for WorldGenerationParameters {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            world_size: Size2D(64, 48),
+            num_plate_tectonics: 5,
+            history_length: 2000,
+            number_of_seed_cities: 3,
+            seed_cities_population: 15,
+            storyteller: String::from("balanced"),
+            st_strength: 1.0,
+            st_city_count: 7,
+            st_city_population: 20,
+            st_village_count: 20,
+            st_village_population: 10,
+        }
+    }
+}
+
 impl WorldGenerationParameters {
 
     pub(crate) fn rng(&self) -> Rng {
         Rng::new(self.seed)
     }
 
+    /// Checks that a world can be generated with these parameters
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(16..=256).contains(&self.world_size.x()) || !(16..=256).contains(&self.world_size.y()) {
+            return Err(String::from("The world size must be between 16 and 256 chunks"));
+        }
+        if !(1..=32).contains(&self.num_plate_tectonics) {
+            return Err(String::from("There must be between 1 and 32 plates"));
+        }
+        if !(1..=10000).contains(&self.history_length) {
+            return Err(String::from("The history must last between 1 and 10000 years"));
+        }
+        if !(1..=100).contains(&self.number_of_seed_cities) {
+            return Err(String::from("There must be between 1 and 100 seed cities"));
+        }
+        if !(0. ..=1.).contains(&self.st_strength) {
+            return Err(String::from("The storyteller strength must be between 0 and 1"));
+        }
+        if self.st_village_count == 0 || self.st_city_population == 0 || self.st_village_population == 0 {
+            return Err(String::from("The storyteller targets must be above 0"));
+        }
+        return Ok(())
+    }
+
+}
+
+/// Where the generation is at. Sent to the observers after every step
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct GenerationProgress {
+    pub(crate) year: i32,
+    /// Year the generation stops at
+    pub(crate) target_year: i32,
+    pub(crate) population: usize,
+    pub(crate) sites: usize,
+    /// Events recorded in the last year
+    pub(crate) events_per_year: usize,
+    pub(crate) state: GenerationState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GenerationState {
+    Running,
+    Paused,
+    Cancelled,
+    /// Reached the target year, or everyone died
+    Finished,
+}
+
+impl GenerationState {
+
+    /// Whether the generator won't step anymore
+    pub(crate) fn is_done(&self) -> bool {
+        return *self == GenerationState::Cancelled || *self == GenerationState::Finished
+    }
+
+}
+
+/// Something that follows the generation, like a progress bar or a log
+pub(crate) trait GenerationObserver {
+    fn on_progress(&mut self, progress: &GenerationProgress);
+}
+
+impl<F> GenerationObserver for F where F: FnMut(&GenerationProgress) {
+    fn on_progress(&mut self, progress: &GenerationProgress) {
+        self(progress)
+    }
 }
 
 pub(crate) struct WorldHistoryGenerator {
     pub(crate) world: World,
     pub(crate) parameters: WorldGenerationParameters,
     history_sim: HistorySimulation,
-    pub(crate) stop: bool
+    /// Everyone died
+    stop: bool,
+    paused: bool,
+    cancelled: bool,
+    /// Stops before the history length, so the world can be saved and resumed later
+    stop_at_year: Option<i32>,
+    observers: Vec<Box<dyn GenerationObserver>>,
 }
 
 impl WorldHistoryGenerator {
@@ -60,6 +152,9 @@ impl WorldHistoryGenerator {
         let now: Instant = Instant::now();
         world_map.precipitation(&mut rng);
         info!("Precipitation {:.2?}", now.elapsed());
+        let now: Instant = Instant::now();
+        world_map.hydrology(&mut rng);
+        info!("Hydrology {:.2?}", now.elapsed());
         // let now: Instant = Instant::now();
         // world_map.erosion(&mut params);
         // info!("Erosion {:.2?}", now.elapsed());
@@ -76,6 +171,10 @@ impl WorldHistoryGenerator {
             history_sim,
             world,
             stop: false,
+            paused: false,
+            cancelled: false,
+            stop_at_year: None,
+            observers: Vec::new(),
         };
 
         return generator;
@@ -86,13 +185,18 @@ impl WorldHistoryGenerator {
 
         let rng = OldRng::seeded(parameters.seed);
 
-        let history_sim = HistorySimulation::new(rng.derive("history"), parameters.clone());
+        let mut history_sim = HistorySimulation::new(rng.derive("history"), parameters.clone());
+        history_sim.restore(&world);
 
         let generator = WorldHistoryGenerator {
             parameters,
             history_sim,
             world,
             stop: false,
+            paused: false,
+            cancelled: false,
+            stop_at_year: None,
+            observers: Vec::new(),
         };
 
         return generator;
@@ -102,4 +206,237 @@ impl WorldHistoryGenerator {
         self.stop = !self.history_sim.simulate_step(step, &mut self.world);
     }
 
+    /// Simulates a step, unless the generation is paused or done, and tells the observers.
+    /// Returns the state after the step
+    pub(crate) fn advance(&mut self, step: Duration) -> GenerationState {
+        if self.state() != GenerationState::Running {
+            return self.state();
+        }
+        self.simulate_step(step);
+        let progress = self.progress();
+        for observer in self.observers.iter_mut() {
+            observer.on_progress(&progress);
+        }
+        return progress.state;
+    }
+
+    /// Advances until the generation is paused or done, or the time runs out
+    pub(crate) fn run(&mut self, step: Duration, max_time: Option<std::time::Duration>) -> GenerationState {
+        let start = Instant::now();
+        loop {
+            let state = self.advance(step);
+            if state != GenerationState::Running {
+                return state;
+            }
+            if max_time.is_some_and(|max_time| start.elapsed() >= max_time) {
+                return state;
+            }
+        }
+    }
+
+    pub(crate) fn observe(&mut self, observer: impl GenerationObserver + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub(crate) fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Stops for good. The world is kept as it is, and can still be saved and resumed
+    pub(crate) fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    /// Finishes at the year instead of the end of the history
+    pub(crate) fn stop_at_year(&mut self, year: i32) {
+        self.stop_at_year = Some(year);
+    }
+
+    pub(crate) fn target_year(&self) -> i32 {
+        let history_end = self.parameters.history_length as i32;
+        return self.stop_at_year.map(|year| year.min(history_end)).unwrap_or(history_end)
+    }
+
+    pub(crate) fn state(&self) -> GenerationState {
+        if self.cancelled {
+            return GenerationState::Cancelled;
+        }
+        if self.stop || self.world.date.year() >= self.target_year() {
+            return GenerationState::Finished;
+        }
+        if self.paused {
+            return GenerationState::Paused;
+        }
+        return GenerationState::Running;
+    }
+
+    pub(crate) fn progress(&self) -> GenerationProgress {
+        let world = &self.world;
+        let population = world.sites.iter().map(|site| site.borrow().creatures.len()).sum();
+        return GenerationProgress {
+            year: world.date.year(),
+            target_year: self.target_year(),
+            population,
+            sites: world.sites.len(),
+            events_per_year: world.events.query().since(world.date - Duration::years(1)).ids().count(),
+            state: self.state(),
+        }
+    }
+
+    /// Sets how many threads the sites are simulated with. The history is the same regardless
+    pub(crate) fn set_threads(&mut self, threads: usize) {
+        self.history_sim.set_threads(threads);
+    }
+
+    /// Simulates the sites one after the other, as before they were simulated in parallel
+    pub(crate) fn set_sequential(&mut self, sequential: bool) {
+        self.history_sim.set_sequential(sequential);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    use crate::{resources::resources::{resources, resources_mut}, world::date::WorldDate};
+
+    use super::*;
+
+    fn generate_world_hash(seed: u64) -> u64 {
+        return generate_world_hash_with_threads(seed, 1);
+    }
+
+    fn generate_world_hash_with_threads(seed: u64, threads: usize) -> u64 {
+        return generate_world_hash_with(seed, threads, false);
+    }
+
+    fn generate_world_hash_with(seed: u64, threads: usize, sequential: bool) -> u64 {
+        let parameters = WorldGenerationParameters {
+            seed,
+            world_size: Size2D(32, 32),
+            history_length: 100,
+            ..Default::default()
+        };
+        let mut resources = Resources::new();
+        resources.load();
+
+        let mut generator = WorldHistoryGenerator::seed_world(parameters, &resources);
+        generator.set_threads(threads);
+        generator.set_sequential(sequential);
+        generator.run(Duration::months(3), None);
+        return hash_world(&generator.world);
+    }
+
+    fn hash_world(world: &World) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        save_world(world).hash(&mut hasher);
+        return hasher.finish();
+    }
+
+    fn save_world(world: &World) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        ciborium::into_writer(world, &mut buffer).unwrap();
+        return buffer;
+    }
+
+    #[test]
+    fn check_determinism() {
+        if resources().species.try_id_of("species:human").is_none() {
+            resources_mut().load();
+        }
+        assert_eq!(generate_world_hash(1234), generate_world_hash(1234));
+    }
+
+    #[test]
+    fn check_determinism_across_threads() {
+        if resources().species.try_id_of("species:human").is_none() {
+            resources_mut().load();
+        }
+        assert_eq!(generate_world_hash_with_threads(1234, 1), generate_world_hash_with_threads(1234, 4));
+    }
+
+    #[test]
+    fn check_determinism_sequential() {
+        if resources().species.try_id_of("species:human").is_none() {
+            resources_mut().load();
+        }
+        assert_eq!(generate_world_hash_with(1234, 1, true), generate_world_hash_with(1234, 1, true));
+    }
+
+    #[test]
+    fn check_determinism_when_resumed() {
+        if resources().species.try_id_of("species:human").is_none() {
+            resources_mut().load();
+        }
+        let parameters = WorldGenerationParameters {
+            seed: 1234,
+            world_size: Size2D(32, 32),
+            history_length: 100,
+            ..Default::default()
+        };
+        let mut resources = Resources::new();
+        resources.load();
+
+        let mut generator = WorldHistoryGenerator::seed_world(parameters.clone(), &resources);
+        generator.stop_at_year(40);
+        generator.run(Duration::months(3), None);
+        let single_run = hash_world(&generator.world);
+
+        let mut generator = WorldHistoryGenerator::seed_world(parameters, &resources);
+        generator.stop_at_year(20);
+        generator.run(Duration::months(3), None);
+        let world: World = ciborium::from_reader(save_world(&generator.world).as_slice()).unwrap();
+        let mut generator = WorldHistoryGenerator::simulator(world);
+        generator.stop_at_year(40);
+        generator.run(Duration::months(3), None);
+        assert_eq!(generator.world.date.year(), 40);
+        assert_eq!(hash_world(&generator.world), single_run);
+    }
+
+    #[test]
+    fn test_pause_cancel_and_stop_at_year() {
+        if resources().species.try_id_of("species:human").is_none() {
+            resources_mut().load();
+        }
+        let parameters = WorldGenerationParameters {
+            seed: 1234,
+            world_size: Size2D(32, 32),
+            history_length: 100,
+            ..Default::default()
+        };
+        let mut resources = Resources::new();
+        resources.load();
+
+        let mut generator = WorldHistoryGenerator::seed_world(parameters, &resources);
+        let reported = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let log = reported.clone();
+        generator.observe(move |progress: &GenerationProgress| log.borrow_mut().push(progress.year));
+
+        generator.stop_at_year(20);
+        assert_eq!(generator.run(Duration::months(3), None), GenerationState::Finished);
+        assert_eq!(generator.world.date.year(), 20);
+        assert_eq!(reported.borrow().last(), Some(&20));
+
+        // Resumes from a save
+        let world: World = ciborium::from_reader(save_world(&generator.world).as_slice()).unwrap();
+        let mut generator = WorldHistoryGenerator::simulator(world);
+        assert_eq!(generator.state(), GenerationState::Running);
+
+        generator.pause();
+        assert_eq!(generator.advance(Duration::months(3)), GenerationState::Paused);
+        assert_eq!(generator.world.date.year(), 20);
+        generator.resume();
+        generator.advance(Duration::months(3));
+        assert!(generator.world.date > WorldDate::new(20, 1, 1));
+
+        generator.cancel();
+        assert_eq!(generator.run(Duration::months(3), None), GenerationState::Cancelled);
+        assert!(generator.world.date.year() < 100);
+    }
+
 }
@@ -8,20 +8,19 @@ const CIVILIAN_FIGHT_CHANCE: f32 = 0.1;
 impl BattleSimulator {
 
     pub(crate) fn simulate_attack(attacker_id: SiteId, attacker: &Site, defender_id: SiteId, defender: &Site, rng: &mut Rng, world: &World) -> Battle {
-        let mut battle = Battle {
-            log: Vec::new(),
-            deaths: Vec::new(),
-            xp_add: Vec::new()
-        };
+        return Self::simulate_assault(attacker_id, &attacker.creatures, defender_id, &defender.creatures, rng, world)
+    }
 
+    /// An attack on a settlement. The attackers fight, while most of the defending civilians hide.
+    pub(crate) fn simulate_assault(attacker_id: SiteId, attackers: &Vec<CreatureId>, defender_id: SiteId, defenders: &Vec<CreatureId>, rng: &mut Rng, world: &World) -> Battle {
         let mut creatures = Vec::new();
 
-        for id in attacker.creatures.iter() {
+        for id in attackers.iter() {
             let creature = world.creatures.get(id);
             creatures.push(BattleCreature { id: *id, site_id: attacker_id, creature, hp: 100., team: 0, tactic: Tactic::Fight })
         }
 
-        for id in defender.creatures.iter() {
+        for id in defenders.iter() {
             let creature = world.creatures.get(id);
             let tactic = match creature.profession {
                 Profession::Guard | Profession::Beast | Profession::Bandit | Profession::Ruler => Tactic::Fight,
@@ -37,6 +36,37 @@ impl BattleSimulator {
             creatures.push(BattleCreature { id: *id, site_id: defender_id, creature, hp: 100., team: 1, tactic })
         }
 
+        return Self::fight(creatures, rng)
+    }
+
+    /// A battle between two armies in the field. Everyone fights.
+    pub(crate) fn simulate_field_battle(attacker_id: SiteId, attackers: &Vec<CreatureId>, defender_id: SiteId, defenders: &Vec<CreatureId>, rng: &mut Rng, world: &World) -> Battle {
+        let mut creatures = Vec::new();
+
+        for id in attackers.iter() {
+            let creature = world.creatures.get(id);
+            creatures.push(BattleCreature { id: *id, site_id: attacker_id, creature, hp: 100., team: 0, tactic: Tactic::Fight })
+        }
+
+        for id in defenders.iter() {
+            let creature = world.creatures.get(id);
+            creatures.push(BattleCreature { id: *id, site_id: defender_id, creature, hp: 100., team: 1, tactic: Tactic::Fight })
+        }
+
+        return Self::fight(creatures, rng)
+    }
+
+    fn fight(mut creatures: Vec<BattleCreature>, rng: &mut Rng) -> Battle {
+        let mut battle = Battle {
+            log: Vec::new(),
+            deaths: Vec::new(),
+            xp_add: Vec::new()
+        };
+
+        if creatures.len() == 0 {
+            return battle
+        }
+
         let max_turns = rng.randu_range(5, 15) * creatures.len();
         for i in 0..max_turns {
             let turn_index = i % creatures.len();
@@ -124,3 +154,11 @@ pub(crate) struct Battle {
     pub(crate) deaths: Vec<(CreatureId, SiteId, CreatureId)>,
     pub(crate) xp_add: Vec<(CreatureId, u32)>,
 }
+
+impl Battle {
+
+    pub(crate) fn casualties_of(&self, site_id: &SiteId) -> u32 {
+        return self.deaths.iter().filter(|(_, death_site_id, _)| death_site_id == site_id).count() as u32
+    }
+
+}
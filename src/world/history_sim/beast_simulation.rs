@@ -0,0 +1,158 @@
+use math::Vec2i;
+
+use crate::{commons::{id_vec::Id, rng::Rng, xp_table::level_to_xp}, history_trace, resources::{biome::BiomeId, resources::Resources, species::SpeciesId}, world::{creature::{CauseOfDeath, CreatureId, SIM_FLAG_GREAT_BEAST}, date::Duration, history_sim::{factories::CreatureFactory, world_ops}, site::{Site, SiteId, SiteResources, SiteType}, world::World}, Event};
+
+/// Great beasts stop growing at this level
+const MAX_LEVEL: u16 = 20;
+/// Years past its lifespan by which a great beast has surely died
+const OLD_AGE_YEARS: f32 = 50.;
+
+/// Every kind of great beast might make a new lair somewhere it likes to live
+pub(crate) fn spawn_great_beasts(world: &mut World, rng: &mut Rng, chance: f32, resources: &Resources) {
+    for (i, species) in resources.species.iter().enumerate() {
+        let ecology = match &species.ecology {
+            Some(ecology) => ecology,
+            None => continue,
+        };
+        if !rng.rand_chance(chance * ecology.spawn_chance) {
+            continue;
+        }
+        let pos = match search_lair_pos(world, rng, &ecology.biomes) {
+            Some(pos) => pos,
+            None => continue,
+        };
+        let mut factory = CreatureFactory::new(rng.derive("creature"));
+        let creature_id = factory.make_single(SpeciesId::new(i), ecology.level, SIM_FLAG_GREAT_BEAST, world);
+        let name = world.creature_desc(&creature_id, resources);
+        let site_id = world.sites.add::<SiteId>(Site {
+            artifacts: Vec::new(),
+            cemetery: Vec::new(),
+            name: Some(format!("The lair of {name}")),
+            creatures: vec!(creature_id),
+            settlement: None,
+            population_peak: (0, 0),
+            population_history: Vec::new(),
+            resources: SiteResources { food: 2. },
+            site_type: SiteType::Lair,
+            xy: pos,
+            structures: Vec::new()
+        });
+        history_trace!("beast_appeared creature_id:{:?} site_id:{:?}", creature_id, site_id);
+        world.events.push(Event::BeastAppeared { date: world.date, creature_id, site_id });
+    }
+}
+
+/// Great beasts grow stronger over the centuries, breed in their lairs, and in the end die of old age
+pub(crate) fn simulate_lair(world: &mut World, site_id: SiteId, step: &Duration, rng: &mut Rng, resources: &Resources) {
+    let beasts: Vec<CreatureId> = {
+        let site = world.sites.get(&site_id);
+        if site.site_type != SiteType::Lair {
+            return;
+        }
+        site.creatures.iter().filter(|id| world.creatures.get(*id).sim_flag_is_great_beast()).copied().collect()
+    };
+
+    for creature_id in beasts {
+        let (species_id, age) = {
+            let creature = world.creatures.get(&creature_id);
+            (creature.species, (world.date - creature.birth).get_years())
+        };
+        let species = resources.species.get(&species_id);
+        let ecology = match &species.ecology {
+            Some(ecology) => ecology,
+            None => continue,
+        };
+
+        if age >= ecology.lifespan {
+            let chance = ((age - ecology.lifespan) as f32 / OLD_AGE_YEARS).min(1.) * step.percentage_of_year();
+            if rng.rand_chance(chance) {
+                world.kill_creature(creature_id, site_id, site_id, CauseOfDeath::OldAge);
+                continue;
+            }
+        }
+
+        let level = (ecology.level as f32 + age as f32 * ecology.levels_per_century / 100.) as u16;
+        {
+            let mut creature = world.creatures.get_mut(&creature_id);
+            creature.experience = creature.experience.max(level_to_xp(level.min(MAX_LEVEL)));
+        }
+
+        let brood = world.sites.get(&site_id).creatures.len();
+        if brood < ecology.max_brood && rng.rand_chance(ecology.brood_chance * step.percentage_of_year()) {
+            let mut factory = CreatureFactory::new(rng.derive("creature"));
+            let young_id = factory.make_single(species_id, ecology.level, SIM_FLAG_GREAT_BEAST, world);
+            world.creatures.get_mut(&young_id).birth = world.date;
+            world.sites.get_mut(&site_id).creatures.push(young_id);
+            history_trace!("beast_bred creature_id:{:?} parent_id:{:?} site_id:{:?}", young_id, creature_id, site_id);
+            world.events.push(Event::BeastBred { date: world.date, creature_id: young_id, parent_id: creature_id, site_id });
+        }
+    }
+}
+
+/// A free spot in one of the biomes the beast likes
+fn search_lair_pos(world: &World, rng: &mut Rng, biomes: &Vec<BiomeId>) -> Option<Vec2i> {
+    for _ in 0..100 {
+        let x = rng.randu_range(3, world.map.size.x() - 3);
+        let y = rng.randu_range(3, world.map.size.y() - 3);
+        let candidate = Vec2i(x as i32, y as i32);
+        let tile = world.map.tile(x, y);
+        if biomes.contains(&BiomeId::new(tile.region_id as usize)) && world_ops::site_pos_is_free(world, &candidate) {
+            return Some(candidate);
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests_beast {
+    use crate::{commons::xp_table::xp_to_level, world::world::fixture::WorldFixture};
+
+    use super::*;
+
+    #[test]
+    fn test_beasts_lair_in_their_biomes() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let grasslands = resources.biomes.id_of("biome:grasslands");
+
+        spawn_great_beasts(&mut fixture.world, &mut Rng::seeded("beasts"), 1000., &resources);
+
+        let mut lairs = 0;
+        for site in fixture.world.sites.iter() {
+            let site = site.borrow();
+            if site.site_type != SiteType::Lair {
+                continue;
+            }
+            lairs += 1;
+            let beast = fixture.world.creatures.get(&site.creatures[0]);
+            assert!(beast.sim_flag_is_great_beast());
+            let species = resources.species.get(&beast.species);
+            assert!(species.ecology.as_ref().unwrap().biomes.contains(&grasslands));
+        }
+        assert!(lairs > 0);
+    }
+
+    #[test]
+    fn test_beasts_grow_over_the_centuries() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+
+        let species = resources.species.id_of("species:varningr");
+        let mut factory = CreatureFactory::new(Rng::seeded("varningr"));
+        let beast_id = factory.make_single(species, 3, SIM_FLAG_GREAT_BEAST, &mut fixture.world);
+        let site_id = SiteId::mock(0);
+        {
+            let mut site = fixture.world.sites.get_mut(&site_id);
+            site.site_type = SiteType::Lair;
+            site.creatures = vec!(beast_id);
+        }
+        // Born 16 years before, 216 years old now
+        fixture.world.date = fixture.world.date + Duration::years(200);
+
+        simulate_lair(&mut fixture.world, site_id, &Duration::years(1), &mut Rng::seeded("lair"), &resources);
+        assert_eq!(xp_to_level(fixture.world.creatures.get(&beast_id).experience), 7);
+    }
+
+}
@@ -0,0 +1,103 @@
+use math::Vec2i;
+
+use crate::{commons::rng::Rng, history_trace, world::{climate::{Climate, Disaster, DisasterKind, Season}, date::{Duration, WorldDate}, site::SiteId, world::World}, Event};
+
+/// How far a drought or flood reaches from where it strikes
+const DISASTER_RADIUS: f32 = 6.;
+/// Part of the stored food that is ruined when a flood strikes
+const FLOOD_SPOILED_FOOD: f32 = 0.3;
+
+/// Droughts and floods strike the regions prone to them, and the ones that ran their course are forgotten
+pub(crate) fn simulate_climate(world: &mut World, step: &Duration, rng: &mut Rng) {
+    let now = world.date;
+    world.disasters.retain(|disaster| disaster.is_active(&now));
+    let factor = step.percentage_of_year();
+    let villages: Vec<(SiteId, Vec2i)> = world.sites.iter_id_val::<SiteId>()
+        .filter(|(_, site)| {
+            let site = site.borrow();
+            site.site_type.is_settlement() && site.creatures.len() > 0
+        })
+        .map(|(site_id, site)| (site_id, site.borrow().xy))
+        .collect();
+
+    for (site_id, xy) in villages {
+        if world.disasters.iter().any(|disaster| disaster.is_active(&world.date) && disaster.affects(&xy)) {
+            continue;
+        }
+        let climate = Climate::of(&world.map, &xy);
+        for kind in DisasterKind::ALL {
+            if rng.rand_chance(climate.disaster_chance(kind) * factor) {
+                strike(world, site_id, xy, kind);
+                break;
+            }
+        }
+    }
+}
+
+fn strike(world: &mut World, site_id: SiteId, xy: Vec2i, kind: DisasterKind) {
+    let duration = match kind {
+        // A whole year without rain
+        DisasterKind::Drought => Duration::years(1),
+        DisasterKind::Flood => Duration::months(3),
+    };
+    let disaster = Disaster { kind, center: xy, radius: DISASTER_RADIUS, start: world.date, end: world.date + duration };
+
+    if kind == DisasterKind::Flood {
+        for site in world.sites.iter() {
+            let mut site = site.borrow_mut();
+            if site.site_type.is_settlement() && disaster.affects(&site.xy) && site.resources.food > 0. {
+                site.resources.food *= 1. - FLOOD_SPOILED_FOOD;
+            }
+        }
+    }
+
+    history_trace!("natural_disaster site_id:{:?} kind:{:?}", site_id, kind);
+    world.disasters.push(disaster);
+    world.events.push(Event::NaturalDisaster { date: world.date, kind, site_id });
+}
+
+/// How much the fields around a position yield at the date, with the season and any disaster
+pub(crate) fn harvest_multiplier(world: &World, xy: &Vec2i, date: &WorldDate) -> f32 {
+    let climate = Climate::of(&world.map, xy);
+    let mut multiplier = climate.harvest_multiplier(Season::of(date));
+    for disaster in world.disasters.iter() {
+        if disaster.is_active(date) && disaster.affects(xy) {
+            multiplier *= disaster.harvest_multiplier();
+        }
+    }
+    return multiplier
+}
+
+#[cfg(test)]
+mod tests_climate_simulation {
+    use crate::{commons::id_vec::Id, world::world::fixture::WorldFixture};
+
+    use super::*;
+
+    #[test]
+    fn test_drought_ruins_harvest() {
+        let mut fixture = WorldFixture::new();
+        let xy = fixture.world.sites.get(&SiteId::mock(0)).xy;
+        fixture.world.date = WorldDate::new(10, 10, 1);
+        let normal = harvest_multiplier(&fixture.world, &xy, &fixture.world.date);
+
+        strike(&mut fixture.world, SiteId::mock(0), xy, DisasterKind::Drought);
+
+        assert!(harvest_multiplier(&fixture.world, &xy, &fixture.world.date) < normal);
+        let next_year = fixture.world.date + Duration::years(1);
+        assert_eq!(harvest_multiplier(&fixture.world, &xy, &next_year), normal);
+    }
+
+    #[test]
+    fn test_drop_past_disasters() {
+        let mut fixture = WorldFixture::new();
+        let xy = fixture.world.sites.get(&SiteId::mock(0)).xy;
+        strike(&mut fixture.world, SiteId::mock(0), xy, DisasterKind::Flood);
+
+        fixture.world.date = fixture.world.date + Duration::years(1);
+        simulate_climate(&mut fixture.world, &Duration::days(0), &mut Rng::seeded("climate"));
+
+        assert!(fixture.world.disasters.is_empty());
+    }
+
+}
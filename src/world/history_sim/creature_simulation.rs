@@ -1,4 +1,4 @@
-use crate::{commons::{id_vec::Identified, rng::Rng}, history_trace, warn, world::{creature::{CauseOfDeath, Creature, CreatureId, Profession}, date::{Duration, WorldDate}, history_sim::{battle_simulator::BattleSimulator, storyteller::SiteChances}, item::{Item, ItemId}, plot::{Plot, PlotGoal, PlotId}, site::{Site, SiteId, SiteType}, world::World}};
+use crate::{commons::rng::Rng, history_trace, resources::resources::Resources, warn, world::{creature::{CauseOfDeath, Creature, CreatureId, Goal, Profession}, date::{Duration, WorldDate}, history_sim::{battle_simulator::{Battle, BattleSimulator}, storyteller::SiteChances, world_ops}, item::{Item, ItemId}, plot::{Plot, PlotGoal, PlotId}, site::{Site, SiteId}, world::World}, Event};
 
 pub(crate) struct CreatureSimulation {}
 
@@ -10,6 +10,7 @@ pub(crate) enum CreatureSideEffect {
     LookForMarriage,
     LookForNewJob,
     MakeArtifact,
+    CommissionRelic,
     BecomeBandit,
     AttackNearbySites,
     StartPlot(PlotGoal),
@@ -20,7 +21,7 @@ pub(crate) enum CreatureSideEffect {
 
 impl CreatureSimulation {
 
-    pub(crate) fn simulate_step_creature(_step: &Duration, now: &WorldDate, rng: &mut Rng, site: &Site, creature_id: &CreatureId, creature: &Creature, supported_plot: Option<Identified<PlotId, Plot>>, chances: &SiteChances) -> CreatureSideEffect {
+    pub(crate) fn simulate_step_creature(_step: &Duration, now: &WorldDate, rng: &mut Rng, site: &Site, creature_id: &CreatureId, creature: &Creature, supported_plot: Option<&Plot>, chances: &SiteChances) -> CreatureSideEffect {
         let age = (*now - creature.birth).get_years();
         // Death by disease
         if rng.rand_chance(chances.disease_death) {
@@ -34,7 +35,7 @@ impl CreatureSimulation {
         }
 
         // Get a profession
-        if creature.sim_flag_is_inteligent() && site.site_type == SiteType::Village {
+        if creature.sim_flag_is_inteligent() && site.site_type.is_settlement() {
 
             match supported_plot {
                 None => {
@@ -99,7 +100,8 @@ impl CreatureSimulation {
             }
         }
 
-        if age >= 40 {
+        // Great beasts live for centuries. Their old age comes in their lairs
+        if age >= 40 && !creature.sim_flag_is_great_beast() {
             // Death of old age
             if rng.rand_chance(chances.base_multiplier * Self::chance_of_death_by_old_age(age as f32)) {
                 return CreatureSideEffect::Death(CauseOfDeath::OldAge);
@@ -112,6 +114,11 @@ impl CreatureSimulation {
                     return CreatureSideEffect::MakeArtifact;
                 }
             },
+            Profession::Priest => {
+                if rng.rand_chance(chances.commission_relic) {
+                    return CreatureSideEffect::CommissionRelic;
+                }
+            },
             _ => ()
         }
         return CreatureSideEffect::None
@@ -133,14 +140,24 @@ impl CreatureSimulation {
 // Legendary beasts
 const HUNT_RADIUS_SQRD: f32 = 5.*5.;
 
-pub(crate) fn attack_nearby_site(world: &mut World, rng: &mut Rng, site_id: SiteId) {
+pub(crate) fn attack_nearby_site(world: &mut World, rng: &mut Rng, site_id: SiteId, resources: &Resources) {
     let mut candidates = Vec::new();
+    let mut beast_id = None;
     {
         let source_site = world.sites.get(&site_id);
+        // Each kind of beast hunts as far as it likes
+        let mut hunt_radius_sqrd = HUNT_RADIUS_SQRD;
+        for creature_id in source_site.creatures.iter() {
+            let creature = world.creatures.get(creature_id);
+            if let Some(ecology) = &resources.species.get(&creature.species).ecology {
+                hunt_radius_sqrd = hunt_radius_sqrd.max(ecology.hunting_radius * ecology.hunting_radius);
+                beast_id = beast_id.or(Some(*creature_id));
+            }
+        }
         for (id, site) in world.sites.iter_id_val::<SiteId>() {
             if id != site_id {
                 let site = site.borrow();
-                if site.creatures.len() > 0 && site.xy.dist_squared(&source_site.xy) < HUNT_RADIUS_SQRD {
+                if site.creatures.len() > 0 && site.xy.dist_squared(&source_site.xy) < hunt_radius_sqrd {
                     candidates.push(id);
                     break;
                 }
@@ -155,27 +172,53 @@ pub(crate) fn attack_nearby_site(world: &mut World, rng: &mut Rng, site_id: Site
             let target_site = world.sites.get(target);
             battle = BattleSimulator::simulate_attack(site_id, &site, *target, &target_site, rng, world);
         }
-
-        for (id, site_id, killer_id) in battle.deaths {
-            let killer = world.creatures.get(&killer_id);
-            let item_used = match &killer.details {
-                Some(details) => details.inventory.first().and_then(|id| Some(*id)),
-                None => None
-            };
-            let cause_of_death = CauseOfDeath::KilledInBattle(killer_id, item_used);
-            drop(killer);
-            world.kill_creature(id, site_id, *target, cause_of_death);
+        apply_battle_results(world, battle, *target);
+
+        // The lord of the attacked site swears to be rid of the beast
+        if let Some(beast_id) = beast_id {
+            let leader_id = world.sites.get(target).settlement.as_ref().and_then(|settlement| settlement.leader);
+            if let Some(leader_id) = leader_id {
+                let beast_alive = world.creatures.get(&beast_id).death.is_none();
+                let mut leader = world.creatures.get_mut(&leader_id);
+                if beast_alive && leader.death.is_none() {
+                    let goal = Goal::KillBeast(beast_id);
+                    history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", leader_id, goal);
+                    leader.add_goal(goal);
+                }
+            }
         }
+    }
+}
 
-        for (id, xp) in battle.xp_add {
-            let mut creature = world.creatures.get_mut(&id);
-            creature.experience += xp;
-        }
+/// Kills the creatures that died in the battle, and gives experience to the survivors
+pub(crate) fn apply_battle_results(world: &mut World, battle: Battle, battle_site_id: SiteId) {
+    for (id, site_id, killer_id) in battle.deaths {
+        let killer = world.creatures.get(&killer_id);
+        let item_used = match &killer.details {
+            Some(details) => details.inventory.first().and_then(|id| Some(*id)),
+            None => None
+        };
+        let cause_of_death = CauseOfDeath::KilledInBattle(killer_id, item_used);
+        drop(killer);
+        world.kill_creature(id, site_id, battle_site_id, cause_of_death);
+    }
+
+    for (id, xp) in battle.xp_add {
+        let mut creature = world.creatures.get_mut(&id);
+        creature.experience += xp;
     }
 }
 
 // Plot stuff
 
+/// Chance that the victim of a foiled ambush kills the plotter
+const FOILED_AMBUSH_DEATH_CHANCE: f32 = 0.5;
+/// Chance that someone that was robbed wants their artifact back
+const STOLEN_ARTIFACT_GRUDGE_CHANCE: f32 = 0.5;
+/// Opinion the victim of a plot loses of the plotter, when the plot is foiled
+const FOILED_PLOT_OPINION: i8 = -50;
+const MIN_MARRIAGE_AGE: i32 = 18;
+
 pub(crate) fn start_plot(world: &mut World, creature_id: CreatureId, goal: PlotGoal) {
     let plot = Plot::new(goal, creature_id, world);
     let plot_id = world.plots.add(plot);
@@ -186,7 +229,7 @@ pub(crate) fn start_plot(world: &mut World, creature_id: CreatureId, goal: PlotG
 
 }
 
-pub(crate) fn find_supporters_for_plot(world: &mut World, creature_id: CreatureId) {
+pub(crate) fn find_supporters_for_plot(world: &mut World, site_id: SiteId, creature_id: CreatureId) {
     let creature = world.creatures.get(&creature_id);
     let plot_id_o = creature.supports_plot;
     // TODO(IhlgIYVA): Kind of a smell
@@ -195,10 +238,12 @@ pub(crate) fn find_supporters_for_plot(world: &mut World, creature_id: CreatureI
     }
     let plot_id = plot_id_o.expect("Shouldn't happen");
     let mut plot = world.plots.get_mut(&plot_id);
+    let site = world.sites.get(&site_id);
+    let victim = plot.goal.victim(world);
 
     for relationship in creature.relationships.iter() {
 
-        if relationship.rival_or_worse() {
+        if relationship.rival_or_worse() || victim == Some(relationship.creature_id) {
             continue;
         }
 
@@ -209,21 +254,7 @@ pub(crate) fn find_supporters_for_plot(world: &mut World, creature_id: CreatureI
             continue;
         }
 
-        let mut can_support = false;
-
-        // If shares a goal
-        for goal in relation.goals.iter() {
-            let plot_goal = goal.as_plot_goal();
-            if let Some(plot_goal) = plot_goal {
-                if plot_goal == plot.goal {
-                    can_support = true;
-                    break;
-
-                }
-            }
-        }
-
-        if can_support {
+        if plot.goal.appeals_to(relationship, &relation, &site) {
             history_trace!("plot_new_supporter creature_id:{:?} plot_id:{:?}", relationship.creature_id, plot_id);
             plot.add_supporter(plot_id, relationship.creature_id, &mut relation);
         }
@@ -232,7 +263,7 @@ pub(crate) fn find_supporters_for_plot(world: &mut World, creature_id: CreatureI
 
 }
 
-pub(crate) fn execute_plot(world: &mut World, site_id: SiteId, creature_id: CreatureId, rng: &mut Rng) {
+pub(crate) fn execute_plot(world: &mut World, site_id: SiteId, creature_id: CreatureId, rng: &mut Rng, resources: &Resources) {
     let creature = world.creatures.get(&creature_id);
     let plot_id_o = creature.supports_plot;
     // TODO(IhlgIYVA): Kind of a smell
@@ -244,57 +275,122 @@ pub(crate) fn execute_plot(world: &mut World, site_id: SiteId, creature_id: Crea
     history_trace!("execute_plot creature_id:{:?} plot_id:{:?} plot:{:?}", creature_id, plot_id, plot);
 
     let goal = plot.goal.clone();
+    let success_chance = plot.success_chance();
     drop(creature);
     drop(plot);
 
-    match goal {
-        PlotGoal::KillBeast(target_id) => {
+    if let Some(victim_id) = goal.victim(world) {
+        // TODO(IhlgIYVA): Bug
+        if world.creatures.get(&victim_id).death.is_some() {
+            warn!("plot: creature is already dead");
+            return;
+        }
+        if victim_id == creature_id || world.is_played_creature(&victim_id) {
+            return;
+        }
+    }
 
-            // TODO(IhlgIYVA): Bug
-            let creature = world.creatures.get(&target_id);
-            if creature.death.is_some() {
-                warn!("plot: creature is already dead");
+    match goal {
+        PlotGoal::KillBeast(target_id) => hunt(world, site_id, target_id, rng),
+        PlotGoal::Avenge { target, victim: _ } => {
+            let is_beast = !world.creatures.get(&target).sim_flag_is_inteligent();
+            match is_beast {
+                true => hunt(world, site_id, target, rng),
+                false => ambush(world, plot_id, site_id, creature_id, target, success_chance, rng),
+            }
+        },
+        PlotGoal::OverthrowLeader { site_id: target_site, leader_id } => {
+            // Only someone inside the walls can take over
+            if target_site != site_id {
                 return;
             }
-            drop(creature);
-
-            // TODO(IhlgIYVA): Performance for Site
-            let ret = world.sites.iter_id_val::<SiteId>().find(|(_id, site)| site.borrow().creatures.contains(&target_id));
-            if let Some((target_id, _)) = ret {
-
-                // TODO(IhlgIYVA): Dupped code
-                // TODO(IhlgIYVA): Separate plotters from site
-                // TODO(IhlgIYVA): Die outside of site
-
-                let battle;
+            if rng.rand_chance(success_chance) {
+                world.creature_kill_creature(leader_id, site_id, creature_id, weapon_of(world, &creature_id), site_id);
+                if let Err(msg) = world.site_install_leader(&site_id, creature_id, resources) {
+                    warn!("[plot] {msg}");
+                }
+                world.events.push(Event::LeaderOverthrown { date: world.date, site_id, creature_id, leader_id });
+            } else {
+                foil_plot(world, plot_id, creature_id, leader_id);
+                // Traitors are put to death
+                world.creature_kill_creature(creature_id, site_id, leader_id, weapon_of(world, &leader_id), site_id);
+            }
+        },
+        PlotGoal::StealArtifact(item_id) => {
+            let owner_id = match world.artifacts.get(&item_id).owner {
+                Some(owner_id) => owner_id,
+                None => return,
+            };
+            if rng.rand_chance(success_chance) {
                 {
-                    let site = world.sites.get(&site_id);
-                    let target_site = world.sites.get(&target_id);
-                    battle = BattleSimulator::simulate_attack(site_id, &site, target_id, &target_site, rng, world);
+                    let mut owner = world.creatures.get_mut(&owner_id);
+                    owner.details().inventory.retain(|id| id != &item_id);
+                    let mut item = world.artifacts.get_mut(&item_id);
+                    let mut creature = world.creatures.get_mut(&creature_id);
+                    add_item_to_inventory(item_id, &mut item, creature_id, &mut creature);
+                    if owner.death.is_none() && rng.rand_chance(STOLEN_ARTIFACT_GRUDGE_CHANCE) {
+                        owner.add_goal(Goal::StealArtifact(item_id));
+                    }
                 }
-        
-                for (id, site_id, killer_id) in battle.deaths {
-                    let killer = world.creatures.get(&killer_id);
-                    let item_used = match &killer.details {
-                        Some(details) => details.inventory.first().and_then(|id| Some(*id)),
-                        None => None
-                    };
-                    let cause_of_death = CauseOfDeath::KilledInBattle(killer_id, item_used);
-                    drop(killer);
-                    world.kill_creature(id, site_id, target_id, cause_of_death);
+                world.events.push(Event::ArtifactStolen { date: world.date, creature_id, from: owner_id, item: item_id });
+            } else {
+                foil_plot(world, plot_id, creature_id, owner_id);
+            }
+        },
+        PlotGoal::FoundSettlement => {
+            // Every follower brings their household along
+            let settlers = {
+                let plot = world.plots.get(&plot_id);
+                let site = world.sites.get(&site_id);
+                // The leader stays behind
+                let leader = site.settlement.as_ref().and_then(|settlement| settlement.leader);
+                let mut settlers: Vec<Vec<CreatureId>> = Vec::new();
+                for head_id in [creature_id].iter().chain(plot.supporters().iter()) {
+                    if settlers.iter().any(|household| household.contains(head_id)) || !site.creatures.contains(head_id) || Some(*head_id) == leader {
+                        continue;
+                    }
+                    match site.structure_occupied_by(head_id) {
+                        Some(structure) => settlers.push(structure.occupants().filter(|id| !world.is_played_creature(id) && Some(**id) != leader).copied().collect()),
+                        None => settlers.push(vec!(*head_id)),
+                    }
                 }
-        
-                for (id, xp) in battle.xp_add {
-                    let mut creature = world.creatures.get_mut(&id);
-                    creature.experience += xp;
+                settlers
+            };
+            match world_ops::found_village(world, site_id, creature_id, settlers, rng, resources) {
+                Ok(new_site_id) => world.events.push(Event::SettlementFounded { date: world.date, creature_id, site_id, new_site_id }),
+                Err(_) => warn!("[plot] No place found for a new village"),
+            }
+        },
+        PlotGoal::MarryInto(lineage_id) => {
+            let candidates: Vec<CreatureId> = {
+                let creature = world.creatures.get(&creature_id);
+                let site = world.sites.get(&site_id);
+                site.creatures.iter().filter(|candidate_id| {
+                    if world.is_played_creature(candidate_id) {
+                        return false;
+                    }
+                    let candidate = world.creatures.get(*candidate_id);
+                    let age = (world.date - candidate.birth).get_years();
+                    candidate.lineage == Some(lineage_id) && candidate.spouse.is_none() && candidate.gender != creature.gender && age >= MIN_MARRIAGE_AGE
+                }).copied().collect()
+            };
+            let spouse_id = match rng.item(&candidates) {
+                Some(spouse_id) => *spouse_id,
+                None => return,
+            };
+            if rng.rand_chance(success_chance) {
+                {
+                    let mut creature = world.creatures.get_mut(&creature_id);
+                    let mut spouse = world.creatures.get_mut(&spouse_id);
+                    creature.spouse = Some(spouse_id);
+                    spouse.spouse = Some(creature_id);
                 }
-
+                world.events.push(Event::CreatureMarriage { date: world.date, creature_id, spouse_id });
             } else {
-                // TODO(IhlgIYVA): Error handling
-                warn!("[plot] Shouldn't happen");
+                // Turned down
+                foil_plot(world, plot_id, creature_id, spouse_id);
             }
-
-        }
+        },
     }
 
     let mut plot = world.plots.get_mut(&plot_id);
@@ -302,6 +398,65 @@ pub(crate) fn execute_plot(world: &mut World, site_id: SiteId, creature_id: Crea
 
 }
 
+/// The plotters attack the site where the target lives
+fn hunt(world: &mut World, site_id: SiteId, target_id: CreatureId, rng: &mut Rng) {
+    // TODO(IhlgIYVA): Performance for Site
+    let ret = world.sites.iter_id_val::<SiteId>().find(|(_id, site)| site.borrow().creatures.contains(&target_id));
+    if let Some((target_id, _)) = ret {
+
+        // TODO(IhlgIYVA): Separate plotters from site
+        // TODO(IhlgIYVA): Die outside of site
+
+        let battle;
+        {
+            let site = world.sites.get(&site_id);
+            let target_site = world.sites.get(&target_id);
+            battle = BattleSimulator::simulate_attack(site_id, &site, target_id, &target_site, rng, world);
+        }
+        apply_battle_results(world, battle, target_id);
+
+    } else {
+        // TODO(IhlgIYVA): Error handling
+        warn!("[plot] Shouldn't happen");
+    }
+}
+
+/// The plotter lies in wait for the target, away from any guards
+fn ambush(world: &mut World, plot_id: PlotId, site_id: SiteId, creature_id: CreatureId, target_id: CreatureId, success_chance: f32, rng: &mut Rng) {
+    let target_site = world.sites.iter_id_val::<SiteId>().find(|(_id, site)| site.borrow().creatures.contains(&target_id)).map(|(id, _)| id);
+    let target_site = match target_site {
+        Some(target_site) => target_site,
+        None => return,
+    };
+    if rng.rand_chance(success_chance) {
+        world.creature_kill_creature(target_id, target_site, creature_id, weapon_of(world, &creature_id), target_site);
+    } else {
+        foil_plot(world, plot_id, creature_id, target_id);
+        if rng.rand_chance(FOILED_AMBUSH_DEATH_CHANCE) {
+            world.creature_kill_creature(creature_id, site_id, target_id, weapon_of(world, &target_id), target_site);
+        }
+    }
+}
+
+/// The victim finds out about the plot before it succeeds
+fn foil_plot(world: &mut World, plot_id: PlotId, creature_id: CreatureId, victim_id: CreatureId) {
+    {
+        let mut victim = world.creatures.get_mut(&victim_id);
+        if victim.death.is_none() {
+            let creature = world.creatures.get(&creature_id);
+            victim.relationship_find_mut_or_insert(&victim_id, creature_id, &creature).add_opinion(FOILED_PLOT_OPINION);
+        }
+    }
+    history_trace!("plot_foiled creature_id:{:?} plot_id:{:?}", creature_id, plot_id);
+    world.plots.get_mut(&plot_id).foil(world);
+    world.events.push(Event::PlotFoiled { date: world.date, creature_id, victim_id });
+}
+
+fn weapon_of(world: &World, creature_id: &CreatureId) -> Option<ItemId> {
+    let creature = world.creatures.get(creature_id);
+    return creature.details.as_ref().and_then(|details| details.inventory.first().copied())
+}
+
 // Artifact operations
 
 
@@ -0,0 +1,215 @@
+use crate::{commons::rng::Rng, history_trace, world::{creature::{CauseOfDeath, CreatureId}, date::Duration, epidemic::{Epidemic, EpidemicId, Outbreak}, site::{SiteId, StructureStatus}, world::World}, Event};
+
+/// Part of the population that is sick when a disease first appears
+const INITIAL_INFECTED: f32 = 0.05;
+/// Part of the sick that recovers each year
+const RECOVERY_RATE: f32 = 3.;
+/// The outbreak is over once fewer than this part of the population is sick
+const OUTBREAK_END_INFECTED: f32 = 0.01;
+/// How far the sick will travel, in a straight line
+const TRAVEL_RADIUS_SQRD: f32 = 10.*10.;
+/// Chance per year that a sick site carries the disease to a linked site, when everyone in it is sick
+const SPREAD_RATE: f32 = 4.;
+
+/// A new disease appears in the site, unless it is already sick
+pub(crate) fn start_epidemic(world: &mut World, site_id: SiteId, rng: &mut Rng) {
+    if site_epidemic(world, &site_id).is_some() {
+        return;
+    }
+    let name = {
+        let site = world.sites.get(&site_id);
+        if !site.site_type.is_settlement() || site.creatures.len() == 0 {
+            return;
+        }
+        String::from(site.name())
+    };
+    let mut epidemic = Epidemic::new(site_id, &name, world.date, rng);
+    epidemic.outbreaks.push(Outbreak::new(site_id, world.date, INITIAL_INFECTED));
+    let epidemic_id = world.epidemics.add::<EpidemicId>(epidemic);
+    history_trace!("plague_started epidemic_id:{:?} site_id:{:?}", epidemic_id, site_id);
+    world.events.push(Event::PlagueStarted { date: world.date, epidemic_id, site_id });
+}
+
+/// The active epidemic in the site, if any
+pub(crate) fn site_epidemic(world: &World, site_id: &SiteId) -> Option<EpidemicId> {
+    return world.epidemics.iter_id_val::<EpidemicId>()
+        .find(|(_, epidemic)| {
+            let epidemic = epidemic.borrow();
+            epidemic.outbreak_at(site_id).map(|outbreak| outbreak.is_active()).unwrap_or(false)
+        })
+        .map(|(epidemic_id, _)| epidemic_id)
+}
+
+pub(crate) fn simulate_epidemics(world: &mut World, step: &Duration, rng: &mut Rng) {
+    let active: Vec<EpidemicId> = world.epidemics.iter_id_val::<EpidemicId>()
+        .filter(|(_, epidemic)| epidemic.borrow().is_active())
+        .map(|(epidemic_id, _)| epidemic_id)
+        .collect();
+    for epidemic_id in active {
+        simulate_epidemic(world, epidemic_id, step, rng);
+    }
+}
+
+fn simulate_epidemic(world: &mut World, epidemic_id: EpidemicId, step: &Duration, rng: &mut Rng) {
+    let factor = step.percentage_of_year();
+    let (virulence, lethality, sites) = {
+        let epidemic = world.epidemics.get(&epidemic_id);
+        let sites: Vec<SiteId> = epidemic.outbreaks.iter().filter(|outbreak| outbreak.is_active()).map(|outbreak| outbreak.site_id).collect();
+        (epidemic.virulence, epidemic.lethality, sites)
+    };
+
+    for site_id in sites {
+        let infected = {
+            let mut epidemic = world.epidemics.get_mut(&epidemic_id);
+            let outbreak = outbreak_mut(&mut epidemic, &site_id);
+            outbreak.progress(virulence, RECOVERY_RATE, factor);
+            outbreak.infected
+        };
+
+        let victims: Vec<CreatureId> = {
+            let site = world.sites.get(&site_id);
+            let mut victims = Vec::new();
+            for creature_id in site.creatures.iter() {
+                if world.is_played_creature(creature_id) {
+                    continue;
+                }
+                if rng.rand_chance(infected * lethality * factor) {
+                    victims.push(*creature_id);
+                }
+            }
+            victims
+        };
+        // The dead are buried together in the site's cemetery
+        for creature_id in victims.iter() {
+            world.kill_creature(*creature_id, site_id, site_id, CauseOfDeath::Plague(epidemic_id));
+        }
+        outbreak_mut(&mut world.epidemics.get_mut(&epidemic_id), &site_id).deaths += victims.len() as u32;
+
+        spread(world, epidemic_id, &site_id, infected, factor, rng);
+
+        let population = world.sites.get(&site_id).creatures.len();
+        if infected < OUTBREAK_END_INFECTED || population == 0 {
+            subside(world, epidemic_id, &site_id);
+        }
+    }
+
+    let mut epidemic = world.epidemics.get_mut(&epidemic_id);
+    if !epidemic.outbreaks.iter().any(|outbreak| outbreak.is_active()) {
+        history_trace!("plague_ended epidemic_id:{:?}", epidemic_id);
+        epidemic.end = Some(world.date);
+    }
+}
+
+/// Travellers from the sick site carry the disease to the sites it trades with
+fn spread(world: &mut World, epidemic_id: EpidemicId, site_id: &SiteId, infected: f32, factor: f32, rng: &mut Rng) {
+    let xy = world.sites.get(site_id).xy;
+    let mut neighbours = Vec::new();
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if &other_id == site_id {
+            continue;
+        }
+        let other = other.borrow();
+        if !other.site_type.is_settlement() || other.creatures.len() == 0 || other.xy.dist_squared(&xy) > TRAVEL_RADIUS_SQRD {
+            continue;
+        }
+        if world.epidemics.get(&epidemic_id).outbreak_at(&other_id).is_some() {
+            continue;
+        }
+        neighbours.push(other_id);
+    }
+
+    for other_id in neighbours {
+        // Sites at war with each other don't trade or travel
+        if let Some(war_id) = world.site_ongoing_war(site_id) {
+            if world.wars.get(&war_id).enemy_of(site_id) == other_id {
+                continue;
+            }
+        }
+        if world.trade_route_cost(site_id, &other_id).is_none() {
+            continue;
+        }
+        // Only one disease at a time
+        if site_epidemic(world, &other_id).is_some() {
+            continue;
+        }
+        if rng.rand_chance(infected * SPREAD_RATE * factor) {
+            world.epidemics.get_mut(&epidemic_id).outbreaks.push(Outbreak::new(other_id, world.date, INITIAL_INFECTED));
+            history_trace!("plague_spread epidemic_id:{:?} from:{:?} to:{:?}", epidemic_id, site_id, other_id);
+            world.events.push(Event::PlagueSpread { date: world.date, epidemic_id, site_id: other_id, from: *site_id });
+        }
+    }
+}
+
+fn subside(world: &mut World, epidemic_id: EpidemicId, site_id: &SiteId) {
+    let (start, deaths) = {
+        let mut epidemic = world.epidemics.get_mut(&epidemic_id);
+        let outbreak = outbreak_mut(&mut epidemic, site_id);
+        outbreak.end = Some(world.date);
+        (outbreak.start, outbreak.deaths)
+    };
+    // Homes where no one survived are left empty
+    let abandoned = world.sites.get(site_id).structures.iter()
+        .filter(|structure| match structure.get_status() {
+            StructureStatus::Abandoned(since) => *since >= start,
+            StructureStatus::Occupied => false,
+        })
+        .count() as u32;
+    history_trace!("plague_subsided epidemic_id:{:?} site_id:{:?} deaths:{:?}", epidemic_id, site_id, deaths);
+    world.events.push(Event::PlagueSubsided { date: world.date, epidemic_id, site_id: *site_id, deaths, abandoned });
+}
+
+fn outbreak_mut<'a>(epidemic: &'a mut Epidemic, site_id: &SiteId) -> &'a mut Outbreak {
+    return epidemic.outbreaks.iter_mut().find(|outbreak| &outbreak.site_id == site_id && outbreak.is_active()).expect("Site should have an active outbreak")
+}
+
+#[cfg(test)]
+mod tests_epidemic {
+    use crate::{commons::id_vec::Id, world::{date::WorldDate, world::fixture::WorldFixture}};
+
+    use super::*;
+
+    #[test]
+    fn test_outbreak_runs_its_course() {
+        let mut outbreak = Outbreak::new(SiteId::mock(0), WorldDate::new(1, 1, 1), INITIAL_INFECTED);
+
+        outbreak.progress(6., RECOVERY_RATE, 0.1);
+        assert!(outbreak.infected > INITIAL_INFECTED);
+
+        for _ in 0..100 {
+            outbreak.progress(6., RECOVERY_RATE, 0.1);
+        }
+        assert!(outbreak.infected < OUTBREAK_END_INFECTED);
+        assert!(outbreak.recovered > 0.5);
+        assert!(outbreak.infected + outbreak.recovered <= 1.);
+    }
+
+    #[test]
+    fn test_plague_kills_and_subsides() {
+        let mut fixture = WorldFixture::new();
+        let mut rng = Rng::seeded("plague");
+        let site_id = SiteId::mock(0);
+
+        start_epidemic(&mut fixture.world, site_id, &mut rng);
+        assert!(site_epidemic(&fixture.world, &site_id).is_some());
+        // A sick site won't start a second disease
+        start_epidemic(&mut fixture.world, site_id, &mut rng);
+        assert_eq!(fixture.world.epidemics.len(), 1);
+
+        {
+            let mut epidemic = fixture.world.epidemics.get_mut(&EpidemicId::mock(0));
+            epidemic.lethality = 100.;
+            epidemic.outbreaks[0].infected = 0.5;
+        }
+        simulate_epidemics(&mut fixture.world, &Duration::months(1), &mut rng);
+
+        let site = fixture.world.sites.get(&site_id);
+        assert_eq!(site.creatures.len(), 0);
+        assert_eq!(site.cemetery.len(), 4);
+        drop(site);
+        let epidemic = fixture.world.epidemics.get(&EpidemicId::mock(0));
+        assert_eq!(epidemic.deaths(), 4);
+        assert!(!epidemic.is_active());
+        assert!(matches!(fixture.world.events.last(), Some(Event::PlagueSubsided { deaths: 4, .. })));
+    }
+
+}
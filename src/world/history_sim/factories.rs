@@ -1,4 +1,4 @@
-use crate::{commons::{bitmask::bitmask_get, rng::Rng, xp_table::level_to_xp}, resources::{resources::Resources, species::SpeciesId}, world::{creature::{Creature, CreatureGender, CreatureId, Profession, SIM_FLAG_INTELIGENT}, date::{Duration, WorldDate}, item::{ArtworkScene, Item}, lineage::Lineage, world::World}, ItemFactory};
+use crate::{commons::{bitmask::bitmask_get, rng::Rng, xp_table::level_to_xp}, resources::{culture::CultureId, resources::Resources, species::SpeciesId}, game::factory::item_factory::MaterialPool, world::{creature::{Creature, CreatureGender, CreatureId, Profession, SIM_FLAG_INTELIGENT}, date::{Duration, WorldDate}, item::{ArtworkScene, Item}, lineage::Lineage, religion::DeityId, world::World}, ItemFactory};
 
 pub(crate) struct CreatureFactory {
     rng: Rng
@@ -10,19 +10,15 @@ impl CreatureFactory {
         CreatureFactory { rng }
     }
 
-    pub(crate) fn make_family_or_single(&mut self, now: &WorldDate, species: SpeciesId, world: &mut World, resources: &Resources) -> Vec<CreatureId> {
+    pub(crate) fn make_family_or_single(&mut self, now: &WorldDate, species: SpeciesId, culture_id: CultureId, world: &mut World, resources: &Resources) -> Vec<CreatureId> {
         let age = self.rng.randi_range(20, 50);
 
-        let culture_id = resources.cultures.random();
         let culture = resources.cultures.get(&culture_id);
-        let lineage = world.lineages.add(Lineage::new(resources.cultures.random(), &culture));
+        let lineage = world.lineages.add(Lineage::new(culture_id, &culture, &self.rng.derive("lineage")));
 
         // Single
         if self.rng.rand_chance(0.5) {
-            let mut gender = CreatureGender::Male;
-            if self.rng.rand_chance(0.5) {
-                gender = CreatureGender::Female;
-            }
+            let gender = CreatureGender::random(&mut self.rng);
             let creature_id = world.creatures.add(Creature {
                 birth: *now - Duration::years(age),
                 death: None,
@@ -40,6 +36,7 @@ impl CreatureFactory {
                 relationships: Vec::new(),
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
             return vec!(creature_id)
         } else {
@@ -65,6 +62,7 @@ impl CreatureFactory {
                 relationships: Vec::new(),
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
             family.push(father_id);
             
@@ -85,6 +83,7 @@ impl CreatureFactory {
                 relationships: Vec::new(),
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
             family.push(mother_id);
 
@@ -94,10 +93,7 @@ impl CreatureFactory {
     }
 
     pub(crate) fn make_single(&mut self, species: SpeciesId, level: u16, sim_flags: u8, world: &mut World) -> CreatureId {
-        let mut gender = CreatureGender::Male;
-        if self.rng.rand_chance(0.5) {
-            gender = CreatureGender::Female;
-        }
+        let gender = CreatureGender::random(&mut self.rng);
         let creature_id = world.creatures.add(Creature {
             birth: world.date - Duration::years(16),
             death: None,
@@ -118,6 +114,7 @@ impl CreatureFactory {
             relationships: Vec::new(),
             goals: Vec::new(),
             supports_plot: None,
+            faith: None,
         });
         return creature_id
     }
@@ -129,14 +126,21 @@ pub(crate) struct ArtifactFactory {
 
 impl ArtifactFactory {
 
-    pub(crate) fn create_statue(rng: &mut Rng, resources: &Resources, subject: CreatureId, world: &World) -> Item {
+    pub(crate) fn create_statue(rng: &mut Rng, resources: &Resources, subject: CreatureId, world: &World, material_pool: Option<&mut MaterialPool>) -> Option<Item> {
         let creature = world.creatures.get(&subject);
         if let Some(details) = &creature.details {
             if let Some(item) = details.inventory.first() {
-                return ItemFactory::statue(rng, resources, ArtworkScene::FullBody { creature_id: subject, artifact_id: Some(*item) })
+                return ItemFactory::statue(rng, resources, ArtworkScene::FullBody { creature_id: subject, artifact_id: Some(*item) }, material_pool)
             }
         }
 
-        return ItemFactory::statue(rng, resources, ArtworkScene::Bust { creature_id: subject });
+        return ItemFactory::statue(rng, resources, ArtworkScene::Bust { creature_id: subject }, material_pool);
+    }
+
+    /// A statue of a god, to be kept as a holy relic
+    pub(crate) fn create_holy_statue(rng: &mut Rng, resources: &Resources, deity_id: DeityId, material_pool: Option<&mut MaterialPool>) -> Option<Item> {
+        let mut item = ItemFactory::statue(rng, resources, ArtworkScene::Deity { deity_id }, material_pool)?;
+        item.dedicated_to = Some(deity_id);
+        return Some(item);
     }
 }
\ No newline at end of file
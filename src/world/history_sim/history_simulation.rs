@@ -1,19 +1,114 @@
 use common::error::Error;
+use serde::{Deserialize, Serialize};
 
-use crate::{commons::{rng::Rng, xp_table::xp_to_level}, engine::geometry::Coord2, game::factory::item_factory::ItemFactory, history_trace, resources::resources::resources, warn, world::{creature::{CreatureId, Profession, SIM_FLAG_GREAT_BEAST}, date::{Duration, WorldDate}, history_generator::WorldGenerationParameters, history_sim::{creature_simulation::{add_item_to_inventory, attack_nearby_site, execute_plot, find_supporters_for_plot, start_plot}, storyteller::Storyteller, world_ops}, item::ItemQuality, site::{Site, SiteId, SiteResources, SiteType}, world::World}, Event};
+use crate::{commons::{id_vec::Id, rng::Rng, xp_table::xp_to_level}, engine::geometry::Coord2, game::factory::item_factory::ItemFactory, history_trace, resources::{material::MaterialId, resources::{resources, Resources}}, warn, world::{climate::{Climate, Season}, creature::{Creature, CreatureGender, CreatureId, Goal, Profession}, date::{Duration, WorldDate}, economy::gather_materials, history_generator::WorldGenerationParameters, history_sim::{beast_simulation, climate_simulation, creature_simulation::{add_item_to_inventory, attack_nearby_site, execute_plot, find_supporters_for_plot, start_plot}, epidemic_simulation, migration_simulation, religion_simulation, road_simulation, settlement_simulation::{self, MINE_YIELD, MONASTERY_GARDEN_FOOD, ORE_TRADE_FOOD}, storyteller::{SiteChances, Storyteller}, succession_simulation, trade_simulation, war_simulation, world_ops}, item::ItemQuality, metrics::WorldMetrics, plot::Plot, site::{Site, SiteId, SiteResources, SiteType}, topology::Water, world::World}, Event};
 
 use super::{creature_simulation::{CreatureSideEffect, CreatureSimulation}, factories::{ArtifactFactory, CreatureFactory}};
 
 pub(crate) struct HistorySimulation {
     pub(crate) rng: Rng,
     storyteller: Storyteller,
+    /// Threads the creatures of the sites decide what to do in
+    threads: usize,
+    /// Simulates the sites one after the other with `simulate_step_site`, instead of in three passes
+    sequential: bool,
+}
+
+/// What the simulation needs besides the world to continue the history where it stopped. Saved with the world
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct HistoryState {
+    rng: u32,
+    selected_for_cities: Vec<SiteId>,
+}
+
+/// A site ready for its creatures to decide what to do
+struct SiteStep {
+    site_id: SiteId,
+    rng: Rng,
+    chances: SiteChances,
+    production: SiteProduction,
+}
+
+/// What the land around a site yields this step, and what its creatures eat
+struct SiteProduction {
+    soil_fertility: f32,
+    harvest: f32,
+    consumption: f32,
+    biome_materials: Vec<(MaterialId, f32)>,
+}
+
+impl SiteProduction {
+
+    /// Adds what the creature produced and gathered to the site, and takes what it ate
+    fn produce(&self, step: &Duration, site_type: SiteType, creature: &Creature, resources: &mut SiteResources, gathered_materials: &mut Vec<(MaterialId, usize)>, rng: &mut Rng, game_resources: &Resources) {
+        let mut production = creature.profession.base_resource_production();
+        let mut years_gathering = step.percentage_of_year();
+        // Monks tend the gardens of the monastery
+        if site_type == SiteType::Monastery {
+            production.food += MONASTERY_GARDEN_FOOD;
+        }
+        production.food = production.food * self.soil_fertility * self.harvest;
+        // Miners dig all day, and trade the ore for food
+        if site_type == SiteType::Mine && creature.profession == Profession::Miner {
+            production.food += ORE_TRADE_FOOD;
+            years_gathering *= MINE_YIELD;
+        }
+        *resources = production + *resources;
+        resources.food -= self.consumption;
+        let gathers = creature.profession.gathered_materials();
+        if gathers != 0 {
+            gathered_materials.append(&mut gather_materials(&self.biome_materials, gathers, years_gathering, rng, game_resources));
+        }
+    }
+
+}
+
+/// What the creatures of a site decided to do, and what they produced
+struct SiteDecisions {
+    site_id: SiteId,
+    rng: Rng,
+    chances: SiteChances,
+    was_fed: bool,
+    resources: SiteResources,
+    gathered_materials: Vec<(MaterialId, usize)>,
+    side_effects: Vec<(CreatureId, CreatureSideEffect)>,
+}
+
+/// What a site does to other sites, applied after every site is simulated
+struct CrossSiteEffects {
+    site_id: SiteId,
+    rng: Rng,
+    marriage_pool: Vec<(CreatureId, CreatureGender)>,
+    side_effects: Vec<(CreatureId, CreatureSideEffect)>,
+}
+
+/// The world as the creatures see it while they decide. Read only, so it can be shared between threads
+struct SimulationView<'a> {
+    played_creature: Option<CreatureId>,
+    sites: Vec<&'a Site>,
+    creatures: Vec<&'a Creature>,
+    plots: Vec<&'a Plot>,
 }
 
 impl HistorySimulation {
     pub(crate) fn new(rng: Rng, generation_params: WorldGenerationParameters) -> Self {
         HistorySimulation {
             rng,
-            storyteller: Storyteller::new(generation_params)
+            storyteller: Storyteller::new(generation_params),
+            threads: std::thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1),
+            sequential: false,
+        }
+    }
+
+    /// Continues the history of a saved world
+    pub(crate) fn restore(&mut self, world: &World) {
+        match &world.history_state {
+            Some(state) => {
+                self.rng = Rng::restore(state.rng);
+                self.storyteller.restore(state.selected_for_cities.clone());
+            },
+            // Saved before the state was kept. The history goes on differently, but doesn't replay the first years
+            None => self.rng = self.rng.derive(world.date),
         }
     }
 
@@ -29,28 +124,7 @@ impl HistorySimulation {
 
         let chances = self.storyteller.global_chances(&mut self.rng, &world, &step);
 
-        if self.rng.rand_chance(chances.spawn_varningr) {
-            let pos = self.find_site_suitable_pos(&mut self.rng.clone(), world);
-
-            if let Some(pos) = pos {
-                let species = resources.species.id_of("species:varningr");
-                let mut factory = CreatureFactory::new(self.rng.derive("creature"));
-                let creature = factory.make_single(species, 3, SIM_FLAG_GREAT_BEAST, world);
-                let site = Site {
-                    artifacts: Vec::new(),
-                    cemetery: Vec::new(),
-                    name: None,
-                    creatures: vec!(creature),
-                    settlement: None,
-                    population_peak: (0, 0),
-                    resources: SiteResources { food: 2. },
-                    site_type: SiteType::VarningrLair,
-                    xy: pos.to_vec2i(),
-                    structures: Vec::new()
-                };
-                world.sites.add::<SiteId>(site);
-            }
-        }
+        beast_simulation::spawn_great_beasts(world, &mut self.rng, chances.spawn_great_beast, &resources);
         if self.rng.rand_chance(chances.spawn_wolf_pack) {
             let pos = self.find_site_suitable_pos(&mut self.rng.clone(), world);
 
@@ -69,6 +143,7 @@ impl HistorySimulation {
                     settlement: None,
                     name: None,
                     population_peak: (0, 0),
+                    population_history: Vec::new(),
                     resources: SiteResources { food: 2. },
                     site_type: SiteType::WolfPack,
                     xy: pos.to_vec2i(),
@@ -86,117 +161,349 @@ impl HistorySimulation {
             plot.borrow_mut().verify_success(world);
         }
 
-        let mut creatures = 0;
+        war_simulation::simulate_wars(world, &step, &mut self.rng);
+        epidemic_simulation::simulate_epidemics(world, &step, &mut self.rng);
+        climate_simulation::simulate_climate(world, &step, &mut self.rng);
 
-        for id in world.sites.iter_ids::<SiteId>() {
+        let mut creatures = 0;
+        let now = world.date;
+
+        if self.sequential {
+            for id in world.sites.iter_ids::<SiteId>() {
+                creatures += world.sites.get(&id).creatures.len();
+                let result = self.simulate_step_site(world, &step, &now, self.rng.clone(), id, &resources);
+                if let Err(msg) = result {
+                    warn!("{msg}");
+                }
+                self.rng.next();
+            }
+        } else {
+            // Sites are prepared in order, their creatures decide what to do in parallel, and the decisions are applied in order again.
+            // Each site has its own rng, so the history is the same regardless of the number of threads.
+            // Unlike the sequential step, every site decides from the world as it was once all sites were prepared,
+            // and bandits, attacks and plots wait until every site is applied
+            let mut site_steps = Vec::new();
+            for id in world.sites.iter_ids::<SiteId>() {
+                creatures += world.sites.get(&id).creatures.len();
+                if let Some(site_step) = self.prepare_site(world, &step, &now, self.rng.clone(), id, &resources) {
+                    site_steps.push(site_step);
+                }
+                self.rng.next();
+            }
 
-            let site = world.sites.get(&id);
-            creatures += site.creatures.len();
-            drop(site);
+            let decisions = Self::decide_sites(world, &step, &now, site_steps, self.threads, &resources);
 
-            let result = self.simulate_step_site(world, &step, &world.date.clone(), self.rng.clone(), &id);
-            if let Err(msg) = result {
-                warn!("{msg}");
+            let mut queued = Vec::new();
+            for site_decisions in decisions {
+                let result = self.apply_site(world, &now, site_decisions, &mut queued, &resources);
+                if let Err(msg) = result {
+                    warn!("{msg}");
+                }
+            }
+            for effects in queued {
+                Self::apply_cross_site(world, &now, effects, &resources);
             }
-
-            self.rng.next();
         }
+        world.history_state = Some(HistoryState {
+            rng: self.rng.seed(),
+            selected_for_cities: self.storyteller.selected_for_cities().clone(),
+        });
+        WorldMetrics::record(world);
         return creatures > 0;
     }
 
-    fn simulate_step_site(&mut self, world: &mut World, step: &Duration, now: &WorldDate, mut rng: Rng, site_id: &SiteId) -> Result<(), Error> {
-
-        let chances = self.storyteller.story_teller_site_chances(site_id, &world, &step);
+    /// Sets how many threads the sites are simulated with. 1 simulates them in the calling thread
+    pub(crate) fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
 
-        let site = world.sites.get_mut(site_id);
+    /// Simulates the sites with the sequential step. Slower, and the history isn't the same as with the passes
+    pub(crate) fn set_sequential(&mut self, sequential: bool) {
+        self.sequential = sequential;
+    }
 
-        let mut resources = site.resources.clone();
+    /// The step the three passes replaced: each site sees what the sites before it did this step, and its effects
+    /// on other sites happen right away. Kept to benchmark the passes against, and to compare their histories
+    fn simulate_step_site(&self, world: &mut World, step: &Duration, now: &WorldDate, rng: Rng, site_id: SiteId, game_resources: &Resources) -> Result<(), Error> {
+        let SiteStep { site_id, mut rng, chances, production } = match self.prepare_site(world, step, now, rng, site_id, game_resources) {
+            Some(site_step) => site_step,
+            None => return Ok(()),
+        };
+        let site_id = &site_id;
 
-        let site_tile = world.map.tile(site.xy.x() as usize, site.xy.y() as usize);
-        
+        let (mut site_resources, was_fed, creatures) = {
+            let site = world.sites.get(site_id);
+            (site.resources, site.site_type.is_settlement() && site.resources.food >= 0., site.creatures.clone())
+        };
+        let mut gathered_materials = Vec::new();
         let mut marriage_pool = Vec::new();
         let mut change_job_pool = Vec::new();
-        let creatures = site.creatures.clone();
-        drop(site);
 
+        for creature_id in creatures.iter() {
+            if world.is_played_creature(creature_id) || world.creatures.get(creature_id).death.is_some() {
+                continue;
+            }
+            let side_effect = {
+                let site = world.sites.get(site_id);
+                let creature = world.creatures.get(creature_id);
+                let plot = creature.supports_plot.map(|plot_id| world.plots.get(&plot_id));
+                let side_effect = CreatureSimulation::simulate_step_creature(step, now, &mut rng, &site, creature_id, &creature, plot.as_deref(), &chances);
+                production.produce(step, site.site_type, &creature, &mut site_resources, &mut gathered_materials, &mut rng, game_resources);
+                side_effect
+            };
+
+            history_trace!("creature_action creature_id:{:?} action:{:?}", creature_id, side_effect);
+
+            let result = Self::apply_side_effect(world, site_id, creature_id, side_effect, &mut rng, &mut marriage_pool, &mut change_job_pool, game_resources);
+            if let Err(str) = result {
+                return Err(Error::new(format!("{str} {:?}", creature_id)));
+            }
+        }
+
+        Self::store_production(world, site_id, now, was_fed, site_resources, gathered_materials);
+        self.simulate_site_life(world, site_id, &chances, &mut rng, game_resources)?;
+        Self::marry(world, now, marriage_pool);
+        Self::change_jobs(world, site_id, now, change_job_pool, &mut rng, game_resources);
+        Ok(())
+    }
+
+    /// Everything that touches the world around the site before its creatures can decide what to do
+    fn prepare_site(&self, world: &mut World, step: &Duration, now: &WorldDate, mut rng: Rng, site_id: SiteId, game_resources: &Resources) -> Option<SiteStep> {
+        if settlement_simulation::fall_to_ruin(world, site_id) {
+            settlement_simulation::loot_ruins(world, site_id, step, &mut rng);
+            return None;
+        }
+        settlement_simulation::levy_food(world, site_id, step);
+
+        let chances = self.storyteller.story_teller_site_chances(&site_id, &world, &step);
+        beast_simulation::simulate_lair(world, site_id, step, &mut rng, game_resources);
+
+        let (site_step, creatures) = {
+            let site = world.sites.get(&site_id);
+            let site_tile = world.map.tile(site.xy.x() as usize, site.xy.y() as usize);
+            let climate = Climate::of(&world.map, &site.xy);
+            let production = SiteProduction {
+                soil_fertility: site_tile.soil_fertility,
+                harvest: climate_simulation::harvest_multiplier(world, &site.xy, now),
+                consumption: climate.food_consumption(Season::of(now)),
+                biome_materials: game_resources.biomes.get_u8(site_tile.region_id).materials.clone(),
+            };
+            (SiteStep { site_id, rng, chances, production }, site.creatures.clone())
+        };
+
+        // Goals can depend on anyone in the world, so they are checked before the sites are split between threads
         for creature_id in creatures.iter() {
             if world.is_played_creature(creature_id) {
                 continue;
             }
-            let site = world.sites.get_mut(site_id);
-            let mut creature = world.creatures.get_mut(creature_id);
+            let goals = std::mem::take(&mut world.creatures.get_mut(creature_id).goals);
+            let goals: Vec<Goal> = goals.into_iter().filter(|goal| !goal.check_completed(creature_id, world)).collect();
+            world.creatures.get_mut(creature_id).goals = goals;
+        }
+        return Some(site_step);
+    }
 
-            // SMELL: Doing things outside of the method because of borrow issues
-            let plot = creature.supports_plot.and_then(|plot_id| Some(world.plots.get(&plot_id)));
-            creature.goals.retain(|goal| {
-                !goal.check_completed(world)
-            });
+    fn decide_sites(world: &mut World, step: &Duration, now: &WorldDate, site_steps: Vec<SiteStep>, threads: usize, game_resources: &Resources) -> Vec<SiteDecisions> {
+        let view = SimulationView {
+            played_creature: world.get_played_creature().copied(),
+            sites: world.sites.values(),
+            creatures: world.creatures.values(),
+            plots: world.plots.values(),
+        };
+        if threads <= 1 || site_steps.len() <= 1 {
+            return site_steps.into_iter().map(|site_step| Self::decide_site(step, now, &view, site_step, game_resources)).collect();
+        }
 
-            let side_effect = CreatureSimulation::simulate_step_creature(step, now, &mut rng, &site, creature_id, &creature, plot, &chances);
+        // Sites are dealt to the threads in turns, so big and small sites even out
+        let mut batches: Vec<Vec<(usize, SiteStep)>> = (0..threads).map(|_| Vec::new()).collect();
+        for (i, site_step) in site_steps.into_iter().enumerate() {
+            batches[i % threads].push((i, site_step));
+        }
+        let mut decisions: Vec<(usize, SiteDecisions)> = std::thread::scope(|scope| {
+            let view = &view;
+            let handles: Vec<_> = batches.into_iter().map(|batch| {
+                scope.spawn(move || {
+                    return batch.into_iter()
+                        .map(|(i, site_step)| (i, Self::decide_site(step, now, view, site_step, game_resources)))
+                        .collect::<Vec<(usize, SiteDecisions)>>()
+                })
+            }).collect();
+            return handles.into_iter().flat_map(|handle| handle.join().expect("Site simulation thread panicked")).collect();
+        });
+        decisions.sort_by_key(|(i, _)| *i);
+        return decisions.into_iter().map(|(_, site_decisions)| site_decisions).collect();
+    }
 
-            // Production and consumption
-            let mut production = creature.profession.base_resource_production();
-            production.food = production.food * site_tile.soil_fertility;
-            resources = production + resources;
-            resources.food -= 1.0;
-            
-            drop(creature);
-            drop(site);
+    /// What each creature of the site does this step, and what they produced. Only reads the world, so it can run in any thread
+    fn decide_site(step: &Duration, now: &WorldDate, view: &SimulationView, site_step: SiteStep, game_resources: &Resources) -> SiteDecisions {
+        let SiteStep { site_id, mut rng, chances, production } = site_step;
+        let site = view.sites[site_id.as_usize()];
+        let mut resources = site.resources.clone();
+        let was_fed = site.site_type.is_settlement() && resources.food >= 0.;
+        let mut gathered_materials = Vec::new();
+        let mut side_effects = Vec::new();
+
+        // Earlier sites might have changed who lives here since the site was prepared
+        for creature_id in site.creatures.iter() {
+            let creature = view.creatures[creature_id.as_usize()];
+            if view.played_creature == Some(*creature_id) || creature.death.is_some() {
+                continue;
+            }
+            let plot = creature.supports_plot.map(|plot_id| view.plots[plot_id.as_usize()]);
+
+            let side_effect = CreatureSimulation::simulate_step_creature(step, now, &mut rng, site, creature_id, creature, plot, &chances);
+            production.produce(step, site.site_type, creature, &mut resources, &mut gathered_materials, &mut rng, game_resources);
+
+            side_effects.push((*creature_id, side_effect));
+        }
+
+        return SiteDecisions { site_id, rng, chances, was_fed, resources, gathered_materials, side_effects }
+    }
+
+    /// Applies what the creatures decided, and simulates the rest of the life of the site.
+    /// Effects that reach other sites are queued, to be applied after every site is done
+    fn apply_site(&self, world: &mut World, now: &WorldDate, site_decisions: SiteDecisions, queued: &mut Vec<CrossSiteEffects>, game_resources: &Resources) -> Result<(), Error> {
+        let SiteDecisions { site_id, mut rng, chances, was_fed, resources: site_resources, gathered_materials, side_effects } = site_decisions;
+        let site_id = &site_id;
+
+        let mut marriage_pool = Vec::new();
+        let mut change_job_pool = Vec::new();
+        let mut cross_site = Vec::new();
 
+        for (creature_id, side_effect) in side_effects {
+            let creature_id = &creature_id;
+            if Self::is_gone(world, site_id, creature_id) {
+                continue;
+            }
             history_trace!("creature_action creature_id:{:?} action:{:?}", creature_id, side_effect);
 
-            let result  = match side_effect {
-                CreatureSideEffect::None => Ok(()),
-                CreatureSideEffect::Death(cause_of_death) => {
-                    world.kill_creature(*creature_id, *site_id, *site_id, cause_of_death);
-                    Ok(())
-                },
-                CreatureSideEffect::HaveChild => world.creature_couple_have_child(*creature_id, site_id, &mut rng),
-                CreatureSideEffect::MoveOutToNewHouse => world.creature_start_new_home_same_site(*creature_id, site_id),
-                CreatureSideEffect::LookForMarriage => {
-                    let creature = world.creatures.get_mut(creature_id);
-                    marriage_pool.push((*creature_id, creature.gender));
-                    Ok(())
-                },
-                CreatureSideEffect::LookForNewJob => {
-                    change_job_pool.push(*creature_id);
-                    Ok(())
-                },
-                CreatureSideEffect::MakeArtifact => {
-                    Self::make_artifact(&creature_id, None, site_id, world, &mut rng);
+            let result = match side_effect {
+                CreatureSideEffect::BecomeBandit | CreatureSideEffect::AttackNearbySites | CreatureSideEffect::ExecutePlot => {
+                    cross_site.push((*creature_id, side_effect));
                     Ok(())
                 },
-                CreatureSideEffect::BecomeBandit => world.creature_leave_for_bandit_camp(*creature_id, *site_id, &mut rng.to_new()),
-                CreatureSideEffect::AttackNearbySites => {
-                    attack_nearby_site(world, &mut rng, *site_id);
-                    Ok(())
-                }
-                CreatureSideEffect::StartPlot(goal) => {
-                    start_plot(world, *creature_id, goal);
-                    Ok(())
-                }
-                CreatureSideEffect::FindSupportersForPlot => {
-                    find_supporters_for_plot(world, *creature_id);
-                    Ok(())
-                }
-                CreatureSideEffect::ExecutePlot => {
-                    execute_plot(world, *site_id, *creature_id, &mut rng);
-                    Ok(())
-                }
+                _ => Self::apply_side_effect(world, site_id, creature_id, side_effect, &mut rng, &mut marriage_pool, &mut change_job_pool, game_resources),
             };
-
             if let Err(str) = result {
                 return Err(Error::new(format!("{str} {:?}", creature_id)));
             }
+        }
+
+        Self::store_production(world, site_id, now, was_fed, site_resources, gathered_materials);
+        self.simulate_site_life(world, site_id, &chances, &mut rng, game_resources)?;
+        Self::change_jobs(world, site_id, now, change_job_pool, &mut rng, game_resources);
+
+        queued.push(CrossSiteEffects { site_id: *site_id, rng, marriage_pool, side_effects: cross_site });
+        Ok(())
+    }
+
+    /// Marriages, bandits leaving and attacks, in the order of the sites
+    fn apply_cross_site(world: &mut World, now: &WorldDate, effects: CrossSiteEffects, game_resources: &Resources) {
+        let CrossSiteEffects { site_id, mut rng, mut marriage_pool, side_effects } = effects;
+
+        for (creature_id, side_effect) in side_effects {
+            if Self::is_gone(world, &site_id, &creature_id) {
+                continue;
+            }
+            let result = Self::apply_cross_site_effect(world, &site_id, &creature_id, side_effect, &mut rng, game_resources);
+            if let Err(msg) = result {
+                warn!("{msg} {:?}", creature_id);
+            }
+        }
+
+        marriage_pool.retain(|(creature_id, _)| world.creatures.get(creature_id).death.is_none());
+        Self::marry(world, now, marriage_pool);
+    }
+
+    /// Applies what a creature decided to do. The effects on other sites happen right away
+    fn apply_side_effect(world: &mut World, site_id: &SiteId, creature_id: &CreatureId, side_effect: CreatureSideEffect, rng: &mut Rng, marriage_pool: &mut Vec<(CreatureId, CreatureGender)>, change_job_pool: &mut Vec<CreatureId>, game_resources: &Resources) -> Result<(), Error> {
+        match side_effect {
+            CreatureSideEffect::None => Ok(()),
+            CreatureSideEffect::Death(cause_of_death) => {
+                world.kill_creature(*creature_id, *site_id, *site_id, cause_of_death);
+                Ok(())
+            },
+            CreatureSideEffect::HaveChild => world.creature_couple_have_child(*creature_id, site_id, rng),
+            CreatureSideEffect::MoveOutToNewHouse => world.creature_start_new_home_same_site(*creature_id, site_id),
+            CreatureSideEffect::LookForMarriage => {
+                let creature = world.creatures.get(creature_id);
+                marriage_pool.push((*creature_id, creature.gender));
+                Ok(())
+            },
+            CreatureSideEffect::LookForNewJob => {
+                change_job_pool.push(*creature_id);
+                Ok(())
+            },
+            CreatureSideEffect::MakeArtifact => {
+                Self::make_artifact(&creature_id, None, site_id, world, rng, game_resources);
+                Ok(())
+            },
+            CreatureSideEffect::CommissionRelic => {
+                let artisan = {
+                    let site = world.sites.get(site_id);
+                    let artisans: Vec<CreatureId> = site.creatures.iter().filter(|id| {
+                        let profession = world.creatures.get(*id).profession;
+                        profession == Profession::Sculptor || profession == Profession::Blacksmith
+                    }).copied().collect();
+                    rng.item(&artisans).copied()
+                };
+                if let Some(artisan) = artisan {
+                    Self::make_artifact(&artisan, Some(creature_id), site_id, world, rng, game_resources);
+                }
+                Ok(())
+            },
+            CreatureSideEffect::StartPlot(goal) => {
+                start_plot(world, *creature_id, goal);
+                Ok(())
+            }
+            CreatureSideEffect::FindSupportersForPlot => {
+                find_supporters_for_plot(world, *site_id, *creature_id);
+                Ok(())
+            }
+            CreatureSideEffect::BecomeBandit | CreatureSideEffect::AttackNearbySites | CreatureSideEffect::ExecutePlot => {
+                Self::apply_cross_site_effect(world, site_id, creature_id, side_effect, rng, game_resources)
+            }
+        }
+    }
 
+    /// Applies what a creature decided to do that reaches other sites
+    fn apply_cross_site_effect(world: &mut World, site_id: &SiteId, creature_id: &CreatureId, side_effect: CreatureSideEffect, rng: &mut Rng, game_resources: &Resources) -> Result<(), Error> {
+        match side_effect {
+            CreatureSideEffect::BecomeBandit => world.creature_leave_for_bandit_camp(*creature_id, *site_id, &mut rng.to_new()),
+            CreatureSideEffect::AttackNearbySites => {
+                attack_nearby_site(world, rng, *site_id, game_resources);
+                Ok(())
+            }
+            CreatureSideEffect::ExecutePlot => {
+                execute_plot(world, *site_id, *creature_id, rng, game_resources);
+                Ok(())
+            }
+            _ => Ok(()),
         }
+    }
 
+    /// Stores what the site produced this step, and records the famine if it ran out of food
+    fn store_production(world: &mut World, site_id: &SiteId, now: &WorldDate, was_fed: bool, site_resources: SiteResources, gathered_materials: Vec<(MaterialId, usize)>) {
+        let famine = was_fed && site_resources.food < 0.;
         {
             let mut site = world.sites.get_mut(site_id);
-            site.resources = resources;
-            self.check_population_peak(now, &mut site);
+            site.resources = site_resources;
+            if let Some(settlement) = site.settlement.as_mut() {
+                for (material_id, count) in gathered_materials {
+                    settlement.add_material(&material_id, count);
+                }
+            }
+            site.update_population_peak(now);
+            site.record_population(now);
         }
+        if famine {
+            history_trace!("famine site_id:{:?}", site_id);
+            world.events.push(Event::Famine { date: *now, site_id: *site_id });
+        }
+    }
 
+    /// Elections, and what the site does as a whole: wars, caravans, faith, plagues, migrations and what it builds
+    fn simulate_site_life(&self, world: &mut World, site_id: &SiteId, chances: &SiteChances, rng: &mut Rng, game_resources: &Resources) -> Result<(), Error> {
         {
             let site = world.sites.get(site_id);
 
@@ -214,25 +521,51 @@ impl HistorySimulation {
             } && site.creatures.len() > 0;
             drop(site);
             if need_election {
-                let site = world.sites.get(site_id);
-                let mut candidates_pool = Vec::new();
-                for creature_id in site.creatures.iter() {
-                    let creature = world.creatures.get(creature_id);
-                    let age = (*now - creature.birth).get_years();
-                    if age > 18 {
-                        candidates_pool.push(creature_id);
-                    }
-                }
-                let new_leader = match candidates_pool.len() {
-                    0 => site.creatures[rng.randu_range(0, site.creatures.len())],
-                    _ => *candidates_pool[rng.randu_range(0, candidates_pool.len())],
-                };     
-                drop(site);
-                world.site_change_leader(site_id, new_leader)?;
+                succession_simulation::succeed_leader(world, site_id, rng, game_resources)?;
             }
             
         }
-        
+
+        if rng.rand_chance(chances.declare_war) {
+            war_simulation::declare_war(world, *site_id, rng, game_resources);
+        }
+
+        if rng.rand_chance(chances.send_caravan) {
+            trade_simulation::send_caravan(world, *site_id, game_resources);
+        }
+
+        religion_simulation::simulate_religion(world, site_id, chances, rng);
+
+        if rng.rand_chance(chances.plague_outbreak) {
+            epidemic_simulation::start_epidemic(world, *site_id, rng);
+        }
+
+        if rng.rand_chance(chances.migrate) {
+            migration_simulation::simulate_migration(world, *site_id, rng, game_resources);
+        }
+
+        if rng.rand_chance(chances.build_road) {
+            road_simulation::simulate_roads(world, *site_id);
+        }
+
+        settlement_simulation::grow_city(world, *site_id, self.storyteller.is_selected_for_city(site_id));
+
+        if rng.rand_chance(chances.build_castle) {
+            settlement_simulation::build_castle(world, *site_id, rng, game_resources);
+        }
+
+        if rng.rand_chance(chances.open_mine) {
+            settlement_simulation::open_mine(world, *site_id, rng, game_resources);
+        }
+
+        if rng.rand_chance(chances.found_monastery) {
+            settlement_simulation::found_monastery(world, *site_id, rng, game_resources);
+        }
+        Ok(())
+    }
+
+    /// Pairs the candidates up, each with the first one of the other gender
+    fn marry(world: &mut World, now: &WorldDate, mut marriage_pool: Vec<(CreatureId, CreatureGender)>) {
         while marriage_pool.len() > 0 {
             let candidate_a = marriage_pool.pop().unwrap();
             let candidate_b = marriage_pool.iter().position(|x| x.1 != candidate_a.1);
@@ -252,20 +585,35 @@ impl HistorySimulation {
                 None => ()
             }
         }
+    }
 
+    fn change_jobs(world: &mut World, site_id: &SiteId, now: &WorldDate, change_job_pool: Vec<CreatureId>, rng: &mut Rng, game_resources: &Resources) {
         for creature_id in change_job_pool {
             let mut creature = world.creatures.get_mut(&creature_id);
             let site = world.sites.get(site_id);
-            let profession = site.select_new_profession(&mut rng);
+            let profession = site.select_new_profession(rng, game_resources);
             creature.profession = profession;
             drop(creature);
             drop(site);
             world.events.push(Event::CreatureProfessionChange { date: now.clone(), creature_id: creature_id, new_profession: profession });
         }
-        Ok(())
     }
 
-    fn make_artifact(artisan_id: &CreatureId, comissioneer_id: Option<&CreatureId>, site_id: &SiteId, world: &mut World, rng: &mut Rng) {
+    /// Whether the creature died or left the site since it decided what to do, because of something another site did
+    fn is_gone(world: &World, site_id: &SiteId, creature_id: &CreatureId) -> bool {
+        return world.creatures.get(creature_id).death.is_some() || !world.sites.get(site_id).creatures.contains(creature_id)
+    }
+
+    fn make_artifact(artisan_id: &CreatureId, comissioneer_id: Option<&CreatureId>, site_id: &SiteId, world: &mut World, rng: &mut Rng, game_resources: &Resources) {
+        // Priests commission holy relics to the patron of their faith
+        let dedicated_to = comissioneer_id.and_then(|comissioneer_id| {
+            let comissioneer = world.creatures.get(comissioneer_id);
+            match (comissioneer.profession, comissioneer.faith) {
+                (Profession::Priest, Some(faith)) => Some(world.religions.get(&faith).patron),
+                _ => None,
+            }
+        });
+
         let mut artisan = world.creatures.get_mut(artisan_id);
         let mut site = world.sites.get_mut(site_id);
         let item = match artisan.profession {
@@ -284,16 +632,26 @@ impl HistorySimulation {
                     quality = ItemQuality::Legendary
                 }
 
-                let item = ItemFactory::weapon(rng, &resources())
+                let culture = artisan.lineage.map(|lineage_id| world.lineages.get(&lineage_id).culture);
+
+                // Without the materials in stock, there's no weapon
+                ItemFactory::weapon(rng, game_resources)
                     .quality(quality)
+                    .culture(culture)
                     .material_pool(site.settlement.as_mut().and_then(|sett| Some(&mut sett.material_stock)))
                     .named()
-                    .make();
-                Some(item)
+                    .try_make()
+                    .map(|mut item| {
+                        item.dedicated_to = dedicated_to;
+                        item
+                    })
             },
             Profession::Sculptor => {
-                if let Some(comissioneer_id) = comissioneer_id {
-                    Some(ArtifactFactory::create_statue(rng, &resources(), *comissioneer_id, &world))
+                let material_pool = site.settlement.as_mut().and_then(|sett| Some(&mut sett.material_stock));
+                if let Some(deity_id) = dedicated_to {
+                    ArtifactFactory::create_holy_statue(rng, game_resources, deity_id, material_pool)
+                } else if let Some(comissioneer_id) = comissioneer_id {
+                    ArtifactFactory::create_statue(rng, game_resources, *comissioneer_id, &world, material_pool)
                 } else {
                     None
                 }
@@ -332,17 +690,14 @@ impl HistorySimulation {
         }
     }
 
-    fn check_population_peak(&self, now: &WorldDate, site: &mut Site) {
-        if site.creatures.len() >= site.population_peak.1 as usize {
-            site.population_peak = (now.year(), site.creatures.len() as u32)
-        }
-    }
-
     fn find_site_suitable_pos(&self, rng: &mut Rng, world: &World) -> Option<Coord2> {
         for _ in 0..100 {
             let x = rng.randu_range(3, world.map.size.x() - 3);
             let y = rng.randu_range(3, world.map.size.y() - 3);
             let candidate = Coord2::xy(x as i32, y as i32);
+            if let Some(Water::Lake) | Some(Water::Ocean) = world.map.water_at(candidate.to_vec2i()) {
+                continue;
+            }
             let too_close = world.sites.iter().any(|site| {
                 let site = site.borrow();
                 let site_xy: Coord2 = site.xy.into();
@@ -0,0 +1,243 @@
+use crate::{commons::rng::Rng, history_trace, resources::resources::Resources, world::{creature::CreatureId, history_sim::{epidemic_simulation::site_epidemic, world_ops}, site::{MigrationCause, SiteId, SiteType, StructureType}, world::World}, Event};
+
+/// How far families will travel to find a better home, in a straight line
+const MIGRATION_RADIUS_SQRD: f32 = 15.*15.;
+/// How many people a site can feed on perfectly fertile land
+const CARRYING_CAPACITY: f32 = 40.;
+/// How much better a site has to look for a family to move there
+const MIN_APPEAL_GAIN: f32 = 0.5;
+/// Chance that a family with nowhere better to go sets out to found a village of their own
+const PIONEER_CHANCE: f32 = 0.3;
+/// Youngest age at which someone can lead their family away
+const MIN_HEAD_AGE: i32 = 16;
+
+/// The hardship of the site drives a family away, to a better village nearby, to the ruins of one, or to found one on fertile land
+pub(crate) fn simulate_migration(world: &mut World, site_id: SiteId, rng: &mut Rng, resources: &Resources) {
+    let cause = match site_hardship(world, &site_id) {
+        Some(cause) => cause,
+        None => return,
+    };
+    let household = match pick_household(world, &site_id, rng) {
+        Some(household) => household,
+        None => return,
+    };
+    let head = household[0];
+
+    let destination = match find_destination(world, &site_id) {
+        Some(destination) => {
+            world_ops::move_household(world, &site_id, &destination, &household);
+            destination
+        },
+        None => {
+            if !rng.rand_chance(PIONEER_CHANCE) {
+                return;
+            }
+            match find_ruins(world, &site_id) {
+                // Old walls are easier to settle than the wilds
+                Some(ruins_id) => {
+                    world_ops::resettle_ruins(world, &site_id, &ruins_id, &household, resources);
+                    world.events.push(Event::RuinsResettled { date: world.date, creature_id: head, site_id, new_site_id: ruins_id });
+                    ruins_id
+                },
+                None => {
+                    let pos = match world_ops::search_new_site_pos(world, rng) {
+                        Ok(pos) => pos,
+                        Err(_) => return,
+                    };
+                    let new_site_id = match world_ops::found_site_at(world, site_id, head, vec!(household.clone()), pos, SiteType::Village, rng, resources) {
+                        Ok(new_site_id) => new_site_id,
+                        Err(_) => return,
+                    };
+                    world.events.push(Event::SettlementFounded { date: world.date, creature_id: head, site_id, new_site_id });
+                    new_site_id
+                }
+            }
+        }
+    };
+
+    history_trace!("migrated creature_id:{:?} from:{:?} to:{:?} cause:{:?}", head, site_id, destination, cause);
+    world.events.push(Event::Migrated { date: world.date, creature_id: head, site_id, new_site_id: destination, cause, household });
+}
+
+/// The worst of what the people of the site endure, if anything
+pub(crate) fn site_hardship(world: &World, site_id: &SiteId) -> Option<MigrationCause> {
+    let site = world.sites.get(site_id);
+    if !site.site_type.is_settlement() || site.settlement.is_none() {
+        return None;
+    }
+    if world.site_ongoing_war(site_id).is_some() {
+        return Some(MigrationCause::War);
+    }
+    if site_epidemic(world, site_id).is_some() {
+        return Some(MigrationCause::Plague);
+    }
+    if site.resources.food < 0. {
+        return Some(MigrationCause::Famine);
+    }
+    let fertility = world.map.tile(site.xy.x() as usize, site.xy.y() as usize).soil_fertility;
+    if site.creatures.len() as f32 > fertility * CARRYING_CAPACITY {
+        return Some(MigrationCause::Overcrowding);
+    }
+    return None;
+}
+
+/// How good a place the site looks to live in. Plenty of food draws people in, hardship drives them away.
+pub(crate) fn site_appeal(world: &World, site_id: &SiteId) -> f32 {
+    let food_per_capita = {
+        let site = world.sites.get(site_id);
+        (site.resources.food / (site.creatures.len().max(1) as f32)).clamp(-1., 2.)
+    };
+    let penalty = match site_hardship(world, site_id) {
+        Some(MigrationCause::War) | Some(MigrationCause::Plague) => 2.,
+        Some(MigrationCause::Famine) | Some(MigrationCause::Overcrowding) => 1.,
+        None => 0.,
+    };
+    return food_per_capita - penalty
+}
+
+/// A family that is free to leave: no one in it rules the site. The eldest comes first.
+fn pick_household(world: &World, site_id: &SiteId, rng: &mut Rng) -> Option<Vec<CreatureId>> {
+    let site = world.sites.get(site_id);
+    let leader = site.settlement.as_ref().and_then(|settlement| settlement.leader);
+    let households: Vec<Vec<CreatureId>> = site.structures.iter()
+        .filter(|structure| structure.get_type() == &StructureType::House && structure.occupant_count() > 0)
+        .map(|structure| structure.occupants().copied().collect::<Vec<CreatureId>>())
+        .filter(|household| household.iter().all(|id| Some(*id) != leader && !world.is_played_creature(id)))
+        .collect();
+    let mut household = rng.item(&households)?.clone();
+    household.sort_by_key(|creature_id| world.creatures.get(creature_id).birth);
+    let head_age = (world.date - world.creatures.get(&household[0]).birth).get_years();
+    if head_age < MIN_HEAD_AGE {
+        return None;
+    }
+    return Some(household)
+}
+
+/// The most appealing village nearby, if it's better enough than home
+fn find_destination(world: &mut World, site_id: &SiteId) -> Option<SiteId> {
+    let (xy, appeal) = (world.sites.get(site_id).xy, site_appeal(world, site_id));
+    let enemy = world.site_ongoing_war(site_id).map(|war_id| world.wars.get(&war_id).enemy_of(site_id));
+
+    let mut candidates = Vec::new();
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if &other_id == site_id || Some(other_id) == enemy {
+            continue;
+        }
+        let other = other.borrow();
+        // Families move into towns, not into castles, mines or monasteries
+        if !matches!(other.site_type, SiteType::Village | SiteType::City) || other.settlement.is_none() || other.creatures.len() == 0 || other.xy.dist_squared(&xy) > MIGRATION_RADIUS_SQRD {
+            continue;
+        }
+        drop(other);
+        let other_appeal = site_appeal(world, &other_id);
+        if other_appeal >= appeal + MIN_APPEAL_GAIN {
+            candidates.push((other_id, other_appeal));
+        }
+    }
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    for (other_id, _) in candidates {
+        if world.trade_route_cost(site_id, &other_id).is_some() {
+            return Some(other_id);
+        }
+    }
+    return None;
+}
+
+/// The closest ruins nearby, that the family could make a home of again
+fn find_ruins(world: &World, site_id: &SiteId) -> Option<SiteId> {
+    let xy = world.sites.get(site_id).xy;
+    let mut closest: Option<(SiteId, f32)> = None;
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        let other = other.borrow();
+        if other.site_type != SiteType::Ruins {
+            continue;
+        }
+        let dist = other.xy.dist_squared(&xy);
+        if dist <= MIGRATION_RADIUS_SQRD && closest.map(|(_, closest_dist)| dist < closest_dist).unwrap_or(true) {
+            closest = Some((other_id, dist));
+        }
+    }
+    return closest.map(|(ruins_id, _)| ruins_id);
+}
+
+#[cfg(test)]
+mod tests_migration {
+    use crate::{commons::id_vec::Id, resources::culture::CultureId, world::{date::WorldDate, site::{SettlementComponent, Structure}, world::fixture::{SiteBuilder, WorldFixture}}};
+    use math::Vec2i;
+
+    use super::*;
+
+    #[test]
+    fn test_family_flees_famine() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        let origin_id = SiteId::mock(0);
+        {
+            let mut origin = fixture.world.sites.get_mut(&origin_id);
+            origin.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture: CultureId::mock(0) });
+            origin.resources.food = -5.;
+            origin.creatures = vec!(fixture.creature_a1, fixture.creature_a2, fixture.creature_a3);
+            let mut house = Structure::new(StructureType::House);
+            house.add_ocuppant(fixture.creature_a1);
+            house.add_ocuppant(fixture.creature_a2);
+            origin.structures.push(house);
+        }
+        let destination_id: SiteId = fixture.world.sites.add(SiteBuilder::new(SiteType::Village)
+            .xy(Vec2i(5, 5))
+            .creatures(vec!(fixture.creature_a4))
+            .food(20.)
+            .settlement(CultureId::mock(0))
+            .build());
+
+        assert_eq!(site_hardship(&fixture.world, &origin_id), Some(MigrationCause::Famine));
+        simulate_migration(&mut fixture.world, origin_id, &mut Rng::seeded("migration"), &resources);
+
+        assert_eq!(fixture.world.sites.get(&origin_id).creatures, vec!(fixture.creature_a3));
+        let destination = fixture.world.sites.get(&destination_id);
+        assert_eq!(destination.creatures.len(), 3);
+        assert_eq!(destination.population_peak, (30, 3));
+        assert!(destination.structure_occupied_by(&fixture.creature_a2).is_some());
+        drop(destination);
+        assert!(matches!(fixture.world.events.last(), Some(Event::Migrated { cause: MigrationCause::Famine, .. })));
+    }
+
+    #[test]
+    fn test_family_resettles_ruins() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        let origin_id = SiteId::mock(0);
+        {
+            let mut origin = fixture.world.sites.get_mut(&origin_id);
+            origin.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture: CultureId::mock(0) });
+            origin.resources.food = -5.;
+            origin.creatures = vec!(fixture.creature_a1, fixture.creature_a2, fixture.creature_a3, fixture.creature_a4);
+            let mut house = Structure::new(StructureType::House);
+            house.add_ocuppant(fixture.creature_a1);
+            house.add_ocuppant(fixture.creature_a2);
+            origin.structures.push(house);
+        }
+        let ruins_id: SiteId = fixture.world.sites.add(SiteBuilder::new(SiteType::Ruins)
+            .xy(Vec2i(5, 5))
+            .settlement(CultureId::mock(0))
+            .build());
+
+        // Families only set out to the ruins some of the time
+        let mut rng = Rng::seeded("resettle");
+        while fixture.world.sites.get(&ruins_id).site_type == SiteType::Ruins {
+            simulate_migration(&mut fixture.world, origin_id, &mut rng, &resources);
+        }
+
+        let ruins = fixture.world.sites.get(&ruins_id);
+        assert_eq!(ruins.site_type, SiteType::Village);
+        assert_eq!(ruins.creatures, vec!(fixture.creature_a1, fixture.creature_a2));
+        assert_eq!(ruins.settlement.as_ref().and_then(|settlement| settlement.leader), Some(fixture.creature_a1));
+        drop(ruins);
+        assert!(fixture.world.events.iter().any(|event| matches!(event, Event::RuinsResettled { .. })));
+    }
+
+}
@@ -1,6 +1,16 @@
 pub(crate) mod battle_simulator;
+pub(crate) mod beast_simulation;
+pub(crate) mod climate_simulation;
 pub(crate) mod creature_simulation;
+pub(crate) mod epidemic_simulation;
 pub(crate) mod factories;
 pub(crate) mod history_simulation;
+pub(crate) mod migration_simulation;
+pub(crate) mod religion_simulation;
+pub(crate) mod road_simulation;
+pub(crate) mod settlement_simulation;
 pub(crate) mod storyteller;
+pub(crate) mod succession_simulation;
+pub(crate) mod trade_simulation;
+pub(crate) mod war_simulation;
 pub(crate) mod world_ops;
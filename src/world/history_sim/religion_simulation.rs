@@ -0,0 +1,223 @@
+use crate::{commons::rng::Rng, history_trace, resources::{culture::CultureId, resources::Resources}, world::{creature::{CreatureId, Profession}, history_sim::storyteller::SiteChances, religion::{Deity, DeityDomain, DeityId, Religion, ReligionId}, site::{SiteId, SiteType, Structure, StructureType}, world::World}, Event};
+
+/// How many gods each culture worships
+const PANTHEON_SIZE: usize = 4;
+/// Youngest age at which someone can choose their own faith
+const MIN_CONVERSION_AGE: i32 = 12;
+/// Opinion a heretic and the priest that denounced them lose of each other
+const HERESY_OPINION: i8 = -40;
+
+/// The religion the people of a culture are born into. Its pantheon is created the first time it's needed.
+pub(crate) fn culture_religion(world: &mut World, culture_id: CultureId, rng: &mut Rng, resources: &Resources) -> ReligionId {
+    let existing = world.religions.iter_id_val::<ReligionId>().find(|(_, religion)| {
+        let religion = religion.borrow();
+        religion.culture == culture_id && religion.parent.is_none()
+    });
+    if let Some((religion_id, _)) = existing {
+        return religion_id
+    }
+
+    let culture = resources.cultures.get(&culture_id);
+    let domains = rng.shuffle(DeityDomain::ALL.to_vec());
+    let mut pantheon = Vec::new();
+    for (i, domain) in domains.into_iter().take(PANTHEON_SIZE).enumerate() {
+        let deity = Deity::new(culture_id, &culture, domain, &rng.derive(i));
+        pantheon.push(world.deities.add::<DeityId>(deity));
+    }
+    rng.next();
+
+    let religion = Religion {
+        name: format!("{} faith", culture.name),
+        culture: culture_id,
+        patron: pantheon[0],
+        pantheon,
+        founded: world.date,
+        parent: None,
+    };
+    let religion_id = world.religions.add::<ReligionId>(religion);
+    history_trace!("religion_founded religion_id:{:?} culture_id:{:?}", religion_id, culture_id);
+    return religion_id
+}
+
+pub(crate) fn simulate_religion(world: &mut World, site_id: &SiteId, chances: &SiteChances, rng: &mut Rng) {
+    let priests: Vec<(CreatureId, ReligionId)> = {
+        let site = world.sites.get(site_id);
+        site.creatures.iter().filter_map(|creature_id| {
+            let creature = world.creatures.get(creature_id);
+            match (creature.profession, creature.faith) {
+                (Profession::Priest, Some(faith)) => Some((*creature_id, faith)),
+                _ => None,
+            }
+        }).collect()
+    };
+
+    for (priest_id, faith) in priests.iter() {
+        if rng.rand_chance(chances.preach) {
+            preach(world, site_id, *priest_id, *faith, rng);
+        }
+        if rng.rand_chance(chances.religious_schism) {
+            start_schism(world, site_id, *priest_id, *faith, rng);
+        }
+        if rng.rand_chance(chances.denounce_heresy) {
+            denounce_heresy(world, site_id, *priest_id, *faith, rng);
+        }
+    }
+
+    if let Some((_, faith)) = priests.first() {
+        if rng.rand_chance(chances.build_temple) {
+            build_temple(world, site_id, *faith);
+        }
+    }
+}
+
+/// The priest tries to convert someone of another faith
+fn preach(world: &mut World, site_id: &SiteId, priest_id: CreatureId, faith: ReligionId, rng: &mut Rng) {
+    let candidates: Vec<CreatureId> = {
+        let site = world.sites.get(site_id);
+        site.creatures.iter().filter(|creature_id| {
+            if world.is_played_creature(creature_id) {
+                return false;
+            }
+            let creature = world.creatures.get(*creature_id);
+            let age = (world.date - creature.birth).get_years();
+            // Other priests won't be swayed
+            age >= MIN_CONVERSION_AGE && creature.faith != Some(faith) && creature.profession != Profession::Priest
+        }).copied().collect()
+    };
+    if let Some(creature_id) = rng.item(&candidates) {
+        world.creatures.get_mut(creature_id).faith = Some(faith);
+        world.events.push(Event::CreatureConverted { date: world.date, creature_id: *creature_id, priest_id, religion_id: faith });
+    }
+}
+
+/// The priest exalts another god of the pantheon, and founds a new religion with the faithful that follow them
+fn start_schism(world: &mut World, site_id: &SiteId, priest_id: CreatureId, faith: ReligionId, rng: &mut Rng) {
+    let religion = world.religions.get(&faith).clone();
+    let candidates: Vec<DeityId> = religion.pantheon.iter().filter(|deity_id| **deity_id != religion.patron).copied().collect();
+    let patron = match rng.item(&candidates) {
+        Some(patron) => *patron,
+        None => return,
+    };
+
+    let name = {
+        let deity = world.deities.get(&patron);
+        match rng.randu_range(0, 3) {
+            0 => format!("Cult of {}", deity.name),
+            1 => format!("Church of {}", deity.name),
+            _ => format!("Way of {}", deity.name),
+        }
+    };
+    let new_religion_id = world.religions.add::<ReligionId>(Religion {
+        name,
+        culture: religion.culture,
+        pantheon: religion.pantheon.clone(),
+        patron,
+        founded: world.date,
+        parent: Some(faith),
+    });
+    history_trace!("religion_schism religion_id:{:?} new_religion_id:{:?}", faith, new_religion_id);
+
+    // The priest's family and friends follow them
+    let followers: Vec<CreatureId> = {
+        let site = world.sites.get(site_id);
+        let priest = world.creatures.get(&priest_id);
+        site.creatures.iter().filter(|creature_id| {
+            let creature = world.creatures.get(*creature_id);
+            if creature.faith != Some(faith) || world.is_played_creature(creature_id) {
+                return false;
+            }
+            **creature_id == priest_id
+                || priest.spouse == Some(**creature_id)
+                || priest.relationship_find(creature_id).map(|relationship| relationship.friend_or_better()).unwrap_or(false)
+        }).copied().collect()
+    };
+    for creature_id in followers {
+        world.creatures.get_mut(&creature_id).faith = Some(new_religion_id);
+    }
+
+    world.events.push(Event::ReligiousSchism { date: world.date, creature_id: priest_id, religion_id: faith, new_religion_id });
+}
+
+/// The priest finds a follower of a religion that broke away from theirs, or that theirs broke away from
+fn denounce_heresy(world: &mut World, site_id: &SiteId, priest_id: CreatureId, faith: ReligionId, rng: &mut Rng) {
+    let candidates: Vec<(CreatureId, ReligionId)> = {
+        let site = world.sites.get(site_id);
+        let religion = world.religions.get(&faith);
+        site.creatures.iter().filter_map(|creature_id| {
+            let creature = world.creatures.get(creature_id);
+            let other_faith = creature.faith?;
+            let other = world.religions.get(&other_faith);
+            match religion.is_schism_of(&faith, &other_faith, &other) {
+                true => Some((*creature_id, other_faith)),
+                false => None,
+            }
+        }).collect()
+    };
+    let (heretic_id, heretic_faith) = match rng.item(&candidates) {
+        Some(heretic) => *heretic,
+        None => return,
+    };
+
+    {
+        let mut priest = world.creatures.get_mut(&priest_id);
+        let mut heretic = world.creatures.get_mut(&heretic_id);
+        priest.relationship_find_mut_or_insert(&priest_id, heretic_id, &heretic).add_opinion(HERESY_OPINION);
+        heretic.relationship_find_mut_or_insert(&heretic_id, priest_id, &priest).add_opinion(HERESY_OPINION);
+    }
+
+    world.events.push(Event::HeresyDeclared { date: world.date, creature_id: priest_id, heretic_id, religion_id: heretic_faith });
+}
+
+fn build_temple(world: &mut World, site_id: &SiteId, faith: ReligionId) {
+    {
+        let mut site = world.sites.get_mut(site_id);
+        if !matches!(site.site_type, SiteType::Village | SiteType::City) || site.structures.iter().any(|structure| structure.get_type() == &StructureType::Temple) {
+            return;
+        }
+        site.structures.push(Structure::new(StructureType::Temple));
+    }
+    world.events.push(Event::TempleBuilt { date: world.date, site_id: *site_id, religion_id: faith });
+}
+
+#[cfg(test)]
+mod tests_religion {
+    use crate::{commons::id_vec::Id, world::world::fixture::WorldFixture};
+
+    use super::*;
+
+    #[test]
+    fn test_culture_religion_is_created_once() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let mut rng = Rng::seeded("religion");
+        let culture_id = resources.cultures.id_of("culture:default");
+
+        let religion_id = culture_religion(&mut fixture.world, culture_id, &mut rng, &resources);
+
+        assert_eq!(culture_religion(&mut fixture.world, culture_id, &mut rng, &resources), religion_id);
+        assert_eq!(fixture.world.religions.len(), 1);
+        assert_eq!(fixture.world.religions.get(&religion_id).pantheon.len(), PANTHEON_SIZE);
+    }
+
+    #[test]
+    fn test_schism_takes_followers() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        let mut rng = Rng::seeded("religion");
+        let faith = culture_religion(&mut fixture.world, resources.cultures.id_of("culture:default"), &mut rng, &resources);
+        for creature_id in [fixture.creature_a1, fixture.creature_a2, fixture.creature_a3] {
+            fixture.world.creatures.get_mut(&creature_id).faith = Some(faith);
+        }
+        fixture.world.creatures.get_mut(&fixture.creature_a1).spouse = Some(fixture.creature_a2);
+
+        start_schism(&mut fixture.world, &SiteId::mock(0), fixture.creature_a1, faith, &mut rng);
+
+        let new_faith = fixture.world.creatures.get(&fixture.creature_a1).faith;
+        assert_ne!(new_faith, Some(faith));
+        assert_eq!(fixture.world.creatures.get(&fixture.creature_a2).faith, new_faith);
+        assert_eq!(fixture.world.creatures.get(&fixture.creature_a3).faith, Some(faith));
+    }
+
+}
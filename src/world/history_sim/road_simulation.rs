@@ -0,0 +1,133 @@
+use crate::{history_trace, world::{road::{Road, RoadId, RoadKind}, site::SiteId, world::World}, Event};
+
+/// How far apart two villages can be, in a straight line, for a road to link them
+const ROAD_RADIUS_SQRD: f32 = 12.*12.;
+/// Villages smaller than this don't bother building roads
+const MIN_ROAD_POPULATION: usize = 3;
+/// How much traffic a trail needs before it's paved...
+const PAVE_TRAFFIC: f32 = 20.;
+/// ...and how many people have to live at both of its ends
+const PAVE_POPULATION: usize = 60;
+/// Traffic a caravan adds to the road it travels
+pub(crate) const CARAVAN_TRAFFIC: f32 = 1.;
+
+/// The site paves its busy trails, and clears a trail to the closest village it isn't linked to yet
+pub(crate) fn simulate_roads(world: &mut World, site_id: SiteId) {
+    let (xy, population) = {
+        let site = world.sites.get(&site_id);
+        if !site.site_type.is_settlement() || site.settlement.is_none() {
+            return;
+        }
+        (site.xy, site.creatures.len())
+    };
+    pave_roads(world, &site_id);
+    if population < MIN_ROAD_POPULATION {
+        return;
+    }
+
+    // No roads to the enemy
+    let enemy = world.site_ongoing_war(&site_id).map(|war_id| world.wars.get(&war_id).enemy_of(&site_id));
+    let mut neighbours = Vec::new();
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if other_id == site_id || Some(other_id) == enemy {
+            continue;
+        }
+        let other = other.borrow();
+        if !other.site_type.is_settlement() || other.settlement.is_none() || other.creatures.len() == 0 {
+            continue;
+        }
+        let dist = other.xy.dist_squared(&xy);
+        if dist <= ROAD_RADIUS_SQRD {
+            neighbours.push((other_id, other.xy, dist));
+        }
+    }
+    neighbours.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    for (other_id, other_xy, _) in neighbours {
+        if world.road_between(&site_id, &other_id).is_some() {
+            continue;
+        }
+        let path = match world.map.travel_path(xy, other_xy) {
+            Some(path) => path,
+            None => continue,
+        };
+        let road_id = world.roads.add::<RoadId>(Road::new(site_id, other_id, path, world.date));
+        history_trace!("road_built road_id:{:?} site_id:{:?} to:{:?}", road_id, site_id, other_id);
+        world.events.push(Event::RoadBuilt { date: world.date, site_id, to: other_id });
+        return;
+    }
+}
+
+/// Travellers wear the road between the two sites, if there's one
+pub(crate) fn add_traffic(world: &mut World, a: &SiteId, b: &SiteId, traffic: f32) {
+    if let Some(road_id) = world.road_between(a, b) {
+        world.roads.get_mut(&road_id).traffic += traffic;
+    }
+}
+
+fn pave_roads(world: &mut World, site_id: &SiteId) {
+    let busy: Vec<(RoadId, SiteId)> = world.roads.iter_id_val::<RoadId>()
+        .filter_map(|(road_id, road)| {
+            let road = road.borrow();
+            if !road.touches(site_id) || road.kind != RoadKind::Trail || road.traffic < PAVE_TRAFFIC {
+                return None;
+            }
+            let population = world.sites.get(&road.site_a).creatures.len() + world.sites.get(&road.site_b).creatures.len();
+            if population < PAVE_POPULATION {
+                return None;
+            }
+            let other = match &road.site_a == site_id {
+                true => road.site_b,
+                false => road.site_a,
+            };
+            return Some((road_id, other))
+        })
+        .collect();
+    for (road_id, other_id) in busy {
+        world.roads.get_mut(&road_id).kind = RoadKind::Paved;
+        history_trace!("road_paved road_id:{:?}", road_id);
+        world.events.push(Event::RoadPaved { date: world.date, site_id: *site_id, to: other_id });
+    }
+}
+
+#[cfg(test)]
+mod tests_road {
+    use crate::{commons::id_vec::Id, resources::culture::CultureId, world::{site::{SettlementComponent, SiteType}, world::fixture::{SiteBuilder, WorldFixture}}};
+    use math::Vec2i;
+
+    use super::*;
+
+    #[test]
+    fn test_neighbours_build_a_road() {
+        let mut fixture = WorldFixture::new();
+        let origin_id = SiteId::mock(0);
+        {
+            let mut origin = fixture.world.sites.get_mut(&origin_id);
+            origin.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture: CultureId::mock(0) });
+            origin.creatures = vec!(fixture.creature_a1, fixture.creature_a2, fixture.creature_a3);
+        }
+        let neighbour_id: SiteId = fixture.world.sites.add(SiteBuilder::new(SiteType::Village)
+            .xy(Vec2i(5, 1))
+            .creatures(vec!(fixture.creature_a4))
+            .settlement(CultureId::mock(0))
+            .build());
+        assert_eq!(fixture.world.trade_route_cost(&origin_id, &neighbour_id), Some(4.));
+
+        simulate_roads(&mut fixture.world, origin_id);
+        // Only one road between the same sites
+        simulate_roads(&mut fixture.world, origin_id);
+
+        assert_eq!(fixture.world.roads.len(), 1);
+        let road_id = fixture.world.road_between(&neighbour_id, &origin_id).unwrap();
+        let road = fixture.world.roads.get(&road_id);
+        assert_eq!(road.kind, RoadKind::Trail);
+        assert_eq!(road.path.first(), Some(&Vec2i(1, 1)));
+        assert_eq!(road.path.last(), Some(&Vec2i(5, 1)));
+        assert_eq!(road.directions_at(&Vec2i(2, 1)), vec!(Vec2i(-1, 0), Vec2i(1, 0)));
+        drop(road);
+        // Travel is cheaper over the road
+        assert!(fixture.world.trade_route_cost(&origin_id, &neighbour_id).unwrap() < 4.);
+        assert!(matches!(fixture.world.events.last(), Some(Event::RoadBuilt { .. })));
+    }
+
+}
@@ -0,0 +1,356 @@
+use math::Vec2i;
+
+use crate::{commons::{bitmask::bitmask_get, rng::Rng}, history_trace, resources::{material::MAT_TAG_METAL, resources::Resources}, world::{creature::{CreatureId, Profession}, date::Duration, history_sim::world_ops, site::{SiteId, SiteType, Structure, StructureType}, world::World}, Event};
+
+/// Villages picked by the storyteller become cities once they're this big
+const CITY_POPULATION: usize = 40;
+/// A city builds a new district for every this many people
+const DISTRICT_POPULATION: usize = 20;
+/// The districts a city builds, in order
+const DISTRICTS: [StructureType; 2] = [StructureType::Market, StructureType::Barracks];
+/// Settlements need to be this big to afford a castle
+const CASTLE_POPULATION: usize = 30;
+/// How far from the settlement it guards a castle is raised
+const CASTLE_RADIUS: i32 = 4;
+/// How far the lands of a castle reach. There's only one castle in them
+const CASTLE_LANDS_RADIUS_SQRD: f32 = 8.*8.;
+/// How many guards man a new castle
+const CASTLE_GARRISON: usize = 4;
+/// Food a castle levies each year for each of its people, from the settlements in its lands
+const CASTLE_LEVY: f32 = 1.;
+/// How far from home the miners look for ore
+const MINE_RADIUS: i32 = 6;
+/// How many households of miners it takes to open a mine
+const MINE_HOUSEHOLDS: usize = 2;
+/// How many unmarried priests it takes to found a monastery
+const MONASTERY_PRIESTS: usize = 2;
+/// How far from the world the monks withdraw
+const MONASTERY_RADIUS: i32 = 10;
+/// Food the monks of a monastery grow in its gardens, on top of their work
+pub(crate) const MONASTERY_GARDEN_FOOD: f32 = 1.5;
+/// How much more ore a miner digs at a mine than at home
+pub(crate) const MINE_YIELD: f32 = 3.;
+/// Food a miner at a mine gets in trade for their ore
+pub(crate) const ORE_TRADE_FOOD: f32 = 1.5;
+/// How far bandits will go to pick through ruins
+const LOOT_RADIUS_SQRD: f32 = 10.*10.;
+/// Chance per year that bandits nearby carry off something left in the ruins
+const LOOT_CHANCE: f32 = 0.3;
+
+/// Settlements with no one left fall to ruin. Returns true if the site is in ruins
+pub(crate) fn fall_to_ruin(world: &mut World, site_id: SiteId) -> bool {
+    {
+        let mut site = world.sites.get_mut(&site_id);
+        if site.site_type == SiteType::Ruins {
+            return true;
+        }
+        if !site.site_type.is_settlement() || site.creatures.len() > 0 {
+            return false;
+        }
+        site.site_type = SiteType::Ruins;
+        if let Some(settlement) = site.settlement.as_mut() {
+            settlement.leader = None;
+        }
+    }
+    history_trace!("site_ruined site_id:{:?}", site_id);
+    world.events.push(Event::SiteRuined { date: world.date, site_id });
+    return true;
+}
+
+/// Bandits nearby pick through the ruins, and carry off the artifacts left behind to their camp
+pub(crate) fn loot_ruins(world: &mut World, site_id: SiteId, step: &Duration, rng: &mut Rng) {
+    let xy = {
+        let site = world.sites.get(&site_id);
+        if site.site_type != SiteType::Ruins || site.artifacts.len() == 0 {
+            return;
+        }
+        site.xy
+    };
+    if !rng.rand_chance(LOOT_CHANCE * step.percentage_of_year()) {
+        return;
+    }
+    let mut looters = None;
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        let other = other.borrow();
+        if other.site_type == SiteType::BanditCamp && other.creatures.len() > 0 && other.xy.dist_squared(&xy) <= LOOT_RADIUS_SQRD {
+            looters = Some(other_id);
+            break;
+        }
+    }
+    let looters = match looters {
+        Some(looters) => looters,
+        None => return,
+    };
+    let item = {
+        let mut site = world.sites.get_mut(&site_id);
+        let i = rng.randu_range(0, site.artifacts.len());
+        site.artifacts.remove(i)
+    };
+    world.sites.get_mut(&looters).artifacts.push(item);
+    history_trace!("ruins_looted site_id:{:?} looters:{:?} item:{:?}", site_id, looters, item);
+    world.events.push(Event::RuinsLooted { date: world.date, site_id, looters, item });
+}
+
+/// A village picked to be a city becomes one once it's big enough. Cities build a new district as they grow
+pub(crate) fn grow_city(world: &mut World, site_id: SiteId, selected_for_city: bool) {
+    let became_city = {
+        let mut site = world.sites.get_mut(&site_id);
+        if site.site_type == SiteType::Village && selected_for_city && site.creatures.len() >= CITY_POPULATION {
+            site.site_type = SiteType::City;
+            true
+        } else {
+            false
+        }
+    };
+    if became_city {
+        history_trace!("became_city site_id:{:?}", site_id);
+        world.events.push(Event::BecameCity { date: world.date, site_id });
+    }
+
+    let district = {
+        let mut site = world.sites.get_mut(&site_id);
+        if site.site_type != SiteType::City {
+            return;
+        }
+        let districts = site.structures.iter().filter(|structure| DISTRICTS.contains(structure.get_type())).count();
+        if districts >= site.creatures.len() / DISTRICT_POPULATION {
+            return;
+        }
+        let district = DISTRICTS[districts % DISTRICTS.len()];
+        site.structures.push(Structure::new(district));
+        district
+    };
+    history_trace!("district_built site_id:{:?} district:{:?}", site_id, district);
+    world.events.push(Event::DistrictBuilt { date: world.date, site_id, district });
+}
+
+/// The garrison of a castle doesn't farm. The castle takes its food from the settlements in its lands
+pub(crate) fn levy_food(world: &mut World, site_id: SiteId, step: &Duration) {
+    let (xy, mut needed) = {
+        let site = world.sites.get(&site_id);
+        if site.site_type != SiteType::Castle {
+            return;
+        }
+        (site.xy, site.creatures.len() as f32 * CASTLE_LEVY * step.percentage_of_year())
+    };
+    let mut levied = 0.;
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if needed <= 0. {
+            break;
+        }
+        if other_id == site_id {
+            continue;
+        }
+        let mut other = other.borrow_mut();
+        if !matches!(other.site_type, SiteType::Village | SiteType::City) || other.resources.food <= 0. || other.xy.dist_squared(&xy) > CASTLE_LANDS_RADIUS_SQRD {
+            continue;
+        }
+        let share = needed.min(other.resources.food);
+        other.resources.food -= share;
+        levied += share;
+        needed -= share;
+    }
+    world.sites.get_mut(&site_id).resources.food += levied;
+}
+
+/// The lord of a large settlement raises a castle nearby, held by a kinsman and manned by the guards of the settlement
+pub(crate) fn build_castle(world: &mut World, site_id: SiteId, rng: &mut Rng, resources: &Resources) {
+    let (xy, households) = {
+        let site = world.sites.get(&site_id);
+        if !matches!(site.site_type, SiteType::Village | SiteType::City) || site.creatures.len() < CASTLE_POPULATION {
+            return;
+        }
+        let leader_id = match site.settlement.as_ref().and_then(|settlement| settlement.leader) {
+            Some(leader_id) => leader_id,
+            None => return,
+        };
+        let lineage = match world.creatures.get(&leader_id).lineage {
+            Some(lineage) => lineage,
+            None => return,
+        };
+        let castellan = site.creatures.iter().find(|id| {
+            let creature = world.creatures.get(*id);
+            **id != leader_id && creature.lineage == Some(lineage) && (world.date - creature.birth).get_years() >= 18
+        });
+        let castellan = match castellan {
+            Some(castellan) => *castellan,
+            None => return,
+        };
+        let mut household = vec!(castellan);
+        if let Some(spouse) = world.creatures.get(&castellan).spouse {
+            if site.creatures.contains(&spouse) && spouse != leader_id {
+                household.push(spouse);
+            }
+        }
+        let mut households = vec!(household.clone());
+        for creature_id in site.creatures.iter() {
+            if households.len() > CASTLE_GARRISON {
+                break;
+            }
+            if !household.contains(creature_id) && world.creatures.get(creature_id).profession == Profession::Guard {
+                households.push(vec!(*creature_id));
+            }
+        }
+        (site.xy, households)
+    };
+
+    let castle_nearby = world.sites.iter().any(|site| {
+        let site = site.borrow();
+        site.site_type == SiteType::Castle && site.creatures.len() > 0 && site.xy.dist_squared(&xy) <= CASTLE_LANDS_RADIUS_SQRD
+    });
+    if castle_nearby {
+        return;
+    }
+    let pos = match world_ops::search_site_pos_near(world, xy, CASTLE_RADIUS, rng) {
+        Ok(pos) => pos,
+        Err(_) => return,
+    };
+    found(world, site_id, households, pos, SiteType::Castle, rng, resources);
+}
+
+/// Miners leave their settlement to open a mine where there's metal to be found
+pub(crate) fn open_mine(world: &mut World, site_id: SiteId, rng: &mut Rng, resources: &Resources) {
+    let (xy, households) = {
+        let site = world.sites.get(&site_id);
+        if !matches!(site.site_type, SiteType::Village | SiteType::City) {
+            return;
+        }
+        let mut households: Vec<Vec<CreatureId>> = Vec::new();
+        for creature_id in site.creatures.iter() {
+            if households.len() >= MINE_HOUSEHOLDS {
+                break;
+            }
+            if world.creatures.get(creature_id).profession != Profession::Miner || households.iter().any(|household| household.contains(creature_id)) {
+                continue;
+            }
+            // Whoever lives in the townhall stays with the leader
+            match site.structure_occupied_by(creature_id) {
+                Some(house) if house.get_type() == &StructureType::House => households.push(house.occupants().copied().collect()),
+                _ => (),
+            }
+        }
+        if households.len() < MINE_HOUSEHOLDS {
+            return;
+        }
+        (site.xy, households)
+    };
+
+    for _ in 0..10 {
+        let pos = match world_ops::search_site_pos_near(world, xy, MINE_RADIUS, rng) {
+            Ok(pos) => pos,
+            Err(_) => return,
+        };
+        let tile = world.map.tile(pos.x() as usize, pos.y() as usize);
+        let has_metal = resources.biomes.get_u8(tile.region_id).materials.iter().any(|(material_id, _)| {
+            bitmask_get(resources.materials.get(material_id).tags_bitmask, MAT_TAG_METAL)
+        });
+        if has_metal {
+            found(world, site_id, households, pos, SiteType::Mine, rng, resources);
+            return;
+        }
+    }
+}
+
+/// The unmarried priests of a settlement withdraw from the world to found a monastery
+pub(crate) fn found_monastery(world: &mut World, site_id: SiteId, rng: &mut Rng, resources: &Resources) {
+    let (xy, households) = {
+        let site = world.sites.get(&site_id);
+        if !matches!(site.site_type, SiteType::Village | SiteType::City) {
+            return;
+        }
+        let households: Vec<Vec<CreatureId>> = site.creatures.iter()
+            .filter(|id| {
+                let creature = world.creatures.get(*id);
+                creature.profession == Profession::Priest && creature.spouse.is_none()
+            })
+            .map(|id| vec!(*id))
+            .collect();
+        if households.len() < MONASTERY_PRIESTS {
+            return;
+        }
+        (site.xy, households)
+    };
+    let pos = match world_ops::search_site_pos_near(world, xy, MONASTERY_RADIUS, rng) {
+        Ok(pos) => pos,
+        Err(_) => return,
+    };
+    found(world, site_id, households, pos, SiteType::Monastery, rng, resources);
+}
+
+/// The head of the first household leads the new site
+fn found(world: &mut World, site_id: SiteId, households: Vec<Vec<CreatureId>>, pos: Vec2i, site_type: SiteType, rng: &mut Rng, resources: &Resources) {
+    let founder_id = households[0][0];
+    if let Ok(new_site_id) = world_ops::found_site_at(world, site_id, founder_id, households, pos, site_type, rng, resources) {
+        history_trace!("site_founded site_id:{:?} new_site_id:{:?} site_type:{:?}", site_id, new_site_id, site_type);
+        world.events.push(Event::SiteFounded { date: world.date, creature_id: founder_id, site_id, new_site_id, site_type });
+    }
+}
+
+#[cfg(test)]
+mod tests_settlement {
+    use crate::{commons::id_vec::Id, resources::culture::CultureId, world::{creature::CreatureId, item::ItemId, site::SettlementComponent, world::fixture::{SiteBuilder, WorldFixture}}};
+
+    use super::*;
+
+    #[test]
+    fn test_selected_village_becomes_city() {
+        let mut fixture = WorldFixture::new();
+        let site_id = SiteId::mock(0);
+        {
+            let mut site = fixture.world.sites.get_mut(&site_id);
+            site.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture: CultureId::mock(0) });
+            site.creatures = (0..CITY_POPULATION).map(CreatureId::mock).collect();
+        }
+
+        grow_city(&mut fixture.world, site_id, false);
+        assert_eq!(fixture.world.sites.get(&site_id).site_type, SiteType::Village);
+
+        grow_city(&mut fixture.world, site_id, true);
+        grow_city(&mut fixture.world, site_id, true);
+        let site = fixture.world.sites.get(&site_id);
+        assert_eq!(site.site_type, SiteType::City);
+        assert_eq!(site.structures.iter().filter(|structure| structure.get_type() == &StructureType::Market).count(), 1);
+        assert_eq!(site.structures.iter().filter(|structure| structure.get_type() == &StructureType::Barracks).count(), 1);
+        drop(site);
+        assert!(matches!(fixture.world.events.last(), Some(Event::DistrictBuilt { .. })));
+    }
+
+    #[test]
+    fn test_depopulated_site_falls_to_ruin() {
+        let mut fixture = WorldFixture::new();
+        let site_id = SiteId::mock(0);
+        fixture.world.sites.get_mut(&site_id).creatures = vec!(fixture.creature_a1);
+        assert!(!fall_to_ruin(&mut fixture.world, site_id));
+
+        fixture.world.sites.get_mut(&site_id).creatures.clear();
+        assert!(fall_to_ruin(&mut fixture.world, site_id));
+        assert_eq!(fixture.world.sites.get(&site_id).site_type, SiteType::Ruins);
+        assert!(matches!(fixture.world.events.last(), Some(Event::SiteRuined { .. })));
+    }
+
+    #[test]
+    fn test_bandits_loot_ruins() {
+        let mut fixture = WorldFixture::new();
+        let site_id = SiteId::mock(0);
+        let item = ItemId::mock(0);
+        {
+            let mut site = fixture.world.sites.get_mut(&site_id);
+            site.creatures.clear();
+            site.artifacts = vec!(item);
+        }
+        assert!(fall_to_ruin(&mut fixture.world, site_id));
+        let camp_id: SiteId = fixture.world.sites.add(SiteBuilder::new(SiteType::BanditCamp)
+            .xy(Vec2i(4, 4))
+            .creatures(vec!(fixture.creature_a1))
+            .build());
+
+        let mut rng = Rng::seeded("loot");
+        for _ in 0..50 {
+            loot_ruins(&mut fixture.world, site_id, &Duration::years(1), &mut rng);
+        }
+        assert_eq!(fixture.world.sites.get(&site_id).artifacts.len(), 0);
+        assert_eq!(fixture.world.sites.get(&camp_id).artifacts, vec!(item));
+        assert!(matches!(fixture.world.events.last(), Some(Event::RuinsLooted { .. })));
+    }
+
+}
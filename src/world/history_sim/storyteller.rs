@@ -1,27 +1,43 @@
-use crate::{commons::{interpolate::lerp, rng::Rng}, world::{date::Duration, history_generator::WorldGenerationParameters, site::{SiteId, SiteType}, world::World}};
+use std::{collections::BTreeMap, fs};
+
+use serde::Deserialize;
+
+use crate::{commons::{interpolate::lerp, rng::Rng}, warn, world::{date::Duration, history_generator::WorldGenerationParameters, site::{SiteId, SiteType}, world::World}};
+
+/// Where the storyteller profiles are, one TOML file each
+const PROFILES_DIRECTORY: &str = "assets/storytellers";
+/// Used when the profile of a world can't be loaded
+const DEFAULT_PROFILE: &str = include_str!("../../../assets/storytellers/balanced.toml");
 
 pub(crate) struct Storyteller {
     params: WorldGenerationParameters,
+    profile: StorytellerProfile,
     selected_for_cities: Vec<SiteId>
 }
 
 impl Storyteller {
 
     pub(crate) fn new(params: WorldGenerationParameters) -> Self {
+        let profile = StorytellerProfile::load(&params.storyteller).unwrap_or_else(|err| {
+            warn!("{err}, using the balanced storyteller");
+            StorytellerProfile::parse(DEFAULT_PROFILE).expect("The balanced storyteller is invalid")
+        });
         Self {
             params,
+            profile,
             selected_for_cities: Vec::new(),
         }
     }
 
     pub(crate) fn global_chances(&mut self, rng: &mut Rng, world: &World, delta_time: &Duration) -> GlobalChances {
-        let mut chances = BASE_GLOBAL_CHANCES.clone();
+        let base_chances = &self.profile.global_chances;
+        let mut chances = base_chances.clone();
 
         // Check city count, village count, and maybe promote village to city
         let mut villages = 0;
         for site_id in world.sites.iter_ids::<SiteId>() {
             let site = world.sites.get(&site_id);
-            if let SiteType::Village = site.site_type {
+            if let SiteType::Village | SiteType::City = site.site_type {
                 if site.creatures.len() == 0 {
                     self.selected_for_cities.retain(|id| id != &site_id);
                     continue;
@@ -36,24 +52,40 @@ impl Storyteller {
 
 
         let villages_divergence = villages as f32 / self.params.st_village_count as f32;
-        if villages_divergence > 1.2 {
-            chances.spawn_village *= 0.;
-        } else if villages_divergence < 0.8 {
-            chances.spawn_village *= 2.;
+        for correction in self.profile.global_corrections.iter() {
+            for (chance, multiplier) in correction.multipliers(villages_divergence) {
+                if let Some(chance) = chances.chance_mut(chance) {
+                    *chance *= multiplier;
+                }
+            }
         }
 
         let factor = delta_time.percentage_of_year();
-        return lerp_global_chances(&BASE_GLOBAL_CHANCES, &chances, self.params.st_strength).scale(factor)
+        return lerp_global_chances(base_chances, &chances, self.params.st_strength).scale(factor)
+    }
+
+    pub(crate) fn is_selected_for_city(&self, site_id: &SiteId) -> bool {
+        return self.selected_for_cities.contains(site_id)
+    }
+
+    pub(crate) fn selected_for_cities(&self) -> &Vec<SiteId> {
+        return &self.selected_for_cities
+    }
+
+    /// Picks up the villages selected for cities before the world was saved
+    pub(crate) fn restore(&mut self, selected_for_cities: Vec<SiteId>) {
+        self.selected_for_cities = selected_for_cities;
     }
 
     pub(crate) fn story_teller_site_chances(&self, site_id: &SiteId, world: &World, delta_time: &Duration) -> SiteChances {
-        let mut chances = BASE_SITE_CHANCES.clone();
-        
+        let base_chances = &self.profile.site_chances;
+        let mut chances = base_chances.clone();
+
         let site = world.sites.get(site_id);
 
-        if site.site_type == SiteType::Village {
+        if let SiteType::Village | SiteType::City = site.site_type {
 
-            let pop_goal = match self.selected_for_cities.contains(site_id) {
+            let pop_goal = match self.selected_for_cities.contains(site_id) || site.site_type == SiteType::City {
                 true => self.params.st_city_population,
                 false => self.params.st_village_population,
             };
@@ -63,36 +95,176 @@ impl Storyteller {
             }).count();
 
             // Balances site population
-            let population_divergence = site.creatures.len() as f32 / pop_goal as f32;
-            if population_divergence < 0.8 {
-                chances.have_child = chances.have_child * 1.5;
-            } else if population_divergence > 1.5 {
-                chances.have_child = chances.have_child * 0.;
-            } else if population_divergence > 1.2 {
-                chances.have_child = chances.have_child * 0.5;
+            for correction in self.profile.site_corrections.iter() {
+                let measured = match correction.measure {
+                    Measure::Population => site.creatures.len(),
+                    Measure::Adults => adults,
+                    Measure::Villages => continue,
+                };
+                for (chance, multiplier) in correction.multipliers(measured as f32 / pop_goal as f32) {
+                    if let Some(chance) = chances.chance_mut(chance) {
+                        *chance *= multiplier;
+                    }
+                }
             }
+        }
 
-            // Balances site population
-            let population_divergence = adults as f32 / pop_goal as f32;
-            if population_divergence < 0.8 {
-                chances.disease_death = chances.disease_death * 0.;
-                chances.leave_for_bandits = chances.leave_for_bandits * 0.2;
-            } else if population_divergence > 1.5 {
-                chances.disease_death = chances.disease_death * 1.2;
-                chances.leave_for_bandits = chances.leave_for_bandits * 1.1;
-            } else if population_divergence > 1.2 {
-                chances.disease_death = chances.disease_death * 1.2;
-                chances.leave_for_bandits = chances.leave_for_bandits * 1.0;
-            }
+        let mut chances = lerp_site_chances(base_chances, &chances, self.params.st_strength);
+        // Famine isn't something the storyteller balances. The hungry have fewer children and fall sick more often
+        if site.resources.food < 0. {
+            chances.have_child = chances.have_child * 0.3;
+            chances.disease_death = chances.disease_death * 3.;
+        }
+        match site.site_type {
+            // Cities are where the merchants are
+            SiteType::City => {
+                chances.send_caravan = chances.send_caravan * 2.;
+            },
+            // Monks don't marry nor have children, they pray, copy books and make relics
+            SiteType::Monastery => {
+                chances.have_child = 0.;
+                chances.marry = 0.;
+                chances.make_inspired_artifact = chances.make_inspired_artifact * 4.;
+                chances.commission_relic = chances.commission_relic * 2.;
+                chances.preach = chances.preach * 2.;
+            },
+            _ => (),
         }
 
         let factor = delta_time.percentage_of_year();
-        return lerp_site_chances(&BASE_SITE_CHANCES, &chances, self.params.st_strength).scale(factor)
+        return chances.scale(factor)
+    }
+
+}
+
+/// How a storyteller balances the world: the chances of things happening, the population it aims for,
+/// and how the chances are corrected when the world strays from it
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct StorytellerProfile {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) targets: PopulationTargets,
+    global_chances: GlobalChances,
+    site_chances: SiteChances,
+    #[serde(default)]
+    global_corrections: Vec<Correction>,
+    #[serde(default)]
+    site_corrections: Vec<Correction>,
+}
+
+impl StorytellerProfile {
+
+    /// Loads a profile from the assets, by file name without extension, eg. "grim"
+    pub(crate) fn load(name: &str) -> Result<Self, String> {
+        let path = format!("{PROFILES_DIRECTORY}/{name}.toml");
+        let toml = fs::read_to_string(&path).map_err(|err| format!("Can't read storyteller {path}: {err}"))?;
+        return Self::parse(&toml).map_err(|err| format!("Invalid storyteller {path}: {err}"))
+    }
+
+    /// Names of the profiles in the assets, sorted
+    pub(crate) fn available() -> Vec<String> {
+        let mut names: Vec<String> = match fs::read_dir(PROFILES_DIRECTORY) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == "toml"))
+                .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        names.sort();
+        return names
+    }
+
+    fn parse(toml: &str) -> Result<Self, String> {
+        let profile: StorytellerProfile = toml::from_str(toml).map_err(|err| err.to_string())?;
+        let mut global_chances = profile.global_chances.clone();
+        for correction in profile.global_corrections.iter() {
+            if correction.measure != Measure::Villages {
+                return Err(format!("Global corrections can only measure villages, not {:?}", correction.measure));
+            }
+            for chance in correction.chances() {
+                global_chances.chance_mut(chance).ok_or(format!("Unknown global chance {chance}"))?;
+            }
+        }
+        let mut site_chances = profile.site_chances.clone();
+        for correction in profile.site_corrections.iter() {
+            if correction.measure == Measure::Villages {
+                return Err(String::from("Site corrections can only measure population or adults"));
+            }
+            for chance in correction.chances() {
+                site_chances.chance_mut(chance).ok_or(format!("Unknown site chance {chance}"))?;
+            }
+        }
+        return Ok(profile)
+    }
+
+    /// Makes the profile's population targets the targets of the world
+    pub(crate) fn apply_targets(&self, params: &mut WorldGenerationParameters) {
+        params.st_city_count = self.targets.city_count;
+        params.st_city_population = self.targets.city_population;
+        params.st_village_count = self.targets.village_count;
+        params.st_village_population = self.targets.village_population;
     }
 
 }
 
-#[derive(Clone)]
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PopulationTargets {
+    pub(crate) city_count: u16,
+    pub(crate) city_population: u16,
+    pub(crate) village_count: u16,
+    pub(crate) village_population: u16,
+}
+
+/// Multiplies chances when a measure of the world strays from its target
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Correction {
+    measure: Measure,
+    /// Checked in order, only the first that matches applies
+    steps: Vec<CorrectionStep>,
+}
+
+impl Correction {
+
+    /// The multipliers for a measure divided by its target
+    fn multipliers(&self, divergence: f32) -> impl Iterator<Item = (&str, f32)> {
+        let step = self.steps.iter().find(|step| {
+            step.below.is_none_or(|below| divergence < below) && step.above.is_none_or(|above| divergence > above)
+        });
+        return step.into_iter().flat_map(|step| step.multiply.iter().map(|(chance, multiplier)| (chance.as_str(), *multiplier)))
+    }
+
+    fn chances(&self) -> impl Iterator<Item = &str> {
+        return self.steps.iter().flat_map(|step| step.multiply.keys().map(|chance| chance.as_str()))
+    }
+
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CorrectionStep {
+    below: Option<f32>,
+    above: Option<f32>,
+    multiply: BTreeMap<String, f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Measure {
+    /// Villages in the world, over the target village count
+    Villages,
+    /// Creatures in a site, over its target population
+    Population,
+    /// Adults in a site, over its target population
+    Adults,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct SiteChances {
     /// Just a base multiplier to be used in more complex rules
     pub(crate) base_multiplier: f32,
@@ -111,9 +283,65 @@ pub(crate) struct SiteChances {
     pub(crate) work_on_plot: f32,
     /// Chance that a great beast will attack a nearby settlement
     pub(crate) great_beast_hunt: f32,
+    /// Chance that the leader of a starving or aggrieved village will declare war on a neighbour
+    pub(crate) declare_war: f32,
+    /// Chance that a settlement will send a caravan to trade with a neighbour
+    pub(crate) send_caravan: f32,
+    /// Chance that a priest will try to convert someone of another faith
+    pub(crate) preach: f32,
+    /// Chance that a priest will break away from their religion and found a new one
+    pub(crate) religious_schism: f32,
+    /// Chance that a priest will denounce a follower of a schismatic religion
+    pub(crate) denounce_heresy: f32,
+    /// Chance that a settlement with priests will build a temple
+    pub(crate) build_temple: f32,
+    /// Chance that a priest will commission a holy relic to an artisan
+    pub(crate) commission_relic: f32,
+    /// Chance that a new disease breaks out in the site
+    pub(crate) plague_outbreak: f32,
+    /// Chance that a family leaves a site that is going through hardship
+    pub(crate) migrate: f32,
+    /// Chance that a settlement works on its roads
+    pub(crate) build_road: f32,
+    /// Chance that the lord of a large settlement raises a castle nearby
+    pub(crate) build_castle: f32,
+    /// Chance that the miners of a settlement leave to open a mine in the hills
+    pub(crate) open_mine: f32,
+    /// Chance that the priests of a settlement withdraw to found a monastery
+    pub(crate) found_monastery: f32,
 }
 
 impl SiteChances {
+
+    fn chance_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "base_multiplier" => Some(&mut self.base_multiplier),
+            "disease_death" => Some(&mut self.disease_death),
+            "leave_for_bandits" => Some(&mut self.leave_for_bandits),
+            "have_child" => Some(&mut self.have_child),
+            "marry" => Some(&mut self.marry),
+            "change_job" => Some(&mut self.change_job),
+            "make_inspired_artifact" => Some(&mut self.make_inspired_artifact),
+            "start_plot" => Some(&mut self.start_plot),
+            "work_on_plot" => Some(&mut self.work_on_plot),
+            "great_beast_hunt" => Some(&mut self.great_beast_hunt),
+            "declare_war" => Some(&mut self.declare_war),
+            "send_caravan" => Some(&mut self.send_caravan),
+            "preach" => Some(&mut self.preach),
+            "religious_schism" => Some(&mut self.religious_schism),
+            "denounce_heresy" => Some(&mut self.denounce_heresy),
+            "build_temple" => Some(&mut self.build_temple),
+            "commission_relic" => Some(&mut self.commission_relic),
+            "plague_outbreak" => Some(&mut self.plague_outbreak),
+            "migrate" => Some(&mut self.migrate),
+            "build_road" => Some(&mut self.build_road),
+            "build_castle" => Some(&mut self.build_castle),
+            "open_mine" => Some(&mut self.open_mine),
+            "found_monastery" => Some(&mut self.found_monastery),
+            _ => None,
+        }
+    }
+
     fn scale(&self, factor: f32) -> Self {
         Self {
             base_multiplier: self.base_multiplier * factor,
@@ -126,23 +354,23 @@ impl SiteChances {
             start_plot: self.start_plot * factor,
             work_on_plot: self.work_on_plot * factor,
             great_beast_hunt: self.great_beast_hunt * factor,
+            declare_war: self.declare_war * factor,
+            send_caravan: self.send_caravan * factor,
+            preach: self.preach * factor,
+            religious_schism: self.religious_schism * factor,
+            denounce_heresy: self.denounce_heresy * factor,
+            build_temple: self.build_temple * factor,
+            commission_relic: self.commission_relic * factor,
+            plague_outbreak: self.plague_outbreak * factor,
+            migrate: self.migrate * factor,
+            build_road: self.build_road * factor,
+            build_castle: self.build_castle * factor,
+            open_mine: self.open_mine * factor,
+            found_monastery: self.found_monastery * factor,
         }
     }
 }
 
-const BASE_SITE_CHANCES: SiteChances = SiteChances {
-    base_multiplier: 1.,
-    disease_death: 0.0015,
-    have_child: 0.6,
-    marry: 0.8,
-    leave_for_bandits: 0.01,
-    change_job: 0.005,
-    make_inspired_artifact: 0.005,
-    start_plot: 0.3,
-    work_on_plot: 0.9,
-    great_beast_hunt: 0.01
-};
-
 fn lerp_site_chances(a: &SiteChances, b: &SiteChances, strength: f32) -> SiteChances {
     SiteChances {
         base_multiplier: lerp(a.base_multiplier as f64, b.base_multiplier as f64, strength as f64) as f32,
@@ -155,37 +383,88 @@ fn lerp_site_chances(a: &SiteChances, b: &SiteChances, strength: f32) -> SiteCha
         start_plot: lerp(a.start_plot as f64, b.start_plot as f64, strength as f64) as f32,
         work_on_plot: lerp(a.work_on_plot as f64, b.work_on_plot as f64, strength as f64) as f32,
         great_beast_hunt: lerp(a.great_beast_hunt as f64, b.great_beast_hunt as f64, strength as f64) as f32,
+        declare_war: lerp(a.declare_war as f64, b.declare_war as f64, strength as f64) as f32,
+        send_caravan: lerp(a.send_caravan as f64, b.send_caravan as f64, strength as f64) as f32,
+        preach: lerp(a.preach as f64, b.preach as f64, strength as f64) as f32,
+        religious_schism: lerp(a.religious_schism as f64, b.religious_schism as f64, strength as f64) as f32,
+        denounce_heresy: lerp(a.denounce_heresy as f64, b.denounce_heresy as f64, strength as f64) as f32,
+        build_temple: lerp(a.build_temple as f64, b.build_temple as f64, strength as f64) as f32,
+        commission_relic: lerp(a.commission_relic as f64, b.commission_relic as f64, strength as f64) as f32,
+        plague_outbreak: lerp(a.plague_outbreak as f64, b.plague_outbreak as f64, strength as f64) as f32,
+        migrate: lerp(a.migrate as f64, b.migrate as f64, strength as f64) as f32,
+        build_road: lerp(a.build_road as f64, b.build_road as f64, strength as f64) as f32,
+        build_castle: lerp(a.build_castle as f64, b.build_castle as f64, strength as f64) as f32,
+        open_mine: lerp(a.open_mine as f64, b.open_mine as f64, strength as f64) as f32,
+        found_monastery: lerp(a.found_monastery as f64, b.found_monastery as f64, strength as f64) as f32,
     }
 }
 
 
-#[derive(Clone)]
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct GlobalChances {
-    pub(crate) spawn_varningr: f32,
+    /// Multiplies the chance of each kind of great beast appearing
+    pub(crate) spawn_great_beast: f32,
     pub(crate) spawn_wolf_pack: f32,
     pub(crate) spawn_village: f32,
 }
 
 impl GlobalChances {
+
+    fn chance_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "spawn_great_beast" => Some(&mut self.spawn_great_beast),
+            "spawn_wolf_pack" => Some(&mut self.spawn_wolf_pack),
+            "spawn_village" => Some(&mut self.spawn_village),
+            _ => None,
+        }
+    }
+
     fn scale(&self, factor: f32) -> Self {
         Self {
-            spawn_varningr: self.spawn_varningr * factor,
+            spawn_great_beast: self.spawn_great_beast * factor,
             spawn_wolf_pack: self.spawn_wolf_pack * factor,
             spawn_village: self.spawn_village * factor
         }
     }
 }
 
-const BASE_GLOBAL_CHANCES: GlobalChances = GlobalChances {
-    spawn_varningr: 0.05,
-    spawn_wolf_pack: 0.2,
-    spawn_village: 0.01,
-};
-
 fn lerp_global_chances(a: &GlobalChances, b: &GlobalChances, strength: f32) -> GlobalChances {
     GlobalChances {
-        spawn_varningr: lerp(a.spawn_varningr as f64, b.spawn_varningr as f64, strength as f64) as f32,
+        spawn_great_beast: lerp(a.spawn_great_beast as f64, b.spawn_great_beast as f64, strength as f64) as f32,
         spawn_wolf_pack: lerp(a.spawn_wolf_pack as f64, b.spawn_wolf_pack as f64, strength as f64) as f32,
         spawn_village: lerp(a.spawn_village as f64, b.spawn_village as f64, strength as f64) as f32,
     }
 }
+
+#[cfg(test)]
+mod tests_storyteller {
+    use super::*;
+
+    #[test]
+    fn test_profiles_load() {
+        for name in ["balanced", "peaceful", "grim", "chaotic"] {
+            let profile = StorytellerProfile::load(name).unwrap();
+            assert!(!profile.name.is_empty());
+        }
+        assert!(StorytellerProfile::load("missing").is_err());
+        assert_eq!(StorytellerProfile::available(), vec!("balanced", "chaotic", "grim", "peaceful"));
+    }
+
+    #[test]
+    fn test_correction_steps() {
+        let profile = StorytellerProfile::parse(DEFAULT_PROFILE).unwrap();
+        let population = &profile.site_corrections[0];
+        assert_eq!(population.multipliers(0.5).collect::<Vec<_>>(), vec!(("have_child", 1.5)));
+        assert_eq!(population.multipliers(1.).count(), 0);
+        assert_eq!(population.multipliers(1.3).collect::<Vec<_>>(), vec!(("have_child", 0.5)));
+        assert_eq!(population.multipliers(2.).collect::<Vec<_>>(), vec!(("have_child", 0.)));
+    }
+
+    #[test]
+    fn test_unknown_chance_is_rejected() {
+        let toml = DEFAULT_PROFILE.replace("multiply = { have_child = 1.5 }", "multiply = { have_children = 1.5 }");
+        assert!(StorytellerProfile::parse(&toml).is_err());
+    }
+
+}
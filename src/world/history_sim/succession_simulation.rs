@@ -0,0 +1,213 @@
+use crate::{commons::{rng::Rng, xp_table::xp_to_level}, history_trace, resources::{culture::SuccessionLaw, resources::Resources}, world::{creature::{CreatureId, Goal}, site::SiteId, world::World}, Event};
+
+/// Youngest age at which someone can be elected, or claim a title
+const MIN_RULER_AGE: i32 = 18;
+/// How many of the site's most powerful people claim the title in a succession crisis
+const MAX_CLAIMANTS: usize = 3;
+
+/// Chooses who rules the site after its leader is gone, following the succession law of its culture
+pub(crate) fn succeed_leader(world: &mut World, site_id: &SiteId, rng: &mut Rng, resources: &Resources) -> Result<(), &'static str> {
+    let (previous, law) = {
+        let site = world.sites.get(site_id);
+        let settlement = site.settlement.as_ref().ok_or("No succession should happen with no settlement")?;
+        let culture = resources.cultures.get(&site.culture().ok_or("Settlement with no culture")?);
+        (settlement.leader, culture.succession)
+    };
+
+    // The first leader of a settlement is always chosen by its people
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return elect(world, site_id, rng, resources),
+    };
+
+    if law == SuccessionLaw::Elective {
+        return elect(world, site_id, rng, resources)
+    }
+
+    match find_heir(world, site_id, &previous, law) {
+        Some(heir) => {
+            world.site_install_leader(site_id, heir, resources)?;
+            history_trace!("title_inherited site_id:{:?} creature_id:{:?} from:{:?}", site_id, heir, previous);
+            world.events.push(Event::TitleInherited { date: world.date, site_id: *site_id, creature_id: heir, from: previous });
+            return Ok(())
+        },
+        None => return succession_crisis(world, site_id, rng, resources),
+    }
+}
+
+fn elect(world: &mut World, site_id: &SiteId, rng: &mut Rng, resources: &Resources) -> Result<(), &'static str> {
+    let new_leader = {
+        let site = world.sites.get(site_id);
+        let candidates = adults(world, site_id);
+        match candidates.len() {
+            0 => site.creatures[rng.randu_range(0, site.creatures.len())],
+            _ => candidates[rng.randu_range(0, candidates.len())],
+        }
+    };
+    return world.site_change_leader(site_id, new_leader, resources)
+}
+
+/// The rightful heir of the previous leader, if there's any living in the site
+fn find_heir(world: &World, site_id: &SiteId, previous: &CreatureId, law: SuccessionLaw) -> Option<CreatureId> {
+    let site = world.sites.get(site_id);
+    let previous = world.creatures.get(previous);
+    match law {
+        SuccessionLaw::Elective => None,
+        // The eldest adult child. Without one, the adults of the site fight for it
+        SuccessionLaw::Primogeniture => {
+            return previous.offspring.iter()
+                .filter(|child_id| {
+                    let child = world.creatures.get(*child_id);
+                    site.creatures.contains(child_id) && child.death.is_none() && (world.date - child.birth).get_years() >= MIN_RULER_AGE
+                })
+                .min_by_key(|child_id| world.creatures.get(*child_id).birth)
+                .copied()
+        },
+        // The most experienced adult of the family, eldest first
+        SuccessionLaw::Tanistry => {
+            let lineage = previous.lineage?;
+            return adults(world, site_id).into_iter()
+                .filter(|creature_id| world.creatures.get(creature_id).lineage == Some(lineage))
+                .max_by_key(|creature_id| {
+                    let creature = world.creatures.get(creature_id);
+                    (creature.experience, std::cmp::Reverse(creature.birth))
+                })
+        },
+    }
+}
+
+/// With no heir, the most powerful people of the site fight for the title. The losers won't forget it.
+fn succession_crisis(world: &mut World, site_id: &SiteId, rng: &mut Rng, resources: &Resources) -> Result<(), &'static str> {
+    let mut claimants = rng.shuffle(adults(world, site_id));
+    if claimants.len() == 0 {
+        return elect(world, site_id, rng, resources)
+    }
+    claimants.sort_by_key(|creature_id| std::cmp::Reverse(world.creatures.get(creature_id).experience));
+    claimants.truncate(MAX_CLAIMANTS);
+
+    // The stronger the claimant, the likelier they prevail
+    let total: f32 = claimants.iter().map(|creature_id| xp_to_level(world.creatures.get(creature_id).experience) as f32).sum();
+    let mut roll = rng.randf() * total;
+    let mut winner = claimants[0];
+    for creature_id in claimants.iter() {
+        let level = xp_to_level(world.creatures.get(creature_id).experience) as f32;
+        if roll < level {
+            winner = *creature_id;
+            break;
+        }
+        roll -= level;
+    }
+
+    world.site_install_leader(site_id, winner, resources)?;
+    for creature_id in claimants.iter() {
+        if *creature_id == winner {
+            continue;
+        }
+        let goal = Goal::OverthrowLeader { site_id: *site_id, leader_id: winner };
+        history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", creature_id, goal);
+        world.creatures.get_mut(creature_id).add_goal(goal);
+    }
+    history_trace!("succession_crisis site_id:{:?} winner:{:?} claimants:{:?}", site_id, winner, claimants);
+    world.events.push(Event::SuccessionCrisis { date: world.date, site_id: *site_id, creature_id: winner, claimants });
+    return Ok(())
+}
+
+fn adults(world: &World, site_id: &SiteId) -> Vec<CreatureId> {
+    let site = world.sites.get(site_id);
+    return site.creatures.iter().filter(|creature_id| {
+        let creature = world.creatures.get(*creature_id);
+        (world.date - creature.birth).get_years() >= MIN_RULER_AGE
+    }).copied().collect()
+}
+
+#[cfg(test)]
+mod tests_succession {
+    use crate::{commons::id_vec::Id, world::{creature::CauseOfDeath, date::WorldDate, site::{SettlementComponent, Structure, StructureType}, world::fixture::WorldFixture}};
+
+    use super::*;
+
+    #[test]
+    fn test_primogeniture_picks_eldest_child() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a3).birth = WorldDate::new(5, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a4).birth = WorldDate::new(3, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a1).offspring = vec!(fixture.creature_a3, fixture.creature_a4);
+
+        let heir = find_heir(&fixture.world, &SiteId::mock(0), &fixture.creature_a1, SuccessionLaw::Primogeniture);
+
+        assert_eq!(heir, Some(fixture.creature_a4));
+    }
+
+    #[test]
+    fn test_primogeniture_skips_minors() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a3).birth = WorldDate::new(20, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a1).offspring = vec!(fixture.creature_a3);
+
+        let heir = find_heir(&fixture.world, &SiteId::mock(0), &fixture.creature_a1, SuccessionLaw::Primogeniture);
+
+        assert_eq!(heir, None);
+    }
+
+    #[test]
+    fn test_headship_passes_within_family() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        let lineage_id = fixture.world.creatures.get(&fixture.creature_a2).lineage.unwrap();
+        fixture.world.lineages.get_mut(&lineage_id).head = Some(fixture.creature_a2);
+        fixture.world.creatures.get_mut(&fixture.creature_a2).offspring = vec!(fixture.creature_a3, fixture.creature_a4);
+        for child_id in [fixture.creature_a3, fixture.creature_a4] {
+            fixture.world.creatures.get_mut(&child_id).mother = fixture.creature_a2;
+        }
+        fixture.world.creatures.get_mut(&fixture.creature_a4).birth = WorldDate::new(3, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a3).birth = WorldDate::new(5, 1, 1);
+
+        fixture.world.kill_creature(fixture.creature_a2, SiteId::mock(0), SiteId::mock(0), CauseOfDeath::OldAge);
+
+        assert_eq!(fixture.world.lineages.get(&lineage_id).head, Some(fixture.creature_a4));
+    }
+
+    #[test]
+    fn test_tanistry_picks_ablest_relative() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        fixture.world.creatures.get_mut(&fixture.creature_a4).experience = 1000;
+
+        // a2, a3 and a4 share a lineage
+        let heir = find_heir(&fixture.world, &SiteId::mock(0), &fixture.creature_a2, SuccessionLaw::Tanistry);
+
+        assert_eq!(heir, Some(fixture.creature_a4));
+    }
+
+    #[test]
+    fn test_crisis_without_heir() {
+        let mut fixture = WorldFixture::new();
+        let mut resources = Resources::new();
+        resources.load();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        let culture = resources.cultures.id_of("culture:default");
+        {
+            // a1 ruled, and left no children behind
+            let mut site = fixture.world.sites.get_mut(&SiteId::mock(0));
+            site.settlement = Some(SettlementComponent { leader: Some(fixture.creature_a1), material_stock: Vec::new(), culture });
+            site.creatures = vec!(fixture.creature_a2, fixture.creature_a3, fixture.creature_a4);
+            let mut house = Structure::new(StructureType::House);
+            for creature_id in site.creatures.clone() {
+                house.add_ocuppant(creature_id);
+            }
+            site.structures.push(house);
+            site.structures.push(Structure::new(StructureType::TownHall));
+        }
+
+        succeed_leader(&mut fixture.world, &SiteId::mock(0), &mut Rng::seeded("succession"), &resources).unwrap();
+
+        let leader = fixture.world.sites.get(&SiteId::mock(0)).settlement.as_ref().unwrap().leader;
+        assert!(leader.is_some());
+        assert_ne!(leader, Some(fixture.creature_a1));
+        assert!(fixture.world.site_title(&SiteId::mock(0)).is_some());
+        assert!(matches!(fixture.world.events.last(), Some(Event::SuccessionCrisis { .. })));
+    }
+
+}
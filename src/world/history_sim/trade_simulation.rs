@@ -0,0 +1,115 @@
+use crate::{history_trace, resources::resources::Resources, world::{economy::{Good, Market}, history_sim::road_simulation, site::SiteId, world::World}};
+
+/// How far a caravan is willing to go, in a straight line
+const TRADE_RADIUS_SQRD: f32 = 10.*10.;
+/// How many units of a good a caravan carries
+const CARAVAN_CAPACITY: f32 = 10.;
+/// Price added to each unit of a good, for each unit of travel cost
+const TRANSPORT_PRICE: f32 = 0.02;
+
+/// Sends a caravan with the site's most profitable surplus to a nearby site that lacks it, and returns with a good the site lacks
+pub(crate) fn send_caravan(world: &mut World, site_id: SiteId, resources: &Resources) {
+    let (xy, has_settlement) = {
+        let site = world.sites.get(&site_id);
+        (site.xy, site.settlement.is_some() && site.creatures.len() > 0)
+    };
+    if !has_settlement {
+        return;
+    }
+
+    let mut partners = Vec::new();
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if other_id == site_id {
+            continue;
+        }
+        let other = other.borrow();
+        if other.site_type.is_settlement() && other.settlement.is_some() && other.creatures.len() > 0 && other.xy.dist_squared(&xy) <= TRADE_RADIUS_SQRD {
+            partners.push(other_id);
+        }
+    }
+    // No trading with the enemy
+    if let Some(war_id) = world.site_ongoing_war(&site_id) {
+        let enemy = world.wars.get(&war_id).enemy_of(&site_id);
+        partners.retain(|id| id != &enemy);
+    }
+
+    let market = {
+        let site = world.sites.get(&site_id);
+        Market::of_site(&site, world, resources)
+    };
+
+    // (partner, transport price, good, quantity, profit)
+    let mut best: Option<(SiteId, f32, Good, f32, f32)> = None;
+    for partner_id in partners {
+        let transport_price = match world.trade_route_cost(&site_id, &partner_id) {
+            Some(cost) => cost * TRANSPORT_PRICE,
+            None => continue,
+        };
+        let partner_market = {
+            let partner = world.sites.get(&partner_id);
+            Market::of_site(&partner, world, resources)
+        };
+        for good in market.goods() {
+            let margin = partner_market.price(good, resources) - market.price(good, resources) - transport_price;
+            let quantity = market.surplus(good).min(partner_market.shortage(good)).min(CARAVAN_CAPACITY).floor();
+            let profit = margin * quantity;
+            if margin > 0. && quantity > 0. && profit > best.map(|b| b.4).unwrap_or(0.) {
+                best = Some((partner_id, transport_price, *good, quantity, profit));
+            }
+        }
+    }
+
+    let (partner_id, transport_price, good, quantity, _) = match best {
+        Some(best) => best,
+        None => return,
+    };
+
+    // Sold at the partner's price
+    let partner_market = {
+        let partner = world.sites.get(&partner_id);
+        Market::of_site(&partner, world, resources)
+    };
+    let earnings = partner_market.price(&good, resources) * quantity;
+    move_good(world, &site_id, &partner_id, &good, quantity);
+    history_trace!("caravan from:{:?} to:{:?} good:{:?} quantity:{:?}", site_id, partner_id, good, quantity);
+    road_simulation::add_traffic(world, &site_id, &partner_id, road_simulation::CARAVAN_TRAFFIC);
+
+    // The earnings are spent on what's most lacking at home
+    let mut payment: Option<(Good, f32, f32)> = None;
+    for good in partner_market.goods() {
+        let price = partner_market.price(good, resources);
+        let margin = market.price(good, resources) - price - transport_price;
+        let quantity = partner_market.surplus(good).min(market.shortage(good)).min(earnings / price).min(CARAVAN_CAPACITY).floor();
+        let profit = margin * quantity;
+        if margin > 0. && quantity > 0. && profit > payment.map(|p| p.2).unwrap_or(0.) {
+            payment = Some((*good, quantity, profit));
+        }
+    }
+    if let Some((good, quantity, _)) = payment {
+        move_good(world, &partner_id, &site_id, &good, quantity);
+        history_trace!("caravan from:{:?} to:{:?} good:{:?} quantity:{:?}", partner_id, site_id, good, quantity);
+    }
+}
+
+fn move_good(world: &mut World, from: &SiteId, to: &SiteId, good: &Good, quantity: f32) {
+    let mut from = world.sites.get_mut(from);
+    let mut to = world.sites.get_mut(to);
+    match good {
+        Good::Food => {
+            let quantity = quantity.min(from.resources.food.max(0.));
+            from.resources.food -= quantity;
+            to.resources.food += quantity;
+        },
+        Good::Material(material_id) => {
+            let taken = match from.settlement.as_mut() {
+                Some(settlement) => settlement.remove_material(material_id, quantity as usize),
+                None => 0,
+            };
+            if let Some(settlement) = to.settlement.as_mut() {
+                if taken > 0 {
+                    settlement.add_material(material_id, taken);
+                }
+            }
+        },
+    }
+}
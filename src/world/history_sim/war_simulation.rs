@@ -0,0 +1,286 @@
+use crate::{commons::rng::Rng, history_trace, resources::resources::Resources, world::{creature::{CreatureId, Profession}, date::{Duration, WorldDate}, history_sim::{battle_simulator::BattleSimulator, creature_simulation::apply_battle_results}, site::SiteId, war::{CasusBelli, War, WarId, WarStatus}, world::World}, Event};
+
+/// How far a leader will look for someone to go to war with
+const WAR_RADIUS_SQRD: f32 = 12.*12.;
+const MIN_SOLDIER_AGE: i32 = 16;
+/// Chance per year that the armies meet in the field during a campaign
+const BATTLE_CHANCE: f32 = 2.;
+/// Chance per year that the besiegers storm the walls, even if the besieged are not starving yet
+const ASSAULT_CHANCE: f32 = 1.;
+/// Food the besieged consume per year, for each creature inside the walls, on top of the usual
+const SIEGE_FOOD_DRAIN: f32 = 1.;
+/// After this many years, both sides are tired and start considering peace
+const WAR_WEARINESS_YEARS: i32 = 3;
+/// Chance per year of peace, once the sides are weary
+const PEACE_CHANCE: f32 = 0.8;
+/// Chance that a defeated attacker gives up on the war
+const RETREAT_CHANCE: f32 = 0.5;
+
+/// The leader of the site looks for a neighbour to declare war on
+pub(crate) fn declare_war(world: &mut World, site_id: SiteId, rng: &mut Rng, resources: &Resources) {
+    if world.site_ongoing_war(&site_id).is_some() {
+        return;
+    }
+
+    let site = world.sites.get(&site_id);
+    if !site.site_type.is_settlement() {
+        return;
+    }
+    let leader_id = match site.settlement.as_ref().and_then(|settlement| settlement.leader) {
+        Some(leader_id) => leader_id,
+        None => return,
+    };
+    let leader = world.creatures.get(&leader_id);
+    if leader.death.is_some() {
+        return;
+    }
+    let starving = site.resources.food < 0.;
+
+    let mut candidates = Vec::new();
+    for (other_id, other) in world.sites.iter_id_val::<SiteId>() {
+        if other_id == site_id {
+            continue;
+        }
+        let other = other.borrow();
+        if !other.site_type.is_settlement() || other.creatures.len() == 0 || other.xy.dist_squared(&site.xy) > WAR_RADIUS_SQRD {
+            continue;
+        }
+        if world.site_ongoing_war(&other_id).is_some() {
+            continue;
+        }
+
+        let grudge = other.settlement.as_ref().and_then(|settlement| settlement.leader).filter(|other_leader_id| {
+            leader.relationship_find(other_leader_id).map(|relationship| relationship.rival_or_worse()).unwrap_or(false)
+        });
+        if let Some(target_id) = grudge {
+            candidates.push((other_id, CasusBelli::Grudge(target_id)));
+        } else if starving && other.resources.food > 0. {
+            candidates.push((other_id, CasusBelli::FoodShortage));
+        } else if let (Some(culture), Some(other_culture)) = (site.culture(), other.culture()) {
+            if rng.rand_chance(resources.cultures.hostility(&culture, &other_culture)) {
+                candidates.push((other_id, CasusBelli::Hostility(other_culture)));
+            }
+        }
+    }
+    drop(leader);
+    drop(site);
+
+    if let Some((defender, cause)) = rng.item(&candidates).copied() {
+        let war_id = world.wars.add::<WarId>(War::new(site_id, defender, cause, world.date));
+        history_trace!("war_declared war_id:{:?} attacker:{:?} defender:{:?} cause:{:?}", war_id, site_id, defender, cause);
+        world.events.push(Event::WarDeclared { date: world.date, war_id, creature_id: leader_id, attacker: site_id, defender, cause });
+    }
+}
+
+pub(crate) fn simulate_wars(world: &mut World, step: &Duration, rng: &mut Rng) {
+    let ongoing: Vec<WarId> = world.wars.iter_id_val::<WarId>()
+        .filter(|(_, war)| war.borrow().is_ongoing())
+        .map(|(war_id, _)| war_id)
+        .collect();
+    for war_id in ongoing {
+        simulate_war(world, war_id, step, rng);
+    }
+}
+
+fn simulate_war(world: &mut World, war_id: WarId, step: &Duration, rng: &mut Rng) {
+    let war = world.wars.get(&war_id).clone();
+    let now = world.date;
+    let factor = step.percentage_of_year();
+
+    // Without anyone to fight, the war is over
+    let attacker_army = raise_army(world, &war.attacker);
+    if attacker_army.len() == 0 {
+        sign_peace(world, war_id, &war);
+        return;
+    }
+
+    if let WarStatus::Siege(_) = war.status {
+        let mut defender = world.sites.get_mut(&war.defender);
+        let besieged = defender.creatures.len() as f32;
+        defender.resources.food -= besieged * SIEGE_FOOD_DRAIN * factor;
+    }
+
+    if is_campaign_season(&now) {
+        match war.status {
+            WarStatus::Campaign => {
+                if rng.rand_chance(BATTLE_CHANCE * factor) {
+                    let defender_army = raise_army(world, &war.defender);
+                    // No one left to defend the fields, the attackers march to the walls
+                    if defender_army.len() == 0 {
+                        world.wars.get_mut(&war_id).status = WarStatus::Siege(now);
+                        return;
+                    }
+                    let battle = BattleSimulator::simulate_field_battle(war.attacker, &attacker_army, war.defender, &defender_army, rng, world);
+                    let attacker_casualties = battle.casualties_of(&war.attacker);
+                    let defender_casualties = battle.casualties_of(&war.defender);
+                    apply_battle_results(world, battle, war.defender);
+
+                    let attacker_losses = attacker_casualties as f32 / attacker_army.len() as f32;
+                    let defender_losses = defender_casualties as f32 / defender_army.len() as f32;
+                    let victor = match attacker_losses <= defender_losses {
+                        true => war.attacker,
+                        false => war.defender,
+                    };
+                    record_battle(world, war_id, &war, victor, attacker_casualties, defender_casualties);
+
+                    if victor == war.attacker {
+                        world.wars.get_mut(&war_id).status = WarStatus::Siege(now);
+                    } else if rng.rand_chance(RETREAT_CHANCE) {
+                        sign_peace(world, war_id, &war);
+                        return;
+                    }
+                }
+            },
+            WarStatus::Siege(_) => {
+                let starving = world.sites.get(&war.defender).resources.food < 0.;
+                if starving || rng.rand_chance(ASSAULT_CHANCE * factor) {
+                    let defenders = world.sites.get(&war.defender).creatures.clone();
+                    let battle = BattleSimulator::simulate_assault(war.attacker, &attacker_army, war.defender, &defenders, rng, world);
+                    let attacker_casualties = battle.casualties_of(&war.attacker);
+                    let defender_casualties = battle.casualties_of(&war.defender);
+                    apply_battle_results(world, battle, war.defender);
+
+                    if raise_army(world, &war.defender).len() == 0 {
+                        record_battle(world, war_id, &war, war.attacker, attacker_casualties, defender_casualties);
+                        conquer(world, war_id, &war);
+                        return;
+                    }
+                    record_battle(world, war_id, &war, war.defender, attacker_casualties, defender_casualties);
+                    if raise_army(world, &war.attacker).len() == 0 {
+                        sign_peace(world, war_id, &war);
+                        return;
+                    }
+                }
+            },
+            WarStatus::Peace(_) | WarStatus::Annexed(_) => (),
+        }
+    }
+
+    if (now - war.start).get_years() >= WAR_WEARINESS_YEARS && rng.rand_chance(PEACE_CHANCE * factor) {
+        sign_peace(world, war_id, &war);
+    }
+}
+
+/// Every adult guard and peasant of the site is called to arms
+fn raise_army(world: &World, site_id: &SiteId) -> Vec<CreatureId> {
+    let site = world.sites.get(site_id);
+    return site.creatures.iter().filter(|creature_id| {
+        if world.is_played_creature(creature_id) {
+            return false;
+        }
+        let creature = world.creatures.get(*creature_id);
+        let age = (world.date - creature.birth).get_years();
+        age >= MIN_SOLDIER_AGE && (creature.profession == Profession::Guard || creature.profession == Profession::Peasant)
+    }).copied().collect();
+}
+
+fn is_campaign_season(date: &WorldDate) -> bool {
+    // Armies don't march from Dec. to Feb.
+    match date.month() {
+        0 | 1 | 2 => false,
+        _ => true
+    }
+}
+
+fn record_battle(world: &mut World, war_id: WarId, war: &War, victor: SiteId, attacker_casualties: u32, defender_casualties: u32) {
+    {
+        let mut war = world.wars.get_mut(&war_id);
+        war.casualties.0 += attacker_casualties;
+        war.casualties.1 += defender_casualties;
+    }
+    world.events.push(Event::BattleFought { date: world.date, war_id, attacker: war.attacker, defender: war.defender, victor, attacker_casualties, defender_casualties });
+}
+
+fn sign_peace(world: &mut World, war_id: WarId, war: &War) {
+    history_trace!("war_peace war_id:{:?}", war_id);
+    world.wars.get_mut(&war_id).status = WarStatus::Peace(world.date);
+    world.events.push(Event::PeaceSigned { date: world.date, war_id, attacker: war.attacker, defender: war.defender });
+}
+
+fn conquer(world: &mut World, war_id: WarId, war: &War) {
+    history_trace!("war_annexed war_id:{:?}", war_id);
+    world.site_annex(&war.defender, &war.attacker);
+    world.wars.get_mut(&war_id).status = WarStatus::Annexed(world.date);
+    world.events.push(Event::SiteConquered { date: world.date, war_id, site_id: war.defender, conqueror: war.attacker });
+}
+
+#[cfg(test)]
+mod tests_war {
+    use crate::{commons::id_vec::Id, resources::culture::CultureId, world::{site::{SettlementComponent, SiteType, Structure, StructureType}, world::fixture::{SiteBuilder, WorldFixture}}};
+    use math::Vec2i;
+
+    use super::*;
+
+    #[test]
+    fn test_annexation_moves_creatures() {
+        let mut fixture = WorldFixture::new();
+        let world = &mut fixture.world;
+        let defender_id = SiteId::mock(0);
+        {
+            let mut defender = world.sites.get_mut(&defender_id);
+            let mut house = Structure::new(StructureType::House);
+            house.add_ocuppant(fixture.creature_a1);
+            house.add_ocuppant(fixture.creature_a2);
+            defender.structures.push(house);
+        }
+        let attacker_id: SiteId = world.sites.add(SiteBuilder::new(SiteType::Village)
+            .xy(Vec2i(5, 5))
+            .settlement(CultureId::mock(0))
+            .build());
+        let war_id = world.wars.add::<WarId>(War::new(attacker_id, defender_id, CasusBelli::FoodShortage, world.date));
+        let war = world.wars.get(&war_id).clone();
+
+        conquer(world, war_id, &war);
+
+        assert_eq!(world.sites.get(&defender_id).creatures.len(), 0);
+        assert_eq!(world.sites.get(&attacker_id).creatures.len(), 4);
+        assert_eq!(world.sites.get(&attacker_id).structure_occupied_by(&fixture.creature_a2).is_some(), true);
+        assert_eq!(world.site_ongoing_war(&attacker_id), None);
+    }
+
+    #[test]
+    fn test_grudge_against_leader() {
+        let mut resources = Resources::new();
+        resources.load();
+        let mut fixture = WorldFixture::new();
+        let world = &mut fixture.world;
+        let culture = resources.cultures.id_of("culture:default");
+        let defender_id = SiteId::mock(0);
+        world.sites.get_mut(&defender_id).settlement = Some(SettlementComponent { leader: Some(fixture.creature_a2), material_stock: Vec::new(), culture });
+        let attacker_id: SiteId = world.sites.add(SiteBuilder::new(SiteType::Village)
+            .xy(Vec2i(5, 5))
+            .creatures(vec!(fixture.creature_a1))
+            .settlement(culture)
+            .leader(fixture.creature_a1)
+            .build());
+        let mut rng = Rng::seeded("grudge");
+
+        // A rival who doesn't lead the site is no reason for war
+        make_rival(world, fixture.creature_a1, fixture.creature_a3);
+        declare_war(world, attacker_id, &mut rng, &resources);
+        assert_eq!(world.site_ongoing_war(&attacker_id), None);
+
+        make_rival(world, fixture.creature_a1, fixture.creature_a2);
+        declare_war(world, attacker_id, &mut rng, &resources);
+        let war_id = world.site_ongoing_war(&attacker_id).unwrap();
+        assert_eq!(world.wars.get(&war_id).cause, CasusBelli::Grudge(fixture.creature_a2));
+    }
+
+    fn make_rival(world: &World, creature_id: CreatureId, rival_id: CreatureId) {
+        let rival = world.creatures.get(&rival_id);
+        world.creatures.get_mut(&creature_id).relationship_find_mut_or_insert(&creature_id, rival_id, &rival).add_opinion(-50);
+    }
+
+    #[test]
+    fn test_raise_army() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.date = WorldDate::new(30, 1, 1);
+        fixture.creature_a3_mut().profession = Profession::Blacksmith;
+        fixture.creature_a4_mut().profession = Profession::Guard;
+
+        let army = raise_army(&fixture.world, &SiteId::mock(0));
+
+        assert_eq!(army, vec!(fixture.creature_a1, fixture.creature_a2, fixture.creature_a4));
+    }
+
+}
@@ -1,12 +1,23 @@
 use math::Vec2i;
 use text::capitalize;
 
-use crate::{commons::{rng::Rng}, resources::resources::Resources, world::{history_sim::factories::CreatureFactory, site::*, world::World}};
+use crate::{commons::{id_vec::Id, rng::Rng}, resources::{biome::BiomeId, resources::Resources}, warn, world::{creature::CreatureId, history_sim::{factories::CreatureFactory, religion_simulation::culture_religion}, site::*, topology::Water, world::World}};
+
+/// How far from their old home the founders of a village settle
+const FOUNDED_VILLAGE_RADIUS: i32 = 8;
+/// How many free spots are compared when looking for fertile land
+const FERTILE_LAND_CANDIDATES: usize = 5;
+/// How much a river or lake nearby is worth, compared to soil fertility, when picking a spot for a village
+const FRESH_WATER_APPEAL: f32 = 0.5;
 
 pub(crate) fn spawn_random_village(world: &mut World, rng: &mut Rng, resources: &Resources, population: u32) -> Result<SiteId, ()> {
     let pos = search_new_site_pos(world, rng)?;
 
-    let name = resources.cultures.get(&resources.cultures.random()).city_name_model.generate(rng, 3, 10);
+    // The people that settle a region are the ones that feel at home there
+    let tile = world.map.tile(pos.x() as usize, pos.y() as usize);
+    let culture_id = resources.cultures.fitting_biome(&BiomeId::new(tile.region_id as usize), rng);
+    let name = resources.cultures.get(&culture_id).city_name_model.generate(rng, 3, 10);
+    let religion_id = culture_religion(world, culture_id, rng, resources);
     let name = capitalize(&name);
     let mut site = Site {
         xy: pos,
@@ -19,10 +30,12 @@ pub(crate) fn spawn_random_village(world: &mut World, rng: &mut Rng, resources:
         name: Some(name),
         settlement: Some(SettlementComponent {
             leader: None,
-            material_stock: Vec::new()
+            material_stock: Vec::new(),
+            culture: culture_id,
         }),
         artifacts: Vec::new(),
         population_peak: (0, 0),
+        population_history: Vec::new(),
         site_type: SiteType::Village,
         structures: Vec::new()
     };
@@ -33,9 +46,10 @@ pub(crate) fn spawn_random_village(world: &mut World, rng: &mut Rng, resources:
         
         let mut factory = CreatureFactory::new(rng.clone());
         let date = world.date.clone();
-        let family = factory.make_family_or_single(&date, resources.species.id_of("species:human"), world, &resources);
+        let family = factory.make_family_or_single(&date, resources.species.id_of("species:human"), culture_id, world, &resources);
         let mut structure = Structure::new(StructureType::House);
         for creature_id in family {
+            world.creatures.get_mut(&creature_id).faith = Some(religion_id);
             site.creatures.push(creature_id);
             structure.add_ocuppant(creature_id);
         }
@@ -49,25 +63,184 @@ pub(crate) fn spawn_random_village(world: &mut World, rng: &mut Rng, resources:
     return Ok(world.sites.add::<SiteId>(site));
 }
 
-fn search_new_site_pos(world: &World, rng: &mut Rng) -> Result<Vec2i, ()> {
+/// Households leave their site to found a new village nearby, led by the founder
+pub(crate) fn found_village(world: &mut World, origin_id: SiteId, founder_id: CreatureId, households: Vec<Vec<CreatureId>>, rng: &mut Rng, resources: &Resources) -> Result<SiteId, ()> {
+    let origin_xy = world.sites.get(&origin_id).xy;
+    let pos = search_site_pos_near(world, origin_xy, FOUNDED_VILLAGE_RADIUS, rng)?;
+    return found_site_at(world, origin_id, founder_id, households, pos, SiteType::Village, rng, resources)
+}
+
+/// Households leave their site to found a new site of the type at the position, led by the founder.
+/// Castles and monasteries house everyone under the same roof, other sites give each household its own house
+pub(crate) fn found_site_at(world: &mut World, origin_id: SiteId, founder_id: CreatureId, households: Vec<Vec<CreatureId>>, pos: Vec2i, site_type: SiteType, rng: &mut Rng, resources: &Resources) -> Result<SiteId, ()> {
+    let culture_id = {
+        let origin = world.sites.get(&origin_id);
+        origin.culture().unwrap_or(resources.cultures.id_of("culture:default"))
+    };
+    let name = resources.cultures.get(&culture_id).city_name_model.generate(&rng.derive("name"), 3, 10);
+    rng.next();
+
+    let mut site = Site {
+        xy: pos,
+        creatures: Vec::new(),
+        cemetery: Vec::new(),
+        resources: SiteResources {
+            food: 0.
+        },
+        name: Some(capitalize(&name)),
+        settlement: Some(SettlementComponent {
+            leader: None,
+            material_stock: Vec::new(),
+            culture: culture_id,
+        }),
+        artifacts: Vec::new(),
+        population_peak: (0, 0),
+        population_history: Vec::new(),
+        site_type,
+        structures: Vec::new()
+    };
+    // Castles and monasteries house everyone in the same hall
+    let shared_hall = match site_type {
+        SiteType::Castle => Some(StructureType::Keep),
+        SiteType::Monastery => Some(StructureType::Cloister),
+        _ => None,
+    };
+    let mut hall = Structure::new(shared_hall.unwrap_or(StructureType::TownHall));
+    let mut houses = Vec::new();
+
+    {
+        let mut origin = world.sites.get_mut(&origin_id);
+        for household in households {
+            let mut structure = Structure::new(StructureType::House);
+            for creature_id in household {
+                origin.remove_creature(&creature_id, world.date);
+                site.creatures.push(creature_id);
+                match shared_hall {
+                    Some(_) => hall.add_ocuppant(creature_id),
+                    None => structure.add_ocuppant(creature_id),
+                }
+            }
+            if shared_hall.is_none() {
+                houses.push(structure);
+            }
+        }
+        site.structures.push(hall);
+        if site_type == SiteType::Mine {
+            site.structures.push(Structure::new(StructureType::MineShaft));
+        }
+        site.structures.append(&mut houses);
+        // The settlers carry their share of the food
+        let share = (site.creatures.len() as f32).min(origin.resources.food.max(0.));
+        origin.resources.food -= share;
+        site.resources.food += share;
+    }
+    site.update_population_peak(&world.date);
+
+    let site_id = world.sites.add::<SiteId>(site);
+    if let Err(msg) = world.site_install_leader(&site_id, founder_id, resources) {
+        warn!("{msg}");
+    }
+    return Ok(site_id)
+}
+
+pub(crate) fn search_site_pos_near(world: &World, center: Vec2i, radius: i32, rng: &mut Rng) -> Result<Vec2i, ()> {
+    for _ in 0..100 {
+        let x = (center.x() + rng.randi_range(-radius, radius + 1)).clamp(3, world.map.size.x() as i32 - 4);
+        let y = (center.y() + rng.randi_range(-radius, radius + 1)).clamp(3, world.map.size.y() as i32 - 4);
+        let candidate = Vec2i(x, y);
+        if site_pos_is_free(world, &candidate) {
+            return Ok(candidate)
+        }
+    }
+    return Err(());
+}
+
+pub(crate) fn site_pos_is_free(world: &World, candidate: &Vec2i) -> bool {
+    // No building on lakes or the sea
+    if let Some(Water::Lake) | Some(Water::Ocean) = world.map.water_at(*candidate) {
+        return false;
+    }
+    return !world.sites.iter().any(|site| {
+        let site = site.borrow();
+        if site.creatures.len() == 0 {
+            return site.xy == *candidate
+        }
+        return site.xy.dist_squared(candidate) < 3. * 3.
+    })
+}
+
+/// A free spot on dry land anywhere in the world, picking the most fertile of a few candidates. Villages like to be by a river or lake
+pub(crate) fn search_new_site_pos(world: &World, rng: &mut Rng) -> Result<Vec2i, ()> {
+    let mut best: Option<(Vec2i, f32)> = None;
+    let mut candidates = 0;
     for _ in 0..100 {
         let x = rng.randu_range(3, world.map.size.x() - 3);
         let y = rng.randu_range(3, world.map.size.y() - 3);
         let candidate = Vec2i(x as i32, y as i32);
-        let too_close = world.sites.iter().any(|site| {
-            let site = site.borrow();
-            if site.creatures.len() == 0 {
-                if site.xy == candidate {
-                    return true;
-                }
-                return false;
-            }
-            return site.xy.dist_squared(&candidate) < 3. * 3.
-        });
-        if too_close {
+        if !site_pos_is_free(world, &candidate) {
             continue;
         }
-        return Ok(candidate)
+        let mut appeal = world.map.tile(x, y).soil_fertility;
+        if world.map.near_fresh_water(candidate) {
+            appeal += FRESH_WATER_APPEAL;
+        }
+        if best.map(|(_, best_appeal)| appeal > best_appeal).unwrap_or(true) {
+            best = Some((candidate, appeal));
+        }
+        candidates += 1;
+        if candidates >= FERTILE_LAND_CANDIDATES {
+            break;
+        }
+    }
+    return best.map(|(pos, _)| pos).ok_or(());
+}
+
+/// Moves a household into another site, into an empty house if there's one. They carry their share of the food.
+pub(crate) fn move_household(world: &mut World, origin_id: &SiteId, destination_id: &SiteId, household: &Vec<CreatureId>) {
+    let mut origin = world.sites.get_mut(origin_id);
+    let mut destination = world.sites.get_mut(destination_id);
+    for creature_id in household.iter() {
+        origin.remove_creature(creature_id, world.date);
+        destination.creatures.push(*creature_id);
+    }
+    let empty_house = destination.structures.iter_mut().find(|structure| structure.get_type() == &StructureType::House && structure.get_status().is_abandoned());
+    match empty_house {
+        Some(house) => {
+            for creature_id in household.iter() {
+                house.add_ocuppant(*creature_id);
+            }
+        },
+        None => {
+            let mut house = Structure::new(StructureType::House);
+            for creature_id in household.iter() {
+                house.add_ocuppant(*creature_id);
+            }
+            destination.structures.push(house);
+        }
+    }
+    let share = (household.len() as f32).min(origin.resources.food.max(0.));
+    origin.resources.food -= share;
+    destination.resources.food += share;
+    destination.update_population_peak(&world.date);
+}
+
+/// A household moves into the ruins, which become a village again, led by the head of the household
+pub(crate) fn resettle_ruins(world: &mut World, origin_id: &SiteId, ruins_id: &SiteId, household: &Vec<CreatureId>, resources: &Resources) {
+    let culture_id = world.sites.get(origin_id).culture().unwrap_or(resources.cultures.id_of("culture:default"));
+    move_household(world, origin_id, ruins_id, household);
+    {
+        let mut site = world.sites.get_mut(ruins_id);
+        site.site_type = SiteType::Village;
+        // The ruins of a castle, mine or monastery have no hall for the village to gather in
+        if !site.structures.iter().any(|structure| structure.get_type() == &StructureType::TownHall) {
+            site.structures.push(Structure::new(StructureType::TownHall));
+        }
+        match site.settlement.as_mut() {
+            Some(settlement) => settlement.culture = culture_id,
+            None => site.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture: culture_id }),
+        }
+    }
+    if let Err(msg) = world.site_install_leader(ruins_id, household[0], resources) {
+        warn!("{msg}");
     }
-    return Err(());
 }
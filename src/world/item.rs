@@ -7,7 +7,7 @@ use text::capitalize;
 
 use crate::{commons::{damage_model::{DamageModel, DamageRoll}, id_vec::IdVec}, engine::{gui::tooltip::{Tooltip, TooltipLine}, pallete_sprite::ColorMap}, game::actor::health_component::BodyPart, resources::{action::{ActionId, Affliction}, item_blueprint::ItemBlueprintId, material::{MaterialId, Materials}, resources::{resources, Resources}, species::SPECIES_SPRITE_SIZE}, Color};
 
-use super::creature::CreatureId;
+use super::{creature::CreatureId, religion::DeityId};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
 pub(crate) struct ItemId(usize);
@@ -34,6 +34,9 @@ pub(crate) struct Item {
     pub(crate) mellee_damage: Option<MelleeDamageComponent>,
     pub(crate) armor: Option<ArmorComponent>,
     pub(crate) artwork_scene: Option<ArtworkSceneComponent>,
+    /// The god a holy relic is dedicated to
+    #[serde(default)]
+    pub(crate) dedicated_to: Option<DeityId>,
     // In-memory cache, shouldn't save
     #[serde(skip)] 
     pub(crate) cached_placed_texture: RefCell<Option<RgbaImage>>,
@@ -209,7 +212,8 @@ pub(crate) enum ItemMakeArguments {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum ArtworkScene {
     Bust { creature_id: CreatureId },
-    FullBody { creature_id: CreatureId, artifact_id: Option<ItemId> }
+    FullBody { creature_id: CreatureId, artifact_id: Option<ItemId> },
+    Deity { deity_id: DeityId },
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -0,0 +1,584 @@
+use std::ops::ControlFlow;
+
+use piston::Key;
+
+use crate::{commons::id_vec::Id, engine::{assets::{assets, Assets}, gui::{button::Button, chart::LineChart, containers::SimpleContainer, label::Label, layout_component::LayoutComponent, UIEvent, UINode}, input::InputEvent, render::RenderContext, scene::{BusEvent, Scene, Update}, COLOR_WHITE}, world::{creature::CreatureId, event::{Event, EventKind}, item::ItemId, lineage::LineageId, site::SiteId, world::World, writer::Writer}, GameContext};
+
+/// Entries shown at once in the list on the left
+const PAGE_SIZE: usize = 50;
+/// How many years the date filter moves with each click
+const YEAR_STEP: i32 = 10;
+/// Most recent events shown on an entity's page
+const MAX_EVENTS: usize = 100;
+/// Residents of a site shown as links
+const MAX_RESIDENTS: usize = 30;
+/// How many generations of a house are shown below its founder
+const FAMILY_TREE_DEPTH: usize = 4;
+
+/// Browses the whole history of a saved world, regardless of what the player has discovered
+pub(crate) struct LegendsScene {
+    world: World,
+    layout: LayoutComponent,
+    tabs: Vec<(Tab, Button)>,
+    controls: Vec<Button>,
+    kind_button: Button,
+    list: SimpleContainer,
+    info: SimpleContainer,
+    tab: Tab,
+    page: usize,
+    pages: usize,
+    from_year: i32,
+    to_year: i32,
+    kind: Option<EventKind>,
+    selected: Option<Selection>,
+    history: Vec<Selection>,
+}
+
+impl LegendsScene {
+
+    pub(crate) fn new(world: World) -> Self {
+        let mut layout = LayoutComponent::new();
+        layout.anchor_center().size([496., 368.]).padding([8.; 4]);
+
+        let mut tabs = Vec::new();
+        for (i, (tab, name)) in [(Tab::Creatures, "Creatures"), (Tab::Sites, "Sites"), (Tab::Houses, "Houses"), (Tab::Artifacts, "Artifacts"), (Tab::Events, "Events"), (Tab::Stats, "Stats")].into_iter().enumerate() {
+            let mut button = Button::text(name);
+            button.layout_component().anchor_top_left(i as f64 * 58., 0.).size([56., 16.]);
+            button.set_selected(tab == Tab::Creatures);
+            tabs.push((tab, button));
+        }
+
+        let mut controls = Vec::new();
+        for (key, text, [x, y, width]) in [("back", "Back", [354., 0., 60.]), ("menu", "Menu", [420., 0., 60.]), ("from:-", "-", [0., 18., 16.]), ("from:+", "+", [74., 18., 16.]), ("to:-", "-", [100., 18., 16.]), ("to:+", "+", [174., 18., 16.]), ("page:-", "<", [290., 18., 16.]), ("page:+", ">", [344., 18., 16.])] {
+            let mut button = Button::text(text).key(key);
+            button.layout_component().anchor_top_left(x, y).size([width, 16.]);
+            controls.push(button);
+        }
+
+        let mut list = SimpleContainer::new();
+        list.layout_component().anchor_top_left(0., 38.).size([140., 314.]);
+
+        let mut info = SimpleContainer::new();
+        info.layout_component().anchor_top_left(148., 38.).size([332., 314.]);
+
+        let mut kind_button = Button::text("All events").key("kind");
+        kind_button.layout_component().anchor_top_left(200., 18.).size([80., 16.]);
+
+        let to_year = world.date.year();
+        return Self {
+            world,
+            layout,
+            tabs,
+            controls,
+            kind_button,
+            list,
+            info,
+            tab: Tab::Creatures,
+            page: 0,
+            pages: 1,
+            from_year: 0,
+            to_year,
+            kind: None,
+            selected: None,
+            history: Vec::new(),
+        };
+    }
+
+    fn event_visible(&self, event: &Event) -> bool {
+        let year = event.date().year();
+        if year < self.from_year || year > self.to_year {
+            return false;
+        }
+        return match self.kind {
+            Some(kind) => event.kind() == kind,
+            None => true,
+        }
+    }
+
+    fn entries(&self) -> Vec<Selection> {
+        match self.tab {
+            Tab::Creatures => {
+                return self.world.creatures.iter_id_val::<CreatureId>()
+                    .filter(|(_, creature)| {
+                        let creature = creature.borrow();
+                        let died_after = creature.death.map(|(date, _)| date.year() >= self.from_year).unwrap_or(true);
+                        creature.birth.year() <= self.to_year && died_after
+                    })
+                    .map(|(id, _)| Selection::Creature(id))
+                    .collect();
+            },
+            Tab::Sites => return self.world.sites.iter_ids::<SiteId>().map(Selection::Site).collect(),
+            Tab::Houses => return self.world.lineages.iter_ids::<LineageId>().map(Selection::House).collect(),
+            Tab::Artifacts => return self.world.artifacts.iter_ids::<ItemId>().map(Selection::Artifact).collect(),
+            Tab::Events => {
+                let mut query = self.world.events.query();
+                if let Some(kind) = self.kind {
+                    query = query.kind(kind);
+                }
+                return query.iter()
+                    .filter(|(_, event)| self.event_visible(event))
+                    .map(|(i, _)| Selection::Event(i))
+                    .collect();
+            },
+            Tab::Stats => return (0..self.world.metrics.series().len()).map(Selection::Metric).collect(),
+        }
+    }
+
+    fn build_list(&mut self, ctx: &GameContext) {
+        self.list.clear();
+        let entries = self.entries();
+        self.pages = entries.len().div_ceil(PAGE_SIZE).max(1);
+        self.page = self.page.min(self.pages - 1);
+        for selection in entries.iter().skip(self.page * PAGE_SIZE).take(PAGE_SIZE) {
+            let name = self.selection_name(selection, ctx);
+            self.list.add(link(&name, selection, 0., 132.));
+        }
+    }
+
+    fn selection_name(&self, selection: &Selection, ctx: &GameContext) -> String {
+        match selection {
+            Selection::Creature(id) => return self.world.creature_desc(id, &ctx.resources),
+            Selection::Site(id) => return String::from(self.world.sites.get(id).name()),
+            Selection::House(id) => {
+                let lineage = self.world.lineages.get(id);
+                if lineage.is_noble() {
+                    return format!("House {}", lineage.name);
+                }
+                return format!("The {} family", lineage.name);
+            },
+            Selection::Artifact(id) => return self.world.artifacts.get(id).name(&ctx.resources.materials),
+            Selection::Event(i) => {
+                let event = &self.world.events[*i];
+                return format!("{}: {}", event.date().year(), event.kind().name());
+            },
+            Selection::Metric(i) => return self.world.metrics.series().swap_remove(*i).title,
+        }
+    }
+
+    fn select(&mut self, selection: Selection, ctx: &GameContext) {
+        if let Some(previous) = self.selected.take() {
+            if previous != selection {
+                self.history.push(previous);
+            }
+        }
+        self.selected = Some(selection);
+        self.build_info(ctx);
+    }
+
+    fn build_info(&mut self, ctx: &GameContext) {
+        self.info.clear();
+        let selection = match self.selected {
+            Some(selection) => selection,
+            None => return,
+        };
+        let name = Label::text(&self.selection_name(&selection, ctx)).font(Assets::font_heading_asset());
+        self.info.add(name);
+        match selection {
+            Selection::Creature(id) => self.build_creature(id, ctx),
+            Selection::Site(id) => self.build_site(id, ctx),
+            Selection::House(id) => self.build_house(id, ctx),
+            Selection::Artifact(id) => self.build_artifact(id, ctx),
+            Selection::Event(i) => self.build_event(i, ctx),
+            Selection::Metric(i) => self.build_metric(i),
+        }
+    }
+
+    fn build_creature(&mut self, creature_id: CreatureId, ctx: &GameContext) {
+        let mut links = Vec::new();
+        {
+            let creature = self.world.creatures.get(&creature_id);
+            let species = ctx.resources.species.get(&creature.species);
+            self.info.add(Label::text(&format!("A {} ({})", species.name, creature.profession.name())));
+            self.info.add(Label::text(&format!("Born {}", self.world.date_desc(&creature.birth))));
+            match creature.death {
+                Some((date, cause)) => self.info.add(Label::text(&format!("Died {} ({:?})", self.world.date_desc(&date), cause))),
+                None => self.info.add(Label::text("Alive")),
+            }
+            if let Some(lineage_id) = creature.lineage {
+                links.push(("Family", Selection::House(lineage_id)));
+            }
+            if creature.father != CreatureId::ancients() {
+                links.push(("Father", Selection::Creature(creature.father)));
+            }
+            if creature.mother != CreatureId::ancients() {
+                links.push(("Mother", Selection::Creature(creature.mother)));
+            }
+            if let Some(spouse_id) = creature.spouse {
+                links.push(("Spouse", Selection::Creature(spouse_id)));
+            }
+            for child_id in creature.offspring.iter() {
+                links.push(("Child", Selection::Creature(*child_id)));
+            }
+        }
+        for (prefix, selection) in links {
+            self.add_link(prefix, &selection, ctx);
+        }
+
+        let events = self.world.events.by_creature(&creature_id).map(|(i, _)| i).collect();
+        self.add_events(events, ctx);
+    }
+
+    fn build_site(&mut self, site_id: SiteId, ctx: &GameContext) {
+        let (leader, residents, hidden, points) = {
+            let site = self.world.sites.get(&site_id);
+            self.info.add(Label::text(site.site_type.description()));
+            self.info.add(Label::text(&format!("Population: {}, at most {} in {}", site.creatures.len(), site.population_peak.1, site.population_peak.0)));
+            let points: Vec<(f64, f64)> = site.population_history.iter()
+                .filter(|(year, _)| *year >= self.from_year && *year <= self.to_year)
+                .map(|(year, population)| (*year as f64, *population as f64))
+                .collect();
+            let residents: Vec<CreatureId> = site.creatures.iter().take(MAX_RESIDENTS).copied().collect();
+            let hidden = site.creatures.len().saturating_sub(MAX_RESIDENTS);
+            (site.settlement.as_ref().and_then(|settlement| settlement.leader), residents, hidden, points)
+        };
+
+        if let Some(leader_id) = leader {
+            self.add_link("Leader", &Selection::Creature(leader_id), ctx);
+        }
+        if points.len() > 1 {
+            self.info.add(LineChart::new("Population", points).layout(|l| { l.size([320., 96.]); }));
+        }
+        if !residents.is_empty() {
+            self.info.add(Label::text("Residents").font(Assets::font_heading_asset()));
+        }
+        for creature_id in residents {
+            self.add_link("", &Selection::Creature(creature_id), ctx);
+        }
+        if hidden > 0 {
+            self.info.add(Label::text(&format!("...and {} more", hidden)));
+        }
+
+        let events = self.world.events.by_site(&site_id).map(|(i, _)| i).collect();
+        self.add_events(events, ctx);
+    }
+
+    fn build_house(&mut self, lineage_id: LineageId, ctx: &GameContext) {
+        let (heraldry, head, founder) = {
+            let lineage = self.world.lineages.get(&lineage_id);
+            (lineage.heraldry.as_ref().map(|heraldry| heraldry.description()), lineage.head, lineage.founder)
+        };
+        if let Some(heraldry) = heraldry {
+            self.info.add(Label::text(&format!("Bears {}.", heraldry)));
+        }
+        if let Some(head) = head {
+            self.add_link("Head", &Selection::Creature(head), ctx);
+        }
+        if let Some(founder) = founder {
+            self.info.add(Label::text("Family tree").font(Assets::font_heading_asset()));
+            self.add_family_tree(founder, lineage_id, 0, ctx);
+        }
+
+        let events = self.world.events.iter().enumerate()
+            .filter(|(_, event)| event.related_lineages(&self.world).contains(&lineage_id))
+            .map(|(i, _)| i)
+            .collect();
+        self.add_events(events, ctx);
+    }
+
+    /// The creature and their descendants that carry the name of the house, as links indented by generation
+    fn add_family_tree(&mut self, creature_id: CreatureId, lineage_id: LineageId, depth: usize, ctx: &GameContext) {
+        let offspring = self.world.creatures.get(&creature_id).offspring.clone();
+        let name = self.selection_name(&Selection::Creature(creature_id), ctx);
+        self.info.add(link(&name, &Selection::Creature(creature_id), depth as f64 * 12., 200.));
+        if depth >= FAMILY_TREE_DEPTH {
+            return;
+        }
+        for child_id in offspring {
+            if self.world.creatures.get(&child_id).lineage == Some(lineage_id) {
+                self.add_family_tree(child_id, lineage_id, depth + 1, ctx);
+            }
+        }
+    }
+
+    fn build_artifact(&mut self, item_id: ItemId, ctx: &GameContext) {
+        let description = {
+            let item = self.world.artifacts.get(&item_id);
+            let mut writer = Writer::new(&self.world, &ctx.resources);
+            writer.describe_item(&item);
+            writer.take_text()
+        };
+        self.info.add(Label::text(&description));
+
+        let events = self.world.events.by_artifact(&item_id).map(|(i, _)| i).collect();
+        self.add_events(events, ctx);
+    }
+
+    fn build_event(&mut self, event_i: usize, ctx: &GameContext) {
+        let event = &self.world.events[event_i];
+        let text = event.event_text(&ctx.resources, &self.world);
+        let mut related = Vec::new();
+        for creature_id in event.related_creatures() {
+            related.push(Selection::Creature(creature_id));
+        }
+        for site_id in event.related_sites() {
+            related.push(Selection::Site(site_id));
+        }
+        for lineage_id in event.related_lineages(&self.world) {
+            related.push(Selection::House(lineage_id));
+        }
+        for item_id in event.related_artifacts() {
+            related.push(Selection::Artifact(item_id));
+        }
+
+        self.info.add(Label::text(&text));
+        if !related.is_empty() {
+            self.info.add(Label::text("Related").font(Assets::font_heading_asset()));
+        }
+        for selection in related {
+            self.add_link("", &selection, ctx);
+        }
+    }
+
+    fn build_metric(&mut self, i: usize) {
+        let series = self.world.metrics.series().swap_remove(i);
+        let points: Vec<(f64, f64)> = series.points.iter()
+            .filter(|(year, _)| *year >= self.from_year && *year <= self.to_year)
+            .map(|(year, value)| (*year as f64, *value))
+            .collect();
+        let (min, max) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), (_, value)| (min.min(*value), max.max(*value)));
+        match points.last() {
+            Some((year, value)) => self.info.add(Label::text(&format!("{} in {}, between {} and {}", value.round(), year, min.round(), max.round()))),
+            None => self.info.add(Label::text("No samples in these years")),
+        }
+        self.info.add(LineChart::new(&series.title, points).layout(|l| { l.size([320., 160.]); }));
+    }
+
+    fn add_link(&mut self, prefix: &str, selection: &Selection, ctx: &GameContext) {
+        let name = self.selection_name(selection, ctx);
+        let text = match prefix {
+            "" => name,
+            prefix => format!("{prefix}: {name}"),
+        };
+        self.info.add(link(&text, selection, 0., 200.));
+    }
+
+    /// The latest of the events that pass the filters
+    fn add_events(&mut self, events: Vec<usize>, ctx: &GameContext) {
+        let events: Vec<&Event> = events.into_iter()
+            .map(|i| &self.world.events[i])
+            .filter(|event| self.event_visible(event))
+            .collect();
+        if events.is_empty() {
+            return;
+        }
+        let mut labels = Vec::new();
+        for event in events.iter().skip(events.len().saturating_sub(MAX_EVENTS)) {
+            labels.push(Label::text(&event.list_text(&ctx.resources, &self.world)));
+        }
+        let hidden = events.len().saturating_sub(MAX_EVENTS);
+
+        self.info.add(Label::text("Events").font(Assets::font_heading_asset()));
+        if hidden > 0 {
+            self.info.add(Label::text(&format!("...{} earlier events", hidden)));
+        }
+        for label in labels {
+            self.info.add(label);
+        }
+    }
+
+    fn handle_key(&mut self, key: &str, ctx: &GameContext) -> ControlFlow<()> {
+        match key {
+            "menu" => return ControlFlow::Break(()),
+            "back" => {
+                if let Some(previous) = self.history.pop() {
+                    self.selected = Some(previous);
+                    self.build_info(ctx);
+                }
+            },
+            "from:-" => self.set_years(self.from_year - YEAR_STEP, self.to_year, ctx),
+            "from:+" => self.set_years(self.from_year + YEAR_STEP, self.to_year, ctx),
+            "to:-" => self.set_years(self.from_year, self.to_year - YEAR_STEP, ctx),
+            "to:+" => self.set_years(self.from_year, self.to_year + YEAR_STEP, ctx),
+            "kind" => {
+                self.kind = match self.kind {
+                    None => Some(EventKind::ALL[0]),
+                    Some(kind) => EventKind::ALL.iter().skip_while(|another| **another != kind).nth(1).copied(),
+                };
+                let text = self.kind.map(|kind| kind.name()).unwrap_or("All events");
+                self.kind_button.set_text(text);
+                self.page = 0;
+                self.build_list(ctx);
+                self.build_info(ctx);
+            },
+            "page:-" => {
+                self.page = self.page.saturating_sub(1);
+                self.build_list(ctx);
+            },
+            "page:+" => {
+                self.page = (self.page + 1).min(self.pages - 1);
+                self.build_list(ctx);
+            },
+            key => {
+                if let Some(selection) = Selection::parse(key) {
+                    self.select(selection, ctx);
+                }
+            }
+        }
+        return ControlFlow::Continue(());
+    }
+
+    fn set_years(&mut self, from_year: i32, to_year: i32, ctx: &GameContext) {
+        self.from_year = from_year.clamp(0, self.world.date.year());
+        self.to_year = to_year.clamp(self.from_year, self.world.date.year());
+        self.build_list(ctx);
+        self.build_info(ctx);
+    }
+
+}
+
+impl Scene for LegendsScene {
+    type Input = ();
+
+    fn init(&mut self, ctx: &mut GameContext) {
+        self.build_list(ctx);
+    }
+
+    fn render(&mut self, ctx: &mut RenderContext, game_ctx: &mut GameContext) {
+        ctx.scale(2.);
+        let copy = ctx.layout_rect;
+        ctx.layout_rect = self.layout.compute_inner_layout_rect(ctx.layout_rect);
+
+        for (_, button) in self.tabs.iter_mut() {
+            button.render(&(), ctx, game_ctx);
+        }
+        for button in self.controls.iter_mut() {
+            button.render(&(), ctx, game_ctx);
+        }
+        self.kind_button.render(&(), ctx, game_ctx);
+
+        let x = ctx.layout_rect[0] as i32;
+        let y = ctx.layout_rect[1] as i32 + 30;
+        let mut assets = assets();
+        let font = assets.font_standard();
+        ctx.text_shadow(&format!("{}", self.from_year), font, [x + 24, y], &COLOR_WHITE);
+        ctx.text_shadow(&format!("{}", self.to_year), font, [x + 124, y], &COLOR_WHITE);
+        ctx.text_shadow(&format!("{}/{}", self.page + 1, self.pages), font, [x + 312, y], &COLOR_WHITE);
+        drop(assets);
+
+        self.list.render(&(), ctx, game_ctx);
+        self.info.render(&(), ctx, game_ctx);
+
+        ctx.layout_rect = copy;
+    }
+
+    fn update(&mut self, _update: &Update, _ctx: &mut GameContext) {
+    }
+
+    fn input(&mut self, evt: &InputEvent, ctx: &mut GameContext) -> ControlFlow<()> {
+        if let InputEvent::Key { key: Key::Escape } = evt {
+            return ControlFlow::Break(());
+        }
+
+        let mut clicked_tab = None;
+        for (tab, button) in self.tabs.iter_mut() {
+            if button.input(&mut (), evt, ctx).is_break() {
+                clicked_tab = Some(*tab);
+            }
+        }
+        if let Some(tab) = clicked_tab {
+            for (another, button) in self.tabs.iter_mut() {
+                button.set_selected(*another == tab);
+            }
+            self.tab = tab;
+            self.page = 0;
+            self.build_list(ctx);
+            return ControlFlow::Continue(());
+        }
+
+        let mut clicked = None;
+        for button in self.controls.iter_mut() {
+            if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = button.input(&mut (), evt, ctx) {
+                clicked = Some(key);
+            }
+        }
+        if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = self.kind_button.input(&mut (), evt, ctx) {
+            clicked = Some(key);
+        }
+        if clicked.is_none() {
+            if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = self.list.input(&mut (), evt, ctx) {
+                clicked = Some(key);
+            } else if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = self.info.input(&mut (), evt, ctx) {
+                clicked = Some(key);
+            }
+        }
+        if let Some(key) = clicked {
+            return self.handle_key(&key, ctx);
+        }
+        return ControlFlow::Continue(());
+    }
+
+    fn event(&mut self, _evt: &BusEvent, _ctx: &mut GameContext) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    Creatures,
+    Sites,
+    Houses,
+    Artifacts,
+    Events,
+    Stats,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Selection {
+    Creature(CreatureId),
+    Site(SiteId),
+    House(LineageId),
+    Artifact(ItemId),
+    Event(usize),
+    /// A series of the world statistics
+    Metric(usize),
+}
+
+impl Selection {
+
+    fn key(&self) -> String {
+        match self {
+            Selection::Creature(id) => format!("creature:{}", id.as_usize()),
+            Selection::Site(id) => format!("site:{}", id.as_usize()),
+            Selection::House(id) => format!("house:{}", id.as_usize()),
+            Selection::Artifact(id) => format!("artifact:{}", id.as_usize()),
+            Selection::Event(i) => format!("event:{}", i),
+            Selection::Metric(i) => format!("metric:{}", i),
+        }
+    }
+
+    fn parse(key: &str) -> Option<Selection> {
+        let (kind, id) = key.split_once(':')?;
+        let id = id.parse::<usize>().ok()?;
+        match kind {
+            "creature" => Some(Selection::Creature(CreatureId::new(id))),
+            "site" => Some(Selection::Site(SiteId::new(id))),
+            "house" => Some(Selection::House(LineageId::new(id))),
+            "artifact" => Some(Selection::Artifact(ItemId::new(id))),
+            "event" => Some(Selection::Event(id)),
+            "metric" => Some(Selection::Metric(id)),
+            _ => None,
+        }
+    }
+
+}
+
+fn link(text: &str, selection: &Selection, indent: f64, width: f64) -> Button {
+    let mut button = Button::text(text).key(&selection.key());
+    button.layout_component().anchor_top_left(indent, 0.).size([width, 16.]);
+    return button;
+}
+
+#[cfg(test)]
+mod tests_legends {
+    use super::*;
+
+    #[test]
+    fn test_selection_keys_round_trip() {
+        let selections = [Selection::Creature(CreatureId::new(3)), Selection::Site(SiteId::new(7)), Selection::House(LineageId::new(1)), Selection::Artifact(ItemId::new(0)), Selection::Event(42), Selection::Metric(2)];
+        for selection in selections {
+            assert_eq!(Selection::parse(&selection.key()), Some(selection));
+        }
+        assert_eq!(Selection::parse("from:-"), None);
+    }
+
+}
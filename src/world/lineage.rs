@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::{id_vec::IdVec, rng::Rng}, resources::culture::{Culture, CultureId}};
+use crate::{commons::{id_vec::IdVec, rng::Rng}, resources::culture::{Culture, CultureId}, world::creature::CreatureId};
 
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
@@ -20,14 +20,121 @@ pub(crate) type Lineages = IdVec<Lineage>;
 pub(crate) struct Lineage {
     pub(crate) name: String,
     pub(crate) culture: CultureId,
+    /// Who speaks for the family. Only noble houses keep track of it
+    #[serde(default)]
+    pub(crate) head: Option<CreatureId>,
+    /// The first of the family to hold a title
+    #[serde(default)]
+    pub(crate) founder: Option<CreatureId>,
+    /// Arms borne by the family, once it becomes a noble house
+    #[serde(default)]
+    pub(crate) heraldry: Option<Heraldry>,
 }
 
 impl Lineage {
-    pub(crate) fn new(culture_id: CultureId, culture: &Culture) -> Self {
-        let name = culture.last_name_model.generate(&Rng::rand(), 8, 15);
+    pub(crate) fn new(culture_id: CultureId, culture: &Culture, rng: &Rng) -> Self {
+        let name = culture.last_name_model.generate(rng, 8, 15);
         Self {
             name,
-            culture: culture_id
+            culture: culture_id,
+            head: None,
+            founder: None,
+            heraldry: None,
         }
     }
-}
\ No newline at end of file
+
+    pub(crate) fn is_noble(&self) -> bool {
+        return self.heraldry.is_some()
+    }
+
+    /// Raises the family to a noble house, with the creature as its founder
+    pub(crate) fn ennoble(&mut self, founder: CreatureId, rng: &mut Rng) {
+        self.founder = Some(founder);
+        self.head = Some(founder);
+        self.heraldry = Some(Heraldry::random(rng));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Heraldry {
+    pub(crate) field: Tincture,
+    pub(crate) charge: Charge,
+    pub(crate) charge_tincture: Tincture,
+}
+
+impl Heraldry {
+
+    pub(crate) fn random(rng: &mut Rng) -> Self {
+        let field = *rng.item(&Tincture::ALL.to_vec()).expect("Not empty");
+        let charge = *rng.item(&Charge::ALL.to_vec()).expect("Not empty");
+        // A charge is never the same colour as the field
+        let others: Vec<Tincture> = Tincture::ALL.iter().filter(|tincture| **tincture != field).copied().collect();
+        let charge_tincture = *rng.item(&others).expect("Not empty");
+        return Self { field, charge, charge_tincture }
+    }
+
+    pub(crate) fn description(&self) -> String {
+        return format!("a {} {} on a field of {}", self.charge_tincture.name(), self.charge.name(), self.field.name())
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Tincture {
+    Gold,
+    Silver,
+    Red,
+    Blue,
+    Green,
+    Black,
+    Purple,
+}
+
+impl Tincture {
+
+    pub(crate) const ALL: [Tincture; 7] = [Tincture::Gold, Tincture::Silver, Tincture::Red, Tincture::Blue, Tincture::Green, Tincture::Black, Tincture::Purple];
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Tincture::Gold => "gold",
+            Tincture::Silver => "silver",
+            Tincture::Red => "red",
+            Tincture::Blue => "blue",
+            Tincture::Green => "green",
+            Tincture::Black => "black",
+            Tincture::Purple => "purple",
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Charge {
+    Lion,
+    Eagle,
+    Stag,
+    Tower,
+    Sword,
+    Wolf,
+    Boar,
+    Sun,
+}
+
+impl Charge {
+
+    pub(crate) const ALL: [Charge; 8] = [Charge::Lion, Charge::Eagle, Charge::Stag, Charge::Tower, Charge::Sword, Charge::Wolf, Charge::Boar, Charge::Sun];
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Charge::Lion => "lion",
+            Charge::Eagle => "eagle",
+            Charge::Stag => "stag",
+            Charge::Tower => "tower",
+            Charge::Sword => "sword",
+            Charge::Wolf => "wolf",
+            Charge::Boar => "boar",
+            Charge::Sun => "sun",
+        }
+    }
+
+}
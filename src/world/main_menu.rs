@@ -1,11 +1,13 @@
 use std::{ops::ControlFlow, sync::Arc};
 
 use graphics::Transformed;
-use crate::{engine::{assets::{assets, Image}, geometry::Size2D, gui::{button::Button, containers::SimpleContainer, label::Label, UIEvent, UINode}, input::InputEvent, render::RenderContext, scene::{Scene, Update}}, loadsave::{SaveFile, SaveMetadata}, loc, loc_date, GameContext};
+use crate::{engine::{assets::{assets, Image}, geometry::Size2D, gui::{button::Button, containers::SimpleContainer, label::Label, UIEvent, UINode}, input::InputEvent, render::RenderContext, scene::{Scene, Update}}, loadsave::{LoadSaveError, SaveFile, SaveMetadata}, loc, loc_date, world::{history_generator::WorldGenerationParameters, worldgen_options::WorldGenOptions}, GameContext};
 
 pub(crate) struct MainMenuScene {
     logo: Arc<Image>,
     container: SimpleContainer,
+    /// Shown instead of the menu when starting a new game
+    options: Option<WorldGenOptions>,
 }
 
 impl MainMenuScene {
@@ -16,6 +18,7 @@ impl MainMenuScene {
         let mut menu = Self {
             logo: assets().image("logo_small.png"),
             container,
+            options: None,
         };
         menu.build_main_menu();
         return menu
@@ -60,9 +63,17 @@ impl MainMenuScene {
         self.container.add(quit);
     }
 
+    /// Goes back to the save list, telling why the save couldn't be opened
+    pub(crate) fn show_load_error(&mut self, error: &LoadSaveError) {
+        self.build_load_menu();
+        self.container.layout_component().size([200., 256.]);
+        let label = Label::text(&format!("{} {}", loc!("main-menu-load-error"), error));
+        self.container.add(label);
+    }
+
     fn build_save_panel(save: &SaveMetadata) -> SimpleContainer {
         let mut container = SimpleContainer::new()
-            .layout(|l| { l.size([200., 62.]).padding([8.; 4]); })
+            .layout(|l| { l.size([200., 82.]).padding([8.; 4]); })
             .background(assets().image_sheet("gui/fade_bg.png", Size2D(180, 8)));
 
         let label = Label::text(&save.save_name.to_string());
@@ -76,6 +87,11 @@ impl MainMenuScene {
             .layout(|l| { l.size([32., 16.]); });
         container.add(button);
 
+        let button = Button::text(loc!("main-menu-legends"))
+            .key(&format!("legends:{}", save.save_file_name))
+            .layout(|l| { l.size([48., 16.]); });
+        container.add(button);
+
         return container;
     }
 
@@ -86,6 +102,10 @@ impl Scene for MainMenuScene {
 
     fn render(&mut self, ctx: &mut RenderContext, game_ctx: &mut GameContext) {
         ctx.scale(2.);
+        if let Some(options) = &mut self.options {
+            options.render(ctx, game_ctx);
+            return;
+        }
         self.container.render(&(), ctx, game_ctx);
 
         let w = self.logo.size.x() as f64 / 2.;
@@ -99,10 +119,23 @@ impl Scene for MainMenuScene {
     }
 
     fn input(&mut self, evt: &InputEvent, ctx: &mut GameContext) -> ControlFlow<MainMenuOption> {
+        if let Some(options) = &mut self.options {
+            return match options.input(evt, ctx) {
+                ControlFlow::Break(Some(params)) => ControlFlow::Break(MainMenuOption::NewGame(params)),
+                ControlFlow::Break(None) => {
+                    self.options = None;
+                    ControlFlow::Continue(())
+                },
+                ControlFlow::Continue(()) => ControlFlow::Continue(()),
+            };
+        }
         match self.container.input(&mut (), &evt, ctx) {
             ControlFlow::Break(UIEvent::ButtonClicked(button)) => {
                 match button.as_str() {
-                    "new_game" => ControlFlow::Break(MainMenuOption::NewGame),
+                    "new_game" => {
+                        self.options = Some(WorldGenOptions::new());
+                        ControlFlow::Continue(())
+                    },
                     "load_game" => {
                         self.build_load_menu();
                         ControlFlow::Continue(())
@@ -116,6 +149,9 @@ impl Scene for MainMenuScene {
                         if other.starts_with("play:") {
                             let save_file = other.split(":").last().unwrap();
                             ControlFlow::Break(MainMenuOption::LoadGame(save_file.to_string()))
+                        } else if other.starts_with("legends:") {
+                            let save_file = other.split(":").last().unwrap();
+                            ControlFlow::Break(MainMenuOption::Legends(save_file.to_string()))
                         } else {
                             ControlFlow::Continue(())
                         }
@@ -132,7 +168,9 @@ impl Scene for MainMenuScene {
 }
 
 pub(crate) enum MainMenuOption {
-    NewGame,
+    NewGame(WorldGenerationParameters),
     LoadGame(String),
+    /// Browse the history of a saved world
+    Legends(String),
     Quit,
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use super::{creature::{CauseOfDeath, Profession}, date::{Duration, WorldDate}, event::{Event, EventKind}, site::SiteType, world::World};
+
+/// Years between samples of the world statistics
+pub(crate) const METRICS_SAMPLE_YEARS: i32 = 5;
+
+/// Statistics of the world sampled through its history, to see how it changed and tune the storyteller
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct WorldMetrics {
+    pub(crate) samples: Vec<MetricsSample>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MetricsSample {
+    pub(crate) date: WorldDate,
+    pub(crate) population: u32,
+    /// Food stored in every site
+    pub(crate) food: f32,
+    pub(crate) settlements: u32,
+    /// Every site that isn't a ruin, lairs and camps included
+    pub(crate) sites: u32,
+    pub(crate) ruins: u32,
+    /// Living creatures in each profession, in the order of `Profession::ALL`
+    pub(crate) professions: Vec<u32>,
+    /// Deaths since the previous sample, by cause
+    pub(crate) deaths_old_age: u32,
+    pub(crate) deaths_disease: u32,
+    pub(crate) deaths_plague: u32,
+    pub(crate) deaths_battle: u32,
+}
+
+/// One of the statistics through time, as (year, value)
+pub(crate) struct MetricSeries {
+    /// Stable name, used as the CSV column
+    pub(crate) key: String,
+    pub(crate) title: String,
+    pub(crate) points: Vec<(i32, f64)>,
+}
+
+impl WorldMetrics {
+
+    /// Samples the world if enough time passed since the last sample
+    pub(crate) fn record(world: &mut World) {
+        let since = match world.metrics.samples.last() {
+            Some(last) => {
+                if world.date.year() - last.date.year() < METRICS_SAMPLE_YEARS {
+                    return;
+                }
+                // Deaths on the day of the last sample were already counted in it
+                last.date + Duration::days(1)
+            },
+            None => WorldDate::new(0, 1, 1),
+        };
+        let sample = MetricsSample::of(world, since);
+        world.metrics.samples.push(sample);
+    }
+
+    /// Every statistic as a series, in the order of the CSV columns
+    pub(crate) fn series(&self) -> Vec<MetricSeries> {
+        let mut series = vec!(
+            self.metric("population", "Population", |sample| sample.population as f64),
+            self.metric("food", "Food", |sample| sample.food as f64),
+            self.metric("settlements", "Settlements", |sample| sample.settlements as f64),
+            self.metric("sites", "Sites", |sample| sample.sites as f64),
+            self.metric("ruins", "Ruins", |sample| sample.ruins as f64),
+            self.metric("deaths_old_age", "Deaths of old age", |sample| sample.deaths_old_age as f64),
+            self.metric("deaths_disease", "Deaths of disease", |sample| sample.deaths_disease as f64),
+            self.metric("deaths_plague", "Deaths of plague", |sample| sample.deaths_plague as f64),
+            self.metric("deaths_battle", "Deaths in battle", |sample| sample.deaths_battle as f64),
+        );
+        for (i, profession) in Profession::ALL.iter().enumerate() {
+            let key = format!("profession_{:?}", profession).to_lowercase();
+            let title = format!("Profession: {}", profession.name());
+            series.push(self.metric(&key, &title, |sample| sample.professions.get(i).copied().unwrap_or(0) as f64));
+        }
+        return series;
+    }
+
+    fn metric<F>(&self, key: &str, title: &str, value: F) -> MetricSeries where F: Fn(&MetricsSample) -> f64 {
+        return MetricSeries {
+            key: String::from(key),
+            title: String::from(title),
+            points: self.samples.iter().map(|sample| (sample.date.year(), value(sample))).collect(),
+        }
+    }
+
+}
+
+impl MetricsSample {
+
+    fn of(world: &World, since: WorldDate) -> Self {
+        let mut sample = MetricsSample {
+            date: world.date,
+            population: 0,
+            food: 0.,
+            settlements: 0,
+            sites: 0,
+            ruins: 0,
+            professions: vec!(0; Profession::ALL.len()),
+            deaths_old_age: 0,
+            deaths_disease: 0,
+            deaths_plague: 0,
+            deaths_battle: 0,
+        };
+        for site in world.sites.iter() {
+            let site = site.borrow();
+            sample.population += site.creatures.len() as u32;
+            sample.food += site.resources.food;
+            if site.site_type == SiteType::Ruins {
+                sample.ruins += 1;
+            } else {
+                sample.sites += 1;
+            }
+            if site.site_type.is_settlement() {
+                sample.settlements += 1;
+            }
+            for creature_id in site.creatures.iter() {
+                let profession = world.creatures.get(creature_id).profession;
+                if let Some(i) = Profession::ALL.iter().position(|another| *another == profession) {
+                    sample.professions[i] += 1;
+                }
+            }
+        }
+        for (_, event) in world.events.query().kind(EventKind::Death).since(since).iter() {
+            if let Event::CreatureDeath { date: _, creature_id: _, cause_of_death } = event {
+                match cause_of_death {
+                    CauseOfDeath::OldAge => sample.deaths_old_age += 1,
+                    CauseOfDeath::Disease => sample.deaths_disease += 1,
+                    CauseOfDeath::Plague(_) => sample.deaths_plague += 1,
+                    CauseOfDeath::KilledInBattle(_, _) => sample.deaths_battle += 1,
+                }
+            }
+        }
+        return sample;
+    }
+
+}
+
+#[cfg(test)]
+mod tests_metrics {
+    use crate::world::world::fixture::WorldFixture;
+
+    use super::*;
+
+    #[test]
+    fn test_record_metrics() {
+        let mut fixture = WorldFixture::new();
+        let world = &mut fixture.world;
+        world.events.push(Event::CreatureDeath { date: world.date, creature_id: fixture.creature_a2, cause_of_death: CauseOfDeath::OldAge });
+        WorldMetrics::record(world);
+        assert_eq!(world.metrics.samples.len(), 1);
+        let sample = &world.metrics.samples[0];
+        assert_eq!(sample.deaths_old_age, 1);
+        assert_eq!(sample.population, 4);
+        assert_eq!(sample.settlements, 1);
+        assert_eq!(sample.professions[3], 4);
+
+        // Too soon for another sample
+        world.date = world.date + Duration::years(1);
+        WorldMetrics::record(world);
+        assert_eq!(world.metrics.samples.len(), 1);
+
+        world.date = world.date + Duration::years(METRICS_SAMPLE_YEARS);
+        world.events.push(Event::CreatureDeath { date: world.date, creature_id: fixture.creature_a1, cause_of_death: CauseOfDeath::Disease });
+        WorldMetrics::record(world);
+        assert_eq!(world.metrics.samples.len(), 2);
+        assert_eq!(world.metrics.samples[1].deaths_disease, 1);
+        assert_eq!(world.metrics.samples[1].deaths_old_age, 0);
+
+        let series = world.metrics.series();
+        assert_eq!(series[0].key, "population");
+        assert_eq!(series[0].points.len(), 2);
+        assert_eq!(series.len(), 9 + Profession::ALL.len());
+    }
+
+}
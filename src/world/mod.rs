@@ -1,15 +1,28 @@
 pub(crate) mod attributes;
+pub(crate) mod climate;
 pub(crate) mod date;
 pub(crate) mod creature;
+pub(crate) mod economy;
+pub(crate) mod epidemic;
 pub(crate) mod event;
+pub(crate) mod event_store;
+pub(crate) mod export;
+pub(crate) mod genealogy;
 pub(crate) mod history_generator;
 pub(crate) mod item;
+pub(crate) mod legends;
 pub(crate) mod lineage;
 pub(crate) mod main_menu;
+pub(crate) mod metrics;
 pub(crate) mod plot;
+pub(crate) mod religion;
+pub(crate) mod road;
 pub(crate) mod topology;
 pub(crate) mod site;
+pub(crate) mod title;
+pub(crate) mod war;
 pub(crate) mod world;
 pub(crate) mod worldgen;
+pub(crate) mod worldgen_options;
 pub(crate) mod history_sim;
 pub(crate) mod writer;
\ No newline at end of file
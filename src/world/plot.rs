@@ -2,7 +2,7 @@ use std::usize;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::{id_vec::IdVec, xp_table::xp_to_level}, world::{creature::{Creature, CreatureId}, world::World}};
+use crate::{commons::{id_vec::IdVec, xp_table::xp_to_level}, world::{creature::{Creature, CreatureId, Profession, Relationship}, item::ItemId, lineage::LineageId, site::{Site, SiteId}, world::World}};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Serialize, Deserialize)]
 pub(crate) struct PlotId(usize);
@@ -73,55 +73,176 @@ impl Plot {
         }
     }
 
+    pub(crate) fn supporters(&self) -> &Vec<CreatureId> {
+        return &self.supporters
+    }
+
     pub(crate) fn verify_success(&mut self, world: &World) {
         if self.status != PlotStatus::Ongoing {
             return;
         }
 
-        match self.goal {
-            PlotGoal::KillBeast(creature_id) => {
-                let creature = world.creatures.get(&creature_id);
-                if creature.death.is_some() {
-                    self.status = PlotStatus::Succeeded
-                }
-            }
-        };
-
-        let mut plotter = world.creatures.get_mut(&self.plotter);
-
-        if self.status == PlotStatus::Ongoing && plotter.death.is_some() {
+        if self.goal.is_achieved(&self.plotter, world) {
+            self.status = PlotStatus::Succeeded
+        } else if self.goal.is_lost(&self.plotter, world) || world.creatures.get(&self.plotter).death.is_some() {
             self.status = PlotStatus::Failed;
         }
 
         // If failed or suceeded
         if self.status != PlotStatus::Ongoing {
-            plotter.supports_plot = None;
+            self.conclude(world);
+        }
+    }
 
-            for supporter in self.supporters.iter() {
-                let mut supporter = world.creatures.get_mut(supporter);
-                supporter.supports_plot = None;
-            }
+    /// The plot was discovered by its victim before it could succeed
+    pub(crate) fn foil(&mut self, world: &World) {
+        if self.status != PlotStatus::Ongoing {
+            return;
+        }
+        self.status = PlotStatus::Failed;
+        self.conclude(world);
+    }
 
-            // Purges from the memory
-            self.supporters.clear();
+    fn conclude(&mut self, world: &World) {
+        let mut plotter = world.creatures.get_mut(&self.plotter);
+        plotter.supports_plot = None;
+
+        for supporter in self.supporters.iter() {
+            let mut supporter = world.creatures.get_mut(supporter);
+            supporter.supports_plot = None;
         }
+
+        // Purges from the memory
+        self.supporters.clear();
     }
 
 }
 
+/// Difficulty of founding a settlement. It takes many hands to build a village
+const FOUND_SETTLEMENT_DIFFICULTY: f64 = 5.;
+/// Difficulty added to marrying into a lineage, for each site it rules
+const RULING_LINEAGE_DIFFICULTY: f64 = 2.;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum PlotGoal {
     KillBeast(CreatureId),
+    /// Kill the murderer of a relative
+    Avenge { target: CreatureId, victim: CreatureId },
+    /// Take the place of the leader of a site
+    OverthrowLeader { site_id: SiteId, leader_id: CreatureId },
+    StealArtifact(ItemId),
+    /// Leave with a group of followers and found a new village
+    FoundSettlement,
+    /// Marry someone of a lineage
+    MarryInto(LineageId),
 }
 
 impl PlotGoal {
 
     fn difficulty(&self, world: &World) -> f64 {
         match self {
-            Self::KillBeast(creature_id) => {
+            Self::KillBeast(creature_id) | Self::Avenge { target: creature_id, victim: _ } => {
                 let creature = world.creatures.get(creature_id);
                 return xp_to_level(creature.experience) as f64
-            }
+            },
+            Self::OverthrowLeader { site_id, leader_id } => {
+                // The leader, and every guard sworn to protect them
+                let leader = world.creatures.get(leader_id);
+                let site = world.sites.get(site_id);
+                let guards: f64 = site.creatures.iter().map(|creature_id| {
+                    let creature = world.creatures.get(creature_id);
+                    match creature.profession {
+                        Profession::Guard => xp_to_level(creature.experience) as f64,
+                        _ => 0.
+                    }
+                }).sum();
+                return xp_to_level(leader.experience) as f64 + guards
+            },
+            Self::StealArtifact(item_id) => {
+                let item = world.artifacts.get(item_id);
+                match item.owner {
+                    Some(owner_id) => return xp_to_level(world.creatures.get(&owner_id).experience) as f64 + 1.,
+                    None => return 1.
+                }
+            },
+            Self::FoundSettlement => return FOUND_SETTLEMENT_DIFFICULTY,
+            Self::MarryInto(lineage_id) => {
+                let ruled_sites = world.sites.iter().filter(|site| {
+                    let site = site.borrow();
+                    let leader = site.settlement.as_ref().and_then(|settlement| settlement.leader);
+                    match leader {
+                        Some(leader_id) => world.creatures.get(&leader_id).lineage == Some(*lineage_id),
+                        None => false
+                    }
+                }).count();
+                return 1. + ruled_sites as f64 * RULING_LINEAGE_DIFFICULTY
+            },
+        }
+    }
+
+    /// The creature that stands in the way of the plot, if any
+    pub(crate) fn victim(&self, world: &World) -> Option<CreatureId> {
+        match self {
+            Self::KillBeast(creature_id) | Self::Avenge { target: creature_id, victim: _ } => Some(*creature_id),
+            Self::OverthrowLeader { site_id: _, leader_id } => Some(*leader_id),
+            Self::StealArtifact(item_id) => world.artifacts.get(item_id).owner,
+            Self::FoundSettlement | Self::MarryInto(_) => None,
+        }
+    }
+
+    pub(crate) fn is_achieved(&self, plotter_id: &CreatureId, world: &World) -> bool {
+        match self {
+            Self::KillBeast(creature_id) | Self::Avenge { target: creature_id, victim: _ } => world.creatures.get(creature_id).death.is_some(),
+            Self::OverthrowLeader { site_id, leader_id: _ } => {
+                let site = world.sites.get(site_id);
+                return site.settlement.as_ref().and_then(|settlement| settlement.leader) == Some(*plotter_id)
+            },
+            Self::StealArtifact(item_id) => world.artifacts.get(item_id).owner == Some(*plotter_id),
+            Self::FoundSettlement => world.creatures.get(plotter_id).profession == Profession::Ruler,
+            Self::MarryInto(lineage_id) => {
+                let plotter = world.creatures.get(plotter_id);
+                match plotter.spouse {
+                    Some(spouse_id) => world.creatures.get(&spouse_id).lineage == Some(*lineage_id),
+                    None => false
+                }
+            },
+        }
+    }
+
+    /// If the plot can't succeed anymore
+    pub(crate) fn is_lost(&self, plotter_id: &CreatureId, world: &World) -> bool {
+        match self {
+            Self::KillBeast(_) | Self::Avenge { target: _, victim: _ } | Self::FoundSettlement => false,
+            // Someone else got rid of the leader first
+            Self::OverthrowLeader { site_id: _, leader_id } => {
+                let leader = world.creatures.get(leader_id);
+                return leader.death.is_some() || leader.profession != Profession::Ruler
+            },
+            // Lost, or buried with its owner
+            Self::StealArtifact(item_id) => {
+                match world.artifacts.get(item_id).owner {
+                    Some(owner_id) => world.creatures.get(&owner_id).death.is_some(),
+                    None => true
+                }
+            },
+            // Married someone else
+            Self::MarryInto(_) => world.creatures.get(plotter_id).spouse.is_some(),
+        }
+    }
+
+    /// If someone in the plotter's circle would lend their support to the plot
+    pub(crate) fn appeals_to(&self, relationship: &Relationship, supporter: &Creature, plotter_site: &Site) -> bool {
+        // Anyone that shares the goal will join
+        if supporter.goals.iter().any(|goal| goal.as_plot_goal().as_ref() == Some(self)) {
+            return true
+        }
+        match self {
+            Self::KillBeast(_) => false,
+            Self::Avenge { target, victim: _ } => supporter.relationship_find(target).map(|r| r.rival_or_worse()).unwrap_or(false),
+            Self::OverthrowLeader { site_id: _, leader_id } => supporter.relationship_find(leader_id).map(|r| r.rival_or_worse()).unwrap_or(false),
+            Self::StealArtifact(_) | Self::MarryInto(_) => relationship.friend_or_better(),
+            // Only neighbours can follow the founder
+            Self::FoundSettlement => relationship.friend_or_better() && plotter_site.creatures.contains(&relationship.creature_id),
         }
     }
 
@@ -133,4 +254,60 @@ pub(crate) enum PlotStatus {
     Ongoing,
     Failed,
     Succeeded,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests_plot {
+    use crate::{commons::id_vec::Id, resources::culture::CultureId, world::{creature::{CauseOfDeath, Goal}, lineage::LineageId, site::SettlementComponent, world::fixture::WorldFixture}};
+
+    use super::*;
+
+    #[test]
+    fn test_overthrow_difficulty_counts_guards() {
+        let mut fixture = WorldFixture::new();
+        let site_id = SiteId::mock(0);
+        fixture.world.sites.get_mut(&site_id).settlement = Some(SettlementComponent { leader: Some(fixture.creature_a1), material_stock: Vec::new(), culture: CultureId::mock(0) });
+        let goal = PlotGoal::OverthrowLeader { site_id, leader_id: fixture.creature_a1 };
+        assert_eq!(goal.difficulty(&fixture.world), 1.);
+
+        fixture.creature_a3_mut().profession = Profession::Guard;
+        fixture.creature_a4_mut().profession = Profession::Guard;
+        assert_eq!(goal.difficulty(&fixture.world), 3.);
+    }
+
+    #[test]
+    fn test_overthrow_is_lost_when_leader_dies() {
+        let mut fixture = WorldFixture::new();
+        fixture.world.creatures.get_mut(&fixture.creature_a1).profession = Profession::Ruler;
+        let mut plot = Plot::new(PlotGoal::OverthrowLeader { site_id: SiteId::mock(0), leader_id: fixture.creature_a1 }, fixture.creature_a2, &fixture.world);
+
+        plot.verify_success(&fixture.world);
+        assert_eq!(plot.status, PlotStatus::Ongoing);
+
+        fixture.world.creatures.get_mut(&fixture.creature_a1).death = Some((fixture.world.date, CauseOfDeath::OldAge));
+        plot.verify_success(&fixture.world);
+        assert_eq!(plot.status, PlotStatus::Failed);
+        assert_eq!(fixture.creature_a2().supports_plot, None);
+    }
+
+    #[test]
+    fn test_goal_completed_like_its_plot() {
+        let mut fixture = WorldFixture::new();
+        let site_id = SiteId::mock(0);
+        fixture.world.sites.get_mut(&site_id).settlement = Some(SettlementComponent { leader: Some(fixture.creature_a1), material_stock: Vec::new(), culture: CultureId::mock(0) });
+        fixture.world.creatures.get_mut(&fixture.creature_a1).profession = Profession::Ruler;
+        let goal = Goal::OverthrowLeader { site_id, leader_id: fixture.creature_a1 };
+        assert!(!goal.check_completed(&fixture.creature_a2, &fixture.world));
+
+        // The plotter took the seat
+        fixture.world.sites.get_mut(&site_id).settlement.as_mut().unwrap().leader = Some(fixture.creature_a2);
+        assert!(goal.check_completed(&fixture.creature_a2, &fixture.world));
+
+        // Married outside the lineage, the goal is lost
+        let goal = Goal::MarryInto(LineageId::mock(0));
+        assert!(!goal.check_completed(&fixture.creature_a3, &fixture.world));
+        fixture.creature_a3_mut().spouse = Some(fixture.creature_a4);
+        assert!(goal.check_completed(&fixture.creature_a3, &fixture.world));
+    }
+
+}
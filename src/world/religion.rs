@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use text::capitalize;
+
+use crate::{commons::{id_vec::IdVec, rng::Rng}, resources::culture::{Culture, CultureId}, world::date::WorldDate};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Serialize, Deserialize)]
+pub(crate) struct DeityId(usize);
+impl crate::commons::id_vec::Id for DeityId {
+    fn new(id: usize) -> Self {
+        DeityId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Deities = IdVec<Deity>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Deity {
+    pub(crate) name: String,
+    pub(crate) culture: CultureId,
+    pub(crate) domain: DeityDomain,
+}
+
+impl Deity {
+
+    pub(crate) fn new(culture_id: CultureId, culture: &Culture, domain: DeityDomain, rng: &Rng) -> Self {
+        let name = culture.first_name_male_model.generate(rng, 4, 9);
+        Self {
+            name: capitalize(&name),
+            culture: culture_id,
+            domain,
+        }
+    }
+
+    pub(crate) fn description(&self) -> String {
+        return format!("{}, god of {}", self.name, self.domain.name())
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum DeityDomain {
+    Sun,
+    Moon,
+    Harvest,
+    War,
+    Death,
+    Forge,
+    Sea,
+    Wilds,
+}
+
+impl DeityDomain {
+
+    pub(crate) const ALL: [DeityDomain; 8] = [DeityDomain::Sun, DeityDomain::Moon, DeityDomain::Harvest, DeityDomain::War, DeityDomain::Death, DeityDomain::Forge, DeityDomain::Sea, DeityDomain::Wilds];
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            DeityDomain::Sun => "the sun",
+            DeityDomain::Moon => "the moon",
+            DeityDomain::Harvest => "the harvest",
+            DeityDomain::War => "war",
+            DeityDomain::Death => "death",
+            DeityDomain::Forge => "the forge",
+            DeityDomain::Sea => "the sea",
+            DeityDomain::Wilds => "the wilds",
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Serialize, Deserialize)]
+pub(crate) struct ReligionId(usize);
+impl crate::commons::id_vec::Id for ReligionId {
+    fn new(id: usize) -> Self {
+        ReligionId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Religions = IdVec<Religion>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Religion {
+    pub(crate) name: String,
+    pub(crate) culture: CultureId,
+    /// The gods worshipped by the religion
+    pub(crate) pantheon: Vec<DeityId>,
+    /// The god above all others
+    pub(crate) patron: DeityId,
+    pub(crate) founded: WorldDate,
+    /// The religion this one split from, if it was born in a schism
+    pub(crate) parent: Option<ReligionId>,
+}
+
+impl Religion {
+
+    /// If one of the religions split from the other
+    pub(crate) fn is_schism_of(&self, self_id: &ReligionId, other_id: &ReligionId, other: &Religion) -> bool {
+        return self.parent.as_ref() == Some(other_id) || other.parent.as_ref() == Some(self_id)
+    }
+
+}
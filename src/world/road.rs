@@ -0,0 +1,90 @@
+use math::Vec2i;
+use serde::{Deserialize, Serialize};
+
+use crate::{commons::id_vec::IdVec, world::{date::WorldDate, site::SiteId}};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+pub(crate) struct RoadId(usize);
+impl crate::commons::id_vec::Id for RoadId {
+    fn new(id: usize) -> Self {
+        RoadId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Roads = IdVec<Road>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum RoadKind {
+    /// A dirt track worn by people going back and forth
+    Trail,
+    /// A paved road
+    Paved,
+}
+
+impl RoadKind {
+
+    /// How much cheaper it is to travel over the road than over the bare land
+    pub(crate) fn travel_cost_multiplier(&self) -> f32 {
+        match self {
+            RoadKind::Trail => 0.8,
+            RoadKind::Paved => 0.5,
+        }
+    }
+
+}
+
+/// A road between two sites, following the terrain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Road {
+    pub(crate) site_a: SiteId,
+    pub(crate) site_b: SiteId,
+    pub(crate) kind: RoadKind,
+    pub(crate) built: WorldDate,
+    /// Every tile the road goes through, from site a to site b
+    pub(crate) path: Vec<Vec2i>,
+    /// How many caravans and travellers used the road
+    pub(crate) traffic: f32,
+}
+
+impl Road {
+
+    pub(crate) fn new(site_a: SiteId, site_b: SiteId, path: Vec<Vec2i>, built: WorldDate) -> Self {
+        Self {
+            site_a,
+            site_b,
+            kind: RoadKind::Trail,
+            built,
+            path,
+            traffic: 0.,
+        }
+    }
+
+    pub(crate) fn connects(&self, a: &SiteId, b: &SiteId) -> bool {
+        return (&self.site_a == a && &self.site_b == b) || (&self.site_a == b && &self.site_b == a)
+    }
+
+    pub(crate) fn touches(&self, site_id: &SiteId) -> bool {
+        return &self.site_a == site_id || &self.site_b == site_id
+    }
+
+    /// Where the road goes from the tile, towards the neighbouring tiles it passes through
+    pub(crate) fn directions_at(&self, xy: &Vec2i) -> Vec<Vec2i> {
+        let mut directions = Vec::new();
+        for (i, tile) in self.path.iter().enumerate() {
+            if tile != xy {
+                continue;
+            }
+            if i > 0 {
+                directions.push(self.path[i - 1] - *xy);
+            }
+            if i < self.path.len() - 1 {
+                directions.push(self.path[i + 1] - *xy);
+            }
+        }
+        return directions
+    }
+
+}
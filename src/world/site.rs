@@ -3,7 +3,7 @@ use std::ops::Add;
 use math::Vec2i;
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::{id_vec::IdVec, rng::Rng}, resources::material::MaterialId, world::date::WorldDate};
+use crate::{commons::{id_vec::IdVec, rng::Rng}, resources::{culture::CultureId, material::MaterialId, resources::Resources}, world::date::WorldDate};
 
 use super::{creature::{CreatureId, Profession}, item::ItemId};
 
@@ -20,6 +20,9 @@ impl crate::commons::id_vec::Id for SiteId {
 
 pub(crate) type Sites = IdVec<Site>;
 
+/// Years between samples of a site's population history
+const POPULATION_SAMPLE_YEARS: i32 = 5;
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct Site {
     pub(crate) xy: Vec2i,
@@ -30,6 +33,9 @@ pub(crate) struct Site {
     pub(crate) settlement: Option<SettlementComponent>,
     pub(crate) artifacts: Vec<ItemId>,
     pub(crate) population_peak: (i32, u32),
+    /// Population sampled every few years, as (year, population)
+    #[serde(default)]
+    pub(crate) population_history: Vec<(i32, u32)>,
     pub(crate) site_type: SiteType,
     pub(crate) structures: Vec<Structure>,
 }
@@ -42,9 +48,14 @@ impl Site {
         }
         match &self.site_type {
             SiteType::BanditCamp => "Bandit camp",
-            SiteType::VarningrLair => "Varningr lair",
+            SiteType::Lair => "Lair",
             SiteType::WolfPack => "Wolf den",
             SiteType::Village => "Village",
+            SiteType::City => "City",
+            SiteType::Castle => "Castle",
+            SiteType::Mine => "Mine",
+            SiteType::Monastery => "Monastery",
+            SiteType::Ruins => "Ruins",
         }
     }
 
@@ -57,27 +68,40 @@ impl Site {
         }
     }
 
-    pub(crate) fn select_new_profession(&self, rng: &mut Rng) -> Profession {
+    pub(crate) fn select_new_profession(&self, rng: &mut Rng, resources: &Resources) -> Profession {
         match self.site_type {
             SiteType::BanditCamp => Profession::Bandit,
-            SiteType::Village => {
+            SiteType::Village | SiteType::City => {
                 // Ideally this would look at what the city needs
-                let rand_job = rng.randf();
-                if rand_job < 0.8 {
-                    return Profession::Peasant;
-                } else if rand_job < 0.88 {
-                    return Profession::Farmer;
-                } else if rand_job < 0.90 {
-                    return Profession::Sculptor;
-                } else if rand_job < 0.95 {
-                    return Profession::Blacksmith;
-                } else {
-                    return Profession::Guard;
-                }
+                let culture_id = self.culture().unwrap_or(resources.cultures.id_of("culture:default"));
+                return resources.cultures.get(&culture_id).random_profession(rng);
             },
-            SiteType::VarningrLair => Profession::Beast,
+            SiteType::Lair => Profession::Beast,
             SiteType::WolfPack => Profession::Beast,
+            SiteType::Castle => Profession::Guard,
+            SiteType::Mine => Profession::Miner,
+            SiteType::Monastery => Profession::Priest,
+            SiteType::Ruins => Profession::None,
+        }
+    }
+
+    pub(crate) fn update_population_peak(&mut self, now: &WorldDate) {
+        if self.creatures.len() >= self.population_peak.1 as usize {
+            self.population_peak = (now.year(), self.creatures.len() as u32)
+        }
+    }
+
+    pub(crate) fn record_population(&mut self, now: &WorldDate) {
+        if let Some((year, _)) = self.population_history.last() {
+            if now.year() - year < POPULATION_SAMPLE_YEARS {
+                return;
+            }
         }
+        self.population_history.push((now.year(), self.creatures.len() as u32));
+    }
+
+    pub(crate) fn culture(&self) -> Option<CultureId> {
+        return self.settlement.as_ref().map(|settlement| settlement.culture)
     }
 
     pub(crate) fn structure_occupied_by(&self, creature_id: &CreatureId) -> Option<&Structure> {
@@ -90,10 +114,21 @@ impl Site {
 
 }
 
+/// What drives people away from a site
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum MigrationCause {
+    Famine,
+    War,
+    Plague,
+    Overcrowding,
+}
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct SettlementComponent {
     pub(crate) leader: Option<CreatureId>,
-    pub(crate) material_stock: Vec<(MaterialId, usize)>
+    pub(crate) material_stock: Vec<(MaterialId, usize)>,
+    #[serde(default)]
+    pub(crate) culture: CultureId,
 }
 
 impl SettlementComponent {
@@ -107,11 +142,29 @@ impl SettlementComponent {
         }
     }
 
+    /// Takes up to `number` of the material from the stock. Returns how many were taken
+    pub(crate) fn remove_material(&mut self, material: &MaterialId, number: usize) -> usize {
+        let i = self.material_stock.iter().position(|(id, _c)| id == material);
+        if let Some(i) = i {
+            let taken = number.min(self.material_stock[i].1);
+            self.material_stock[i].1 -= taken;
+            if self.material_stock[i].1 == 0 {
+                self.material_stock.remove(i);
+            }
+            return taken;
+        }
+        return 0;
+    }
+
+    pub(crate) fn material_count(&self, material: &MaterialId) -> usize {
+        return self.material_stock.iter().find(|(id, _c)| id == material).map(|(_id, count)| *count).unwrap_or(0);
+    }
+
 }
 
 #[cfg(test)]
 mod tests_site {
-    use crate::commons::id_vec::Id;
+    use crate::{commons::id_vec::Id, world::world::fixture::SiteBuilder};
     use super::*;
 
     #[test]
@@ -127,6 +180,7 @@ mod tests_site {
             settlement: None,
             artifacts: Vec::new(),
             population_peak: (0, 0),
+            population_history: Vec::new(),
             site_type: SiteType::Village,
             structures: Vec::new()
         };
@@ -136,14 +190,70 @@ mod tests_site {
         assert_eq!(site.creatures.len(), 1);
         assert_eq!(site.creatures[0].as_usize(), 1);
     }
+
+    #[test]
+    fn test_record_population() {
+        let mut site = SiteBuilder::new(SiteType::Village).creatures(vec!(CreatureId::mock(0))).build();
+        site.record_population(&WorldDate::new(1, 1, 1));
+        site.creatures.push(CreatureId::mock(1));
+        site.record_population(&WorldDate::new(3, 1, 1));
+        site.record_population(&WorldDate::new(6, 1, 1));
+        assert_eq!(site.population_history, vec!((1, 1), (6, 2)));
+    }
+
+    #[test]
+    fn test_load_varningr_lair() {
+        let mut buffer = Vec::new();
+        ciborium::into_writer(&"VarningrLair", &mut buffer).unwrap();
+        let site_type: SiteType = ciborium::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(site_type, SiteType::Lair);
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum SiteType {
     Village,
+    /// A village the storyteller picked to grow, built in districts
+    City,
+    /// The keep of a ruler, manned by guards and fed by the villages around it
+    Castle,
+    /// Miners digging the metals out of the hills
+    Mine,
+    /// Priests living apart from the world
+    Monastery,
+    /// What's left of a settlement after its last inhabitant is gone
+    Ruins,
     BanditCamp,
     WolfPack,
-    VarningrLair,
+    /// Where a great beast makes its home
+    #[serde(alias = "VarningrLair")]
+    Lair,
+}
+
+impl SiteType {
+
+    /// Sites where people live under a leader, and not beasts or outlaws
+    pub(crate) fn is_settlement(&self) -> bool {
+        match self {
+            SiteType::Village | SiteType::City | SiteType::Castle | SiteType::Mine | SiteType::Monastery => true,
+            SiteType::Ruins | SiteType::BanditCamp | SiteType::WolfPack | SiteType::Lair => false,
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            SiteType::Village => "A village.",
+            SiteType::City => "A city.",
+            SiteType::Castle => "A castle.",
+            SiteType::Mine => "A mine.",
+            SiteType::Monastery => "A monastery.",
+            SiteType::Ruins => "The ruins of a settlement.",
+            SiteType::BanditCamp => "A camp of bandits.",
+            SiteType::Lair => "The lair of a great beast.",
+            SiteType::WolfPack => "A den of wolves.",
+        }
+    }
+
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -280,9 +390,19 @@ impl StructureStatus {
 
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub(crate) enum StructureType {
     House,
     TownHall,
-    BanditCamp
+    BanditCamp,
+    Temple,
+    /// City district of merchants and stalls
+    Market,
+    /// City district where the guard is quartered
+    Barracks,
+    /// The castle itself, home of its lord and the garrison
+    Keep,
+    MineShaft,
+    /// Where the monks of a monastery live and pray
+    Cloister,
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{commons::id_vec::IdVec, world::{creature::CreatureId, date::WorldDate, site::SiteId}};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Serialize, Deserialize)]
+pub(crate) struct TitleId(usize);
+impl crate::commons::id_vec::Id for TitleId {
+    fn new(id: usize) -> Self {
+        TitleId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Titles = IdVec<Title>;
+
+/// The right to rule a site, passed down from holder to holder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Title {
+    pub(crate) name: String,
+    pub(crate) site_id: SiteId,
+    /// Everyone that held the title, and since when
+    pub(crate) holders: Vec<(WorldDate, CreatureId)>,
+}
+
+impl Title {
+
+    pub(crate) fn new(name: String, site_id: SiteId) -> Self {
+        Self {
+            name,
+            site_id,
+            holders: Vec::new(),
+        }
+    }
+
+    pub(crate) fn holder(&self) -> Option<CreatureId> {
+        return self.holders.last().map(|(_, creature_id)| *creature_id)
+    }
+
+}
@@ -1,9 +1,26 @@
 use std::{collections::{BTreeMap, VecDeque}, f32::consts::PI};
 
+use engine::astar::{AStar, MovementCost};
+use math::Vec2i;
 use noise::{NoiseFn, Perlin};
 use serde::{Deserialize, Serialize};
 use crate::{commons::{matrix_index::MatrixIndex, rng::Rng}, engine::{geometry::{Size2D, Vector2}, Point2D}, resources::biome::Biomes};
 
+/// Tiles below this elevation are under the sea
+const SEA_LEVEL: i32 = 0;
+/// Rivers spring from tiles at least this wet...
+const RIVER_SOURCE_PRECIPITATION: u8 = 150;
+/// ...and at least this high
+const RIVER_SOURCE_ELEVATION: i32 = 40;
+/// Chance that a suitable tile is the source of a river
+const RIVER_SOURCE_CHANCE: f32 = 0.02;
+/// Rivers that wander for longer than this just sink into the ground
+const MAX_RIVER_LENGTH: usize = 500;
+/// Basins that would hold a larger lake than this become a lake with no outlet
+const MAX_LAKE_SIZE: usize = 64;
+/// Coasts at or above this elevation are cliffs
+const CLIFF_ELEVATION: i32 = 16;
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct WorldTopology {
     pub(crate) size: Size2D,
@@ -12,7 +29,11 @@ pub(crate) struct WorldTopology {
     pub(crate) temperature: Vec<u8>,
     pub(crate) vegetation: Vec<f32>,
     pub(crate) soil_ferility: Vec<f32>,
-    pub(crate) region_id: Vec<u8>
+    pub(crate) region_id: Vec<u8>,
+    #[serde(default)]
+    pub(crate) water: Vec<Water>,
+    #[serde(default)]
+    pub(crate) coastline: Vec<Coastline>,
 }
 
 impl WorldTopology {
@@ -26,21 +47,89 @@ impl WorldTopology {
             temperature: vec![0; len],
             vegetation: vec![0.0; len],
             soil_ferility: vec![0.0; len],
-            region_id: vec![0; len]
+            region_id: vec![0; len],
+            water: vec![Water::None; len],
+            coastline: vec![Coastline::None; len],
         }
     }
 
+    /// Saves from before the water and coastline layers have them empty
+    pub(crate) fn fill_missing_layers(&mut self) {
+        let len = self.size.area();
+        self.water.resize(len, Water::None);
+        self.coastline.resize(len, Coastline::None);
+    }
+
     pub(crate) fn tile(&self, x: usize, y: usize) -> WorldTileData {
         let i = (y * self.size.x()) + x;
         return WorldTileData {
             // xy: Point2D(x, y),
             // elevation: self.elevation[i],
-            // precipitation: self.precipitation[i],
-            // temperature: self.temperature[i],
+            precipitation: self.precipitation[i],
+            temperature: self.temperature[i],
             // vegetation: self.vegetation[i],
             soil_fertility: self.soil_ferility[i],
             region_id: self.region_id[i],
+            water: self.water[i],
+            coastline: self.coastline[i],
+        }
+    }
+
+    /// The water on the tile. None if the position is out of the map
+    pub(crate) fn water_at(&self, xy: Vec2i) -> Option<Water> {
+        if xy.x() < 0 || xy.y() < 0 || xy.x() >= self.size.x() as i32 || xy.y() >= self.size.y() as i32 {
+            return None;
+        }
+        return Some(self.water[(xy.y() as usize * self.size.x()) + xy.x() as usize])
+    }
+
+    /// If there's a river or lake on the tile or right next to it
+    pub(crate) fn near_fresh_water(&self, xy: Vec2i) -> bool {
+        for offset in [Vec2i(0, 0), Vec2i(0, -1), Vec2i(0, 1), Vec2i(-1, 0), Vec2i(1, 0)] {
+            if let Some(Water::River) | Some(Water::Lake) = self.water_at(xy + offset) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /// Cost of travelling between two points over land, following the terrain. None if there's no way through
+    pub(crate) fn travel_cost(&self, from: Vec2i, to: Vec2i) -> Option<f32> {
+        if from == to {
+            return Some(0.);
+        }
+        let path = self.travel_path(from, to)?;
+        return Some(path.iter().skip(1).filter_map(|xy| self.tile_travel_cost(*xy)).sum());
+    }
+
+    /// The cheapest way between two points over land, both included. None if there's no way through
+    pub(crate) fn travel_path(&self, from: Vec2i, to: Vec2i) -> Option<Vec<Vec2i>> {
+        let mut astar = AStar::new(Vec2i(self.size.x() as i32, self.size.y() as i32), to);
+        astar.find_path(from, |xy| {
+            match self.tile_travel_cost(xy) {
+                Some(cost) => MovementCost::Cost(cost),
+                None => MovementCost::Impossible,
+            }
+        });
+        let path = astar.get_path(from);
+        if path.len() == 0 {
+            return None;
         }
+        return Some(path);
+    }
+
+    fn tile_travel_cost(&self, xy: Vec2i) -> Option<f32> {
+        if xy.x() < 0 || xy.y() < 0 || xy.x() >= self.size.x() as i32 || xy.y() >= self.size.y() as i32 {
+            return None;
+        }
+        let i = (xy.y() as usize * self.size.x()) + xy.x() as usize;
+        // Rivers can be forded or bridged, but not lakes or the sea
+        if self.water[i] == Water::Lake || self.water[i] == Water::Ocean {
+            return None;
+        }
+        let elevation = self.elevation[i];
+        // Climbing is slow
+        return Some(1. + elevation.max(0) as f32 / 64.);
     }
 
     pub(crate) fn plate_tectonics(&mut self, rng: &mut Rng, num_plate_tectonics: u8) {
@@ -212,6 +301,101 @@ impl WorldTopology {
         }
     }
 
+    /// Seas fill everything under the sea level, rivers flow downhill from the wet highlands and pool in basins as lakes
+    pub(crate) fn hydrology(&mut self, rng: &mut Rng) {
+        for i in 0..self.size.area() {
+            if self.elevation[i] < SEA_LEVEL {
+                self.water[i] = Water::Ocean;
+            }
+        }
+        for i in 0..self.size.area() {
+            if self.water[i] == Water::None && self.precipitation[i] >= RIVER_SOURCE_PRECIPITATION && self.elevation[i] >= RIVER_SOURCE_ELEVATION && rng.rand_chance(RIVER_SOURCE_CHANCE) {
+                self.flow_river(i);
+            }
+        }
+        for i in 0..self.size.area() {
+            if self.water[i] == Water::Ocean {
+                continue;
+            }
+            if self.neighbours(i).iter().any(|n| self.water[*n] == Water::Ocean) {
+                self.coastline[i] = match self.elevation[i] >= CLIFF_ELEVATION {
+                    true => Coastline::Cliff,
+                    false => Coastline::Beach,
+                };
+            }
+        }
+    }
+
+    fn flow_river(&mut self, source: usize) {
+        let mut path = Vec::new();
+        let mut i = source;
+        while path.len() < MAX_RIVER_LENGTH {
+            // Reached the sea, a lake, or joined another river
+            if self.water[i] != Water::None {
+                return;
+            }
+            self.water[i] = Water::River;
+            path.push(i);
+            let lowest = self.neighbours(i).into_iter()
+                .filter(|n| !path.contains(n))
+                .min_by_key(|n| self.elevation[*n]);
+            i = match lowest {
+                Some(n) if self.elevation[n] <= self.elevation[i] => n,
+                // Nowhere lower to go, the water pools
+                _ => match self.fill_lake(i) {
+                    Some(outlet) => outlet,
+                    None => return,
+                }
+            };
+        }
+    }
+
+    /// Floods the basin around the tile until the water finds a way out, and returns where it spills. None if it never does.
+    fn fill_lake(&mut self, start: usize) -> Option<usize> {
+        let mut lake = vec!(start);
+        let mut level = self.elevation[start];
+        let outlet = loop {
+            if lake.len() > MAX_LAKE_SIZE {
+                break None;
+            }
+            let lowest = lake.iter()
+                .flat_map(|i| self.neighbours(*i))
+                .filter(|n| !lake.contains(n))
+                .min_by_key(|n| self.elevation[*n]);
+            let n = match lowest {
+                Some(n) => n,
+                None => break None,
+            };
+            if self.elevation[n] < level || self.water[n] == Water::Ocean || self.water[n] == Water::Lake {
+                break Some(n);
+            }
+            level = self.elevation[n];
+            lake.push(n);
+        };
+        for i in lake {
+            self.water[i] = Water::Lake;
+        }
+        return outlet
+    }
+
+    fn neighbours(&self, i: usize) -> Vec<usize> {
+        let (x, y) = (i % self.size.x(), i / self.size.x());
+        let mut neighbours = Vec::with_capacity(4);
+        if x > 0 {
+            neighbours.push(i - 1);
+        }
+        if x < self.size.x() - 1 {
+            neighbours.push(i + 1);
+        }
+        if y > 0 {
+            neighbours.push(i - self.size.x());
+        }
+        if y < self.size.y() - 1 {
+            neighbours.push(i + self.size.x());
+        }
+        return neighbours
+    }
+
     pub(crate) fn noise(&mut self, rng: &Rng, biomes: &Biomes) {
         let rng = rng.derive("world_map");
         let n_temp = Perlin::new(rng.derive("temperature").seed());
@@ -269,11 +453,29 @@ impl WorldTopology {
 pub(crate) struct WorldTileData {
     // pub(crate) xy: Point2D,
     // pub(crate) elevation: i32,
-    // pub(crate) precipitation: u8,
-    // pub(crate) temperature: u8,
+    pub(crate) precipitation: u8,
+    pub(crate) temperature: u8,
     // pub(crate) vegetation: f32,
     pub(crate) soil_fertility: f32,
-    pub(crate) region_id: u8
+    pub(crate) region_id: u8,
+    pub(crate) water: Water,
+    pub(crate) coastline: Coastline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Water {
+    None,
+    River,
+    Lake,
+    Ocean,
+}
+
+/// What the land looks like where it meets the sea
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Coastline {
+    None,
+    Beach,
+    Cliff,
 }
 
 
@@ -306,20 +508,83 @@ mod tests {
             temperature: (0, 5),
             vegetation: (0.0, 0.0),
             soil_fertility_range: (0.8, 1.2),
+            materials: Vec::new(),
         });
         biomes.add("b", Biome {
             elevation: (0, 2000),
             temperature: (0, 5),
             vegetation: (0.0, 0.1),
             soil_fertility_range: (0.8, 1.2),
+            materials: Vec::new(),
         });
 
+        world_a.hydrology(&mut rng_a);
+        world_b.hydrology(&mut rng_b);
+        compare(&world_a, &world_b);
+
         world_a.noise(&mut rng_a, &biomes);
         world_b.noise(&mut rng_b, &biomes);
         compare(&world_a, &world_b);
 
     }
 
+    #[test]
+    fn test_load_without_water_layers() {
+        let world = WorldTopology::new(Size2D(8, 8));
+        let mut buffer = Vec::new();
+        ciborium::into_writer(&world, &mut buffer).unwrap();
+
+        // Saves from before the water layers don't have them
+        let mut value: ciborium::Value = ciborium::from_reader(buffer.as_slice()).unwrap();
+        if let ciborium::Value::Map(fields) = &mut value {
+            fields.retain(|(key, _)| key.as_text() != Some("water") && key.as_text() != Some("coastline"));
+        }
+        let mut buffer = Vec::new();
+        ciborium::into_writer(&value, &mut buffer).unwrap();
+
+        let mut world: WorldTopology = ciborium::from_reader(buffer.as_slice()).unwrap();
+        world.fill_missing_layers();
+        assert_eq!(world.water_at(Vec2i(7, 7)), Some(Water::None));
+        assert_eq!(world.tile(7, 7).coastline, Coastline::None);
+    }
+
+    #[test]
+    fn test_travel_cost() {
+        let mut world = WorldTopology::new(Size2D(8, 8));
+        assert_eq!(world.travel_cost(Vec2i(1, 1), Vec2i(1, 1)), Some(0.));
+        assert_eq!(world.travel_cost(Vec2i(1, 1), Vec2i(4, 1)), Some(3.));
+
+        // Mountains in the way make the trip longer
+        for y in 0..7 {
+            world.elevation[(y * 8) + 2] = 128;
+        }
+        assert_eq!(world.travel_cost(Vec2i(1, 1), Vec2i(4, 1)), Some(5.));
+    }
+
+    #[test]
+    fn test_hydrology() {
+        let mut world = WorldTopology::new(Size2D(8, 3));
+        for y in 0..3 {
+            for x in 0..8 {
+                world.elevation[(y * 8) + x] = 60 - (x as i32 * 10);
+            }
+        }
+        // A basin halfway down, and a cliff by the sea
+        world.elevation[8 + 3] = 5;
+        world.elevation[6] = 20;
+        world.hydrology(&mut Rng::seeded("hydrology"));
+        assert_eq!(world.water[7], Water::Ocean);
+
+        world.flow_river(8);
+        let row: Vec<Water> = world.water[8..16].to_vec();
+        assert_eq!(row, vec!(Water::River, Water::River, Water::River, Water::Lake, Water::Lake, Water::River, Water::River, Water::Ocean));
+        assert_eq!(world.coastline[8 + 6], Coastline::Beach);
+        assert_eq!(world.coastline[6], Coastline::Cliff);
+        assert_eq!(world.coastline[8 + 5], Coastline::None);
+        assert!(world.near_fresh_water(Vec2i(4, 0)));
+        assert!(!world.near_fresh_water(Vec2i(7, 0)));
+    }
+
     fn compare(world_a: &WorldTopology, world_b: &WorldTopology) {
         for i in 0..world_a.size.area() {
             assert_eq!(world_a.elevation[i], world_b.elevation[i]);
@@ -328,6 +593,8 @@ mod tests {
             assert_eq!(world_a.vegetation[i], world_b.vegetation[i]);
             assert_eq!(world_a.soil_ferility[i], world_b.soil_ferility[i]);
             assert_eq!(world_a.region_id[i], world_b.region_id[i]);
+            assert_eq!(world_a.water[i], world_b.water[i]);
+            assert_eq!(world_a.coastline[i], world_b.coastline[i]);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{commons::id_vec::IdVec, resources::culture::CultureId, world::{creature::CreatureId, date::WorldDate, site::SiteId}};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Serialize, Deserialize)]
+pub(crate) struct WarId(usize);
+
+impl crate::commons::id_vec::Id for WarId {
+    fn new(id: usize) -> Self {
+        WarId(id)
+    }
+    fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) type Wars = IdVec<War>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct War {
+    pub(crate) attacker: SiteId,
+    pub(crate) defender: SiteId,
+    pub(crate) cause: CasusBelli,
+    pub(crate) start: WorldDate,
+    pub(crate) status: WarStatus,
+    /// Creatures lost by the attacker and by the defender
+    pub(crate) casualties: (u32, u32),
+}
+
+impl War {
+
+    pub(crate) fn new(attacker: SiteId, defender: SiteId, cause: CasusBelli, start: WorldDate) -> Self {
+        Self {
+            attacker,
+            defender,
+            cause,
+            start,
+            status: WarStatus::Campaign,
+            casualties: (0, 0),
+        }
+    }
+
+    pub(crate) fn is_ongoing(&self) -> bool {
+        match self.status {
+            WarStatus::Campaign | WarStatus::Siege(_) => true,
+            WarStatus::Peace(_) | WarStatus::Annexed(_) => false,
+        }
+    }
+
+    pub(crate) fn involves(&self, site_id: &SiteId) -> bool {
+        return &self.attacker == site_id || &self.defender == site_id;
+    }
+
+    /// The other side of the war
+    pub(crate) fn enemy_of(&self, site_id: &SiteId) -> SiteId {
+        if &self.attacker == site_id {
+            return self.defender
+        }
+        return self.attacker
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum CasusBelli {
+    /// The attacker is starving and wants the defender's food
+    FoodShortage,
+    /// The attacker's leader holds a grudge against the defender's leader
+    Grudge(CreatureId),
+    /// The attacker's people despise the defender's culture
+    Hostility(CultureId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum WarStatus {
+    /// Armies are marching and fighting in the field
+    Campaign,
+    /// The defender is besieged since the date
+    Siege(WorldDate),
+    Peace(WorldDate),
+    /// The defender was conquered and annexed by the attacker
+    Annexed(WorldDate),
+}
@@ -1,15 +1,25 @@
-use std::{fs::File, io::Write};
+use std::{collections::HashSet, fs::File, io::Write};
 
 use common::error::Error;
 use math::{rng::Rng, Vec2i};
 use serde::{Deserialize, Serialize};
 
-use crate::{commons::rng::Rng as OldRng, engine::geometry::Coord2, game::codex::Codex, history_trace, info, resources::resources::resources, warn, world::{creature::{CauseOfDeath, Creature, CreatureGender, Goal, Profession}, history_generator::WorldGenerationParameters, item::{ItemId, Items}, plot::Plots, site::{Site, SiteId, SiteResources, SiteType, Structure, StructureType}}, Event, Resources};
+use crate::{commons::rng::Rng as OldRng, engine::geometry::Coord2, game::codex::Codex, history_trace, info, resources::resources::resources, warn, world::{climate::Disaster, creature::{CauseOfDeath, Creature, CreatureGender, Goal, Profession}, epidemic::Epidemics, history_generator::WorldGenerationParameters, history_sim::history_simulation::HistoryState, item::{ItemId, Items}, lineage::LineageId, plot::Plots, religion::{Deities, Religions}, road::{RoadId, Roads}, site::{Site, SiteId, SiteResources, SiteType, Structure, StructureType}, title::{Title, TitleId, Titles}, war::{WarId, Wars}}, Event, Resources};
 
-use super::{creature::{CreatureId, Creatures}, date::WorldDate, lineage::Lineages, topology::WorldTopology, site::Sites};
+use super::{creature::{CreatureId, Creatures}, date::WorldDate, economy::TradeRoutes, event_store::EventStore, lineage::Lineages, metrics::WorldMetrics, topology::WorldTopology, site::Sites};
 
 use crate::commons::id_vec::IdVec;
 
+/// Chance that a relative that swore revenge on a murderer will act on it
+const AVENGE_CHANCE: f32 = 0.8;
+/// Chance that a sibling passed over in an inheritance covets each of the artifacts
+const COVET_INHERITANCE_CHANCE: f32 = 0.3;
+/// Chance that an unmarried creature wants to marry into the family of a new leader
+const MARRY_INTO_CHANCE: f32 = 0.05;
+/// Chance that a conquered creature wants to leave and found a village of their own
+const FOUND_SETTLEMENT_CHANCE: f32 = 0.1;
+const PLOTTER_MIN_AGE: i32 = 18;
+
 #[derive(Serialize, Deserialize)]
 pub(crate) struct World {
     pub(crate) date: WorldDate,
@@ -19,9 +29,30 @@ pub(crate) struct World {
     pub(crate) lineages: Lineages,
     pub(crate) creatures: Creatures,
     pub(crate) plots: Plots,
-    pub(crate) events: Vec<Event>,
+    #[serde(default)]
+    pub(crate) epidemics: Epidemics,
+    #[serde(default)]
+    pub(crate) disasters: Vec<Disaster>,
+    #[serde(default)]
+    pub(crate) titles: Titles,
+    #[serde(default)]
+    pub(crate) roads: Roads,
+    #[serde(default)]
+    pub(crate) wars: Wars,
+    #[serde(default)]
+    pub(crate) deities: Deities,
+    #[serde(default)]
+    pub(crate) religions: Religions,
+    pub(crate) events: EventStore,
     pub(crate) artifacts: Items,
     pub(crate) codex: Codex,
+    #[serde(default)]
+    pub(crate) metrics: WorldMetrics,
+    /// Where the history simulation stopped, to resume it
+    #[serde(default)]
+    pub(crate) history_state: Option<HistoryState>,
+    #[serde(skip)]
+    trade_routes: TradeRoutes,
     played_creature: Option<CreatureId>
 }
 
@@ -36,9 +67,19 @@ impl World {
             creatures: Creatures::new(),
             lineages: Lineages::new(),
             plots: Plots::new(),
+            epidemics: Epidemics::new(),
+            disasters: Vec::new(),
+            titles: Titles::new(),
+            roads: Roads::new(),
+            wars: Wars::new(),
+            deities: Deities::new(),
+            religions: Religions::new(),
             artifacts: IdVec::new(),
-            events: Vec::new(),
+            events: EventStore::new(),
             codex: Codex::new(),
+            metrics: WorldMetrics::default(),
+            history_state: None,
+            trade_routes: TradeRoutes::default(),
             played_creature: None,
         }
     }
@@ -51,7 +92,7 @@ impl World {
         let mut candidate = None;
         'outer: for site_id in self.sites.iter_ids::<SiteId>() {
             let site = self.sites.get(&site_id);
-            if site.site_type == SiteType::Village {
+            if site.site_type.is_settlement() {
                 for creature_id in site.creatures.iter() {
                     let creature = self.creatures.get(creature_id);
                     let age = (self.date - creature.birth).get_years();
@@ -69,7 +110,7 @@ impl World {
             // Major sites
             for site_id in self.sites.iter_ids::<SiteId>() {
                 let site = self.sites.get(&site_id);
-                if site.creatures.len() > 0 && site.site_type == SiteType::Village {
+                if site.creatures.len() > 0 && site.site_type.is_settlement() {
                     self.codex.site_mut(&site_id);
                 }
             }
@@ -82,15 +123,36 @@ impl World {
             myself.add_birth();
             myself.add_death();
             myself.add_appearance();
-            for (i, event) in self.events.iter().enumerate() {
-                if event.relates_to_creature(&candidate.0) {
-                    myself.add_event(i);
-                }
+            for i in self.events.query().creature(&candidate.0).ids() {
+                myself.add_event(i);
             }
 
             // Information about my family
             let myself = self.creatures.get(&candidate.0);
 
+            // Lore of my faith
+            if let Some(faith) = myself.faith {
+                let religion = self.codex.religion_mut(&faith);
+                for (i, event) in self.events.iter().enumerate() {
+                    if event.related_religions().contains(&faith) {
+                        religion.add_event(i);
+                    }
+                }
+            }
+
+            // History of my family
+            let noble_lineage = myself.lineage.filter(|lineage_id| self.lineages.get(lineage_id).is_noble());
+            if let Some(lineage_id) = noble_lineage {
+                let events: Vec<usize> = self.events.iter().enumerate()
+                    .filter(|(_, event)| event.related_lineages(self).contains(&lineage_id))
+                    .map(|(i, _)| i)
+                    .collect();
+                let house = self.codex.house_mut(&lineage_id);
+                for i in events {
+                    house.add_event(i);
+                }
+            }
+
             let father = self.codex.creature_mut(&myself.father);
             father.add_name();
             father.add_appearance();
@@ -130,6 +192,12 @@ impl World {
         for artifact in event.related_artifacts() {
             self.codex.artifact_mut(&artifact).add_event(event_id);
         }
+        for religion in event.related_religions() {
+            self.codex.religion_mut(&religion).add_event(event_id);
+        }
+        for lineage in event.related_lineages(self) {
+            self.codex.house_mut(&lineage).add_event(event_id);
+        }
     }
 
     pub(crate) fn creature_desc(&self, creature_id: &CreatureId, resources: &Resources) -> String {
@@ -154,6 +222,41 @@ impl World {
         return self.played_creature.as_ref();
     }
 
+    pub(crate) fn site_ongoing_war(&self, site_id: &SiteId) -> Option<WarId> {
+        for (war_id, war) in self.wars.iter_id_val::<WarId>() {
+            let war = war.borrow();
+            if war.is_ongoing() && war.involves(site_id) {
+                return Some(war_id)
+            }
+        }
+        return None
+    }
+
+    /// Cost for a caravan to travel between the two sites, if they are connected at all. Cheaper if there's a road
+    pub(crate) fn trade_route_cost(&mut self, a: &SiteId, b: &SiteId) -> Option<f32> {
+        let cost = match self.trade_routes.get(a, b) {
+            Some(cost) => cost,
+            None => {
+                let a_xy = self.sites.get(a).xy;
+                let b_xy = self.sites.get(b).xy;
+                let cost = self.map.travel_cost(a_xy, b_xy);
+                self.trade_routes.insert(a, b, cost);
+                cost
+            }
+        };
+        let multiplier = match self.road_between(a, b) {
+            Some(road_id) => self.roads.get(&road_id).kind.travel_cost_multiplier(),
+            None => 1.,
+        };
+        return cost.map(|cost| cost * multiplier);
+    }
+
+    pub(crate) fn road_between(&self, a: &SiteId, b: &SiteId) -> Option<RoadId> {
+        return self.roads.iter_id_val::<RoadId>()
+            .find(|(_, road)| road.borrow().connects(a, b))
+            .map(|(road_id, _)| road_id)
+    }
+
     pub(crate) fn get_site_at(&self, coord: &Coord2) -> Option<SiteId> {
         for site_id in self.sites.iter_ids::<SiteId>() {
             let site = self.sites.get(&site_id);
@@ -169,7 +272,7 @@ impl World {
 #[cfg(test)]
 pub(crate) mod fixture {
 
-    use crate::{commons::id_vec::{Identified, IdentifiedMut}, engine::geometry::Size2D, world::{creature::{Creature, CreatureGender, Profession, SIM_FLAG_INTELIGENT}, lineage::Lineage, site::{Site, SiteId, SiteResources}}};
+    use crate::{commons::id_vec::{Identified, IdentifiedMut}, engine::geometry::Size2D, resources::{culture::CultureId, material::MaterialId}, world::{creature::{Creature, CreatureGender, Profession, SIM_FLAG_INTELIGENT}, lineage::Lineage, site::{SettlementComponent, Site, SiteId, SiteResources, SiteType}}};
 
     use super::*;
 
@@ -196,6 +299,7 @@ pub(crate) mod fixture {
                 history_length: 0,
                 number_of_seed_cities: 0,
                 seed_cities_population: 0,
+                storyteller: String::from("balanced"),
                 st_strength: 0.,
                 st_city_count: 0,
                 st_city_population: 0,
@@ -209,10 +313,11 @@ pub(crate) mod fixture {
             resources.load();
 
             let human_id = resources.species.id_of("species:human");
-            let culture = resources.cultures.random();
+            let culture = resources.cultures.id_of("culture:default");
 
-            let lineage_1 = world.lineages.add(Lineage::new(culture, &resources.cultures.get(&culture)));
-            let lineage_2 = world.lineages.add(Lineage::new(culture, &resources.cultures.get(&culture)));
+            let rng = OldRng::seeded("fixture");
+            let lineage_1 = world.lineages.add(Lineage::new(culture, &resources.cultures.get(&culture), &rng.derive(1)));
+            let lineage_2 = world.lineages.add(Lineage::new(culture, &resources.cultures.get(&culture), &rng.derive(2)));
 
             let creature_a1 = world.creatures.add(Creature {
                 birth: WorldDate::new(1, 1, 1),
@@ -231,6 +336,7 @@ pub(crate) mod fixture {
                 spouse: None,
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
 
             let creature_a2 = world.creatures.add(Creature {
@@ -250,6 +356,7 @@ pub(crate) mod fixture {
                 spouse: None,
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
 
             let creature_a3 = world.creatures.add(Creature {
@@ -269,6 +376,7 @@ pub(crate) mod fixture {
                 spouse: None,
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
 
             let creature_a4 = world.creatures.add(Creature {
@@ -288,20 +396,14 @@ pub(crate) mod fixture {
                 spouse: None,
                 goals: Vec::new(),
                 supports_plot: None,
+                faith: None,
             });
 
-            let _: SiteId = world.sites.add(Site {
-                artifacts: Vec::new(),
-                cemetery: Vec::new(),
-                name: None,
-                creatures: vec!(creature_a1, creature_a2, creature_a3, creature_a4),
-                population_peak: (2, 1),
-                resources: SiteResources { food: 0. },
-                settlement: None,
-                site_type: crate::world::site::SiteType::Village,
-                xy: Vec2i(1, 1),
-                structures: Vec::new()
-            });
+            let _: SiteId = world.sites.add(SiteBuilder::new(SiteType::Village)
+                .xy(Vec2i(1, 1))
+                .creatures(vec!(creature_a1, creature_a2, creature_a3, creature_a4))
+                .population_peak((2, 1))
+                .build());
 
             return WorldFixture {
                 world,
@@ -338,6 +440,72 @@ pub(crate) mod fixture {
 
     }
 
+    /// An empty site at the origin, with no food and no settlement, to fill with what the test needs
+    pub(crate) struct SiteBuilder {
+        site: Site,
+    }
+
+    impl SiteBuilder {
+
+        pub(crate) fn new(site_type: SiteType) -> Self {
+            let site = Site {
+                artifacts: Vec::new(),
+                cemetery: Vec::new(),
+                name: None,
+                creatures: Vec::new(),
+                population_peak: (0, 0),
+                population_history: Vec::new(),
+                resources: SiteResources { food: 0. },
+                settlement: None,
+                site_type,
+                xy: Vec2i(0, 0),
+                structures: Vec::new()
+            };
+            return Self { site }
+        }
+
+        pub(crate) fn xy(mut self, xy: Vec2i) -> Self {
+            self.site.xy = xy;
+            return self;
+        }
+
+        pub(crate) fn creatures(mut self, creatures: Vec<CreatureId>) -> Self {
+            self.site.creatures = creatures;
+            return self;
+        }
+
+        pub(crate) fn population_peak(mut self, population_peak: (i32, u32)) -> Self {
+            self.site.population_peak = population_peak;
+            return self;
+        }
+
+        pub(crate) fn food(mut self, food: f32) -> Self {
+            self.site.resources.food = food;
+            return self;
+        }
+
+        /// A settlement of the culture, with no leader and nothing in stock
+        pub(crate) fn settlement(mut self, culture: CultureId) -> Self {
+            self.site.settlement = Some(SettlementComponent { leader: None, material_stock: Vec::new(), culture });
+            return self;
+        }
+
+        pub(crate) fn leader(mut self, leader: CreatureId) -> Self {
+            self.site.settlement.as_mut().expect("Leaders need a settlement").leader = Some(leader);
+            return self;
+        }
+
+        pub(crate) fn material_stock(mut self, material_stock: Vec<(MaterialId, usize)>) -> Self {
+            self.site.settlement.as_mut().expect("Stock needs a settlement").material_stock = material_stock;
+            return self;
+        }
+
+        pub(crate) fn build(self) -> Site {
+            return self.site;
+        }
+
+    }
+
 }
 
 // World operations ----------------------------------------------------------------------------
@@ -350,7 +518,7 @@ impl World {
         let father_id = mother.spouse.ok_or("Woman with no spouse trying to have a child")?;
         let father = self.creatures.get(&father_id);
         let lineage = father.lineage.clone();
-        let gender = CreatureGender::random_det(rng);
+        let gender = CreatureGender::random(rng);
         let child = Creature {
             birth: self.date.clone(),
             death: None,
@@ -368,6 +536,8 @@ impl World {
             relationships: vec!(),
             goals: vec!(),
             supports_plot: None,
+            // Raised in the mother's faith
+            faith: mother.faith,
         };
 
         drop(mother);
@@ -458,6 +628,7 @@ impl World {
                 settlement: None,
                 name: None,
                 population_peak: (0, 0),
+                population_history: Vec::new(),
                 site_type: SiteType::BanditCamp,
                 resources: SiteResources {
                     food: 1.
@@ -539,8 +710,38 @@ impl World {
             }
 
             if let CauseOfDeath::KilledInBattle(killer_id, _) = &cause_of_death {
+                // The family swears revenge
+                let mut relatives = creature.offspring.clone();
+                relatives.push(creature.father);
+                relatives.push(creature.mother);
+                if let Some(spouse_id) = creature.spouse {
+                    relatives.push(spouse_id);
+                }
+                relatives.retain(|id| id != &CreatureId::ancients() && id != killer_id);
+                for relative_id in relatives.iter() {
+                    let mut relative = self.creatures.get_mut(relative_id);
+                    if relative.death.is_some() || !relative.sim_flag_is_inteligent() {
+                        continue
+                    }
+                    let killer = self.creatures.get(killer_id);
+                    let killer_relationship = relative.relationship_find_mut_or_insert(relative_id, *killer_id, &killer);
+                    killer_relationship.add_opinion(-75);
+
+                    if killer_relationship.mortal_enemy_or_worse() {
+                        let mut rng = OldRng::seeded(self.generation_parameters.seed).derive((*relative_id, *killer_id, now));
+                        if rng.rand_chance(AVENGE_CHANCE) {
+                            let goal = Goal::Avenge { target: *killer_id, victim: creature_id };
+                            history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", relative_id, goal);
+                            relative.add_goal(goal);
+                        }
+                    }
+                }
+
                 for relationship in creature.relationships.iter() {
                     let relationship_creature_id = relationship.creature_id;
+                    if relatives.contains(&relationship_creature_id) {
+                        continue
+                    }
                     let mut relationship_creature = self.creatures.get_mut(&relationship_creature_id);
                     let relationship = relationship_creature.relationship_find(&creature_id);
                     if let Some(relationship) = relationship {
@@ -555,9 +756,9 @@ impl World {
                             killer_relationship.add_opinion(-75);
 
                             if killer_relationship.mortal_enemy_or_worse() {
-                                // TODO(IhlgIYVA): Determinate
+                                let mut rng = OldRng::seeded(self.generation_parameters.seed).derive((relationship_creature_id, *killer_id, now));
                                 // TODO(IhlgIYVA): Magic number
-                                if OldRng::rand().rand_chance(0.8) {
+                                if rng.rand_chance(0.8) {
                                     let goal = Goal::KillBeast(*killer_id);
                                     history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", relationship_creature_id, goal);
                                     relationship_creature.goals.push(goal);
@@ -572,8 +773,16 @@ impl World {
             // Purges unnecessary data after death
             creature.relationships.clear();
 
+            let lineage = creature.lineage;
             drop(creature);
 
+            if let Some(lineage_id) = lineage {
+                let was_head = self.lineages.get(&lineage_id).head == Some(creature_id);
+                if was_head {
+                    self.lineage_pass_headship(lineage_id, creature_id);
+                }
+            }
+
             if has_possession {
                 if let Some(inheritor_id) = inheritor {
                     self.transfer_inventory(creature_id, inheritor_id);
@@ -612,6 +821,27 @@ impl World {
         for item in inventory.iter() {
             self.record_event(Event::InheritedArtifact { date: self.date.clone(), creature_id: new_owner_id, from: current_id, item: *item });
         }
+
+        // The siblings that were passed over might covet the inheritance
+        let siblings = self.creatures.get(&current_id).offspring.clone();
+        for sibling_id in siblings.iter() {
+            if sibling_id == &new_owner_id || self.is_played_creature(sibling_id) {
+                continue
+            }
+            let mut sibling = self.creatures.get_mut(sibling_id);
+            if sibling.death.is_some() {
+                continue
+            }
+            for item in inventory.iter() {
+                let mut rng = OldRng::seeded(self.generation_parameters.seed).derive((*sibling_id, *item, self.date));
+                if rng.rand_chance(COVET_INHERITANCE_CHANCE) {
+                    let goal = Goal::StealArtifact(*item);
+                    history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", sibling_id, goal);
+                    sibling.add_goal(goal);
+                }
+            }
+        }
+
         let mut new_owner = self.creatures.get_mut(&new_owner_id);
         new_owner.details().inventory.append(&mut inventory);
     }
@@ -631,7 +861,14 @@ impl World {
 
     // Sites
 
-    pub(crate) fn site_change_leader(&mut self, site_id: &SiteId, new_leader: CreatureId) -> Result<(), &'static str> {
+    pub(crate) fn site_change_leader(&mut self, site_id: &SiteId, new_leader: CreatureId, resources: &Resources) -> Result<(), &'static str> {
+        self.site_install_leader(site_id, new_leader, resources)?;
+        self.record_event(Event::NewLeaderElected { date: self.date.clone(), site_id: *site_id, creature_id: new_leader });
+        return Ok(())
+    }
+
+    /// Makes the creature the leader of the site, without recording how they came to power
+    pub(crate) fn site_install_leader(&mut self, site_id: &SiteId, new_leader: CreatureId, resources: &Resources) -> Result<(), &'static str> {
         {
             let mut leader = self.creatures.get_mut(&new_leader);
             leader.profession = Profession::Ruler;
@@ -658,10 +895,182 @@ impl World {
                 }
             }
         }
-        self.record_event(Event::NewLeaderElected { date: self.date.clone(), site_id: *site_id, creature_id: new_leader });
+        self.site_grant_title(site_id, new_leader, resources);
+        self.site_add_leader_goals(site_id, new_leader);
         return Ok(())
     }
 
+    pub(crate) fn site_title(&self, site_id: &SiteId) -> Option<TitleId> {
+        return self.titles.iter_id_val::<TitleId>().find(|(_, title)| title.borrow().site_id == *site_id).map(|(title_id, _)| title_id)
+    }
+
+    /// The title of the site passes to the new leader, and their family becomes a noble house if it wasn't already
+    fn site_grant_title(&mut self, site_id: &SiteId, holder_id: CreatureId, resources: &Resources) {
+        let title_id = match self.site_title(site_id) {
+            Some(title_id) => title_id,
+            None => {
+                let site = self.sites.get(site_id);
+                let ruler_title = match site.culture() {
+                    Some(culture_id) => resources.cultures.get(&culture_id).ruler_title.clone(),
+                    None => String::from("Lord"),
+                };
+                let title = Title::new(format!("{} of {}", ruler_title, site.name()), *site_id);
+                drop(site);
+                self.titles.add::<TitleId>(title)
+            }
+        };
+        self.titles.get_mut(&title_id).holders.push((self.date, holder_id));
+
+        let lineage_id = match self.creatures.get(&holder_id).lineage {
+            Some(lineage_id) => lineage_id,
+            None => return,
+        };
+        let mut lineage = self.lineages.get_mut(&lineage_id);
+        if lineage.is_noble() {
+            lineage.head = Some(holder_id);
+        } else {
+            let mut rng = OldRng::seeded(self.generation_parameters.seed).derive(("heraldry", lineage_id));
+            lineage.ennoble(holder_id, &mut rng);
+            drop(lineage);
+            history_trace!("noble_house_founded lineage_id:{:?} creature_id:{:?}", lineage_id, holder_id);
+            self.record_event(Event::NobleHouseFounded { date: self.date, lineage_id, creature_id: holder_id });
+        }
+    }
+
+    /// The headship of a house passes to whoever of the family rules a site, or else to its eldest member.
+    /// The family is found walking the parents, spouses and children of the old head, living or dead
+    fn lineage_pass_headship(&mut self, lineage_id: LineageId, old_head: CreatureId) {
+        let mut heir: Option<(CreatureId, WorldDate, bool)> = None;
+        let mut visited = HashSet::from([old_head, CreatureId::ancients()]);
+        let mut family = vec!(old_head);
+        while let Some(creature_id) = family.pop() {
+            let creature = self.creatures.get(&creature_id);
+            let relatives = [creature.father, creature.mother].into_iter().chain(creature.spouse).chain(creature.offspring.iter().copied());
+            for relative_id in relatives {
+                if visited.insert(relative_id) && self.creatures.get(&relative_id).lineage == Some(lineage_id) {
+                    family.push(relative_id);
+                }
+            }
+            if creature.death.is_some() {
+                continue
+            }
+            let ruler = creature.profession == Profession::Ruler;
+            let better = match &heir {
+                None => true,
+                Some((heir_id, birth, heir_ruler)) => (ruler && !heir_ruler) || (ruler == *heir_ruler && (creature.birth, creature_id) < (*birth, *heir_id)),
+            };
+            if better {
+                heir = Some((creature_id, creature.birth, ruler));
+            }
+        }
+        self.lineages.get_mut(&lineage_id).head = heir.map(|(creature_id, _, _)| creature_id);
+    }
+
+    /// Those that despise the new leader want them gone, and the ambitious want to marry into their family
+    fn site_add_leader_goals(&mut self, site_id: &SiteId, leader_id: CreatureId) {
+        let lineage = self.creatures.get(&leader_id).lineage;
+        let site = self.sites.get(site_id);
+        for creature_id in site.creatures.iter() {
+            if creature_id == &leader_id || self.is_played_creature(creature_id) {
+                continue
+            }
+            let mut creature = self.creatures.get_mut(creature_id);
+            let age = (self.date - creature.birth).get_years();
+            if creature.death.is_some() || !creature.sim_flag_is_inteligent() || age < PLOTTER_MIN_AGE {
+                continue
+            }
+
+            let rival = creature.relationship_find(&leader_id).map(|relationship| relationship.rival_or_worse()).unwrap_or(false);
+            if rival {
+                let goal = Goal::OverthrowLeader { site_id: *site_id, leader_id };
+                history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", creature_id, goal);
+                creature.add_goal(goal);
+            }
+
+            if let Some(lineage) = lineage {
+                if creature.spouse.is_none() && creature.lineage != Some(lineage) {
+                    let mut rng = OldRng::seeded(self.generation_parameters.seed).derive((*creature_id, leader_id, self.date));
+                    if rng.rand_chance(MARRY_INTO_CHANCE) {
+                        let goal = Goal::MarryInto(lineage);
+                        history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", creature_id, goal);
+                        creature.add_goal(goal);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves every creature of the conquered site, with their households, into the conqueror site
+    pub(crate) fn site_annex(&mut self, conquered_id: &SiteId, conqueror_id: &SiteId) {
+        let mut conquered = self.sites.get_mut(conquered_id);
+        let mut conqueror = self.sites.get_mut(conqueror_id);
+
+        // The deposed leader is just another subject now
+        let mut deposed_leader = None;
+        if let Some(settlement) = conquered.settlement.as_mut() {
+            if let Some(leader_id) = settlement.leader.take() {
+                let mut leader = self.creatures.get_mut(&leader_id);
+                if leader.death.is_none() {
+                    leader.profession = Profession::Peasant;
+                }
+                deposed_leader = Some(leader_id);
+            }
+        }
+
+        for structure in conquered.structures.iter_mut() {
+            let household = structure.occupants_take(self.date);
+            if household.len() == 0 {
+                continue;
+            }
+            let mut house = Structure::new(StructureType::House);
+            for creature_id in household {
+                house.add_ocuppant(creature_id);
+            }
+            conqueror.structures.push(house);
+        }
+
+        let creatures: Vec<CreatureId> = conquered.creatures.drain(..).collect();
+
+        // The deposed leader wants their seat back, and some of their people a home of their own
+        let conqueror_leader = conqueror.settlement.as_ref().and_then(|settlement| settlement.leader);
+        for creature_id in creatures.iter() {
+            if self.is_played_creature(creature_id) {
+                continue
+            }
+            let mut creature = self.creatures.get_mut(creature_id);
+            let age = (self.date - creature.birth).get_years();
+            if creature.death.is_some() || !creature.sim_flag_is_inteligent() || age < PLOTTER_MIN_AGE {
+                continue
+            }
+            if Some(*creature_id) == deposed_leader {
+                if let Some(leader_id) = conqueror_leader {
+                    let goal = Goal::OverthrowLeader { site_id: *conqueror_id, leader_id };
+                    history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", creature_id, goal);
+                    creature.add_goal(goal);
+                }
+                continue
+            }
+            let mut rng = OldRng::seeded(self.generation_parameters.seed).derive((*creature_id, *conquered_id, self.date));
+            if rng.rand_chance(FOUND_SETTLEMENT_CHANCE) {
+                let goal = Goal::FoundSettlement;
+                history_trace!("creature_add_goal creature_id:{:?} goal:{:?}", creature_id, goal);
+                creature.add_goal(goal);
+            }
+        }
+
+        conqueror.creatures.extend(creatures);
+
+        // Spoils of war
+        conqueror.resources.food += conquered.resources.food.max(0.);
+        conquered.resources.food = 0.;
+        let stock: Vec<_> = conquered.settlement.as_mut().map(|settlement| settlement.material_stock.drain(..).collect()).unwrap_or_default();
+        if let Some(settlement) = conqueror.settlement.as_mut() {
+            for (material_id, count) in stock {
+                settlement.add_material(&material_id, count);
+            }
+        }
+    }
+
     fn site_search_new_pos_closeby(&self, center: Vec2i, max_radius: i32, rng: &mut Rng) -> Option<Vec2i> {
         let x_limit = [
             (center.x() - max_radius).max(3),
@@ -1,16 +1,23 @@
-use std::{ops::ControlFlow, time::Instant};
+use std::ops::ControlFlow;
 
 use graphics::Transformed;
 use image::ImageReader;
 use opengl_graphics::{Filter, Texture, TextureSettings};
+use piston::Key;
 
-use crate::{engine::{assets::assets, audio::TrackMood, gui::UINode, input::InputEvent, render::RenderContext, scene::{Scene, Update}, COLOR_WHITE}, game::map_component::MapComponent, resources::resources::Resources, world::{date::Duration, site::SiteType}, GameContext};
+use crate::{engine::{assets::assets, audio::TrackMood, gui::{chart::LineChart, UINode}, input::InputEvent, render::RenderContext, scene::{Scene, Update}, COLOR_WHITE}, game::map_component::MapComponent, resources::resources::Resources, world::{date::Duration, site::SiteType}, GameContext};
 
-use super::{history_generator::{WorldGenerationParameters, WorldHistoryGenerator}, world::World};
+use super::{history_generator::{GenerationState, WorldGenerationParameters, WorldHistoryGenerator}, world::World};
+
+/// Statistics charted while the history is generated
+const CHARTED_METRICS: [&str; 3] = ["population", "settlements", "food"];
 
 pub(crate) struct WorldGenScene {
     generator: WorldHistoryGenerator,
     map: MapComponent,
+    charts: Vec<LineChart>,
+    /// Samples the charts were built with
+    charted_samples: usize,
     banner_texture: Texture,
     pub(crate) save_file: Option<String>
 }
@@ -23,6 +30,8 @@ impl WorldGenScene {
         let mut scene = WorldGenScene {
             generator: WorldHistoryGenerator::seed_world(params.clone(), resources),
             map: MapComponent::new(),
+            charts: Vec::new(),
+            charted_samples: 0,
             banner_texture: Texture::from_image(&spritesheet.to_rgba8(), &settings),
             save_file: None,
         };
@@ -32,13 +41,19 @@ impl WorldGenScene {
 
     pub(crate) fn continue_simulation(save_file: String, mut world: World) -> WorldGenScene {
         world.generation_parameters.history_length = world.generation_parameters.history_length + 50;
+        return Self::from_world(save_file, world);
+    }
 
+    /// Resumes a saved world, simulating only what's left of its history length
+    pub(crate) fn from_world(save_file: String, world: World) -> WorldGenScene {
         let spritesheet = ImageReader::open("assets/sprites/banner.png").unwrap().decode().unwrap();
         let settings = TextureSettings::new().filter(Filter::Nearest);
 
         let mut scene = WorldGenScene {
             generator: WorldHistoryGenerator::simulator(world),
             map: MapComponent::new(),
+            charts: Vec::new(),
+            charted_samples: 0,
             banner_texture: Texture::from_image(&spritesheet.to_rgba8(), &settings),
             save_file: Some(save_file)
         };
@@ -50,6 +65,22 @@ impl WorldGenScene {
         self.map.set_topology(&self.generator.world.map);
     }
 
+    fn build_charts(&mut self) {
+        let metrics = &self.generator.world.metrics;
+        if metrics.samples.len() == self.charted_samples {
+            return;
+        }
+        self.charted_samples = metrics.samples.len();
+        self.charts = metrics.series().into_iter()
+            .filter(|series| CHARTED_METRICS.contains(&series.key.as_str()))
+            .enumerate()
+            .map(|(i, series)| {
+                let points = series.points.iter().map(|(year, value)| (*year as f64, *value)).collect();
+                LineChart::new(&series.title, points).layout(|l| { l.anchor_bottom_left(8., 8. + i as f64 * 84.).size([192., 80.]); })
+            })
+            .collect();
+    }
+
     pub(crate) fn into_world(self) -> World {
         return self.generator.world
     }
@@ -78,32 +109,47 @@ impl Scene for WorldGenScene {
         let text = self.generator.world.date_desc(&self.generator.world.date);
         let text_width = font.width(&text);
         ctx.text(&text, font, [(center - text_width / 2.).round() as i32, 16], &COLOR_WHITE);
-        let text = "Press <enter> to start playing";
+        let progress = self.generator.progress();
+        let text = format!("Population {}, {} sites, {} events a year", progress.population, progress.sites, progress.events_per_year);
         let text_width = font.width(&text);
         ctx.text_shadow(&text, font, [(center - text_width / 2.).round() as i32, 40], &COLOR_WHITE);
+        let text = match progress.state {
+            GenerationState::Running => "<space> to pause, <esc> to stop here, <enter> to start playing",
+            GenerationState::Paused => "Paused. <space> to resume, <enter> to start playing",
+            GenerationState::Cancelled | GenerationState::Finished => "Press <enter> to start playing",
+        };
+        let text_width = font.width(&text);
+        ctx.text_shadow(&text, font, [(center - text_width / 2.).round() as i32, 56], &COLOR_WHITE);
+        drop(assets);
+
+        for chart in self.charts.iter_mut() {
+            chart.render(&(), ctx, game_ctx);
+        }
     }
 
     fn update(&mut self, _update: &Update, _ctx: &mut GameContext) {
-        if self.generator.stop || self.generator.world.date.year() >= self.generator.parameters.history_length as i32 {
+        self.build_charts();
+        if self.generator.state() != GenerationState::Running {
             return
         }
-        let start = Instant::now();
-        loop {
-            // TODO(CF3fkX3): Too small and everything dies
-            
-            self.generator.simulate_step(Duration::months(3));
-            // Simulate years until reach the max time per iteration, otherwise it takes longer than it needs
-            if start.elapsed().as_secs_f64() >= _update.max_update_time {
-                break;
-            }
-            if self.generator.stop || self.generator.world.date.year() >= self.generator.parameters.history_length as i32  {
-                break;
-            }
-        }
-        self.map.update_visible_sites(&self.generator.world, |_id, site| site.site_type == SiteType::Village);
+        // TODO(CF3fkX3): Too small and everything dies
+        // Simulate years until reach the max time per iteration, otherwise it takes longer than it needs
+        self.generator.run(Duration::months(3), Some(std::time::Duration::from_secs_f64(_update.max_update_time)));
+        self.map.update_visible_sites(&self.generator.world, |_id, site| site.site_type.is_settlement() || site.site_type == SiteType::Ruins);
     }
 
-    fn input(&mut self, _evt: &InputEvent, _ctx: &mut GameContext) -> ControlFlow<()> {
+    fn input(&mut self, evt: &InputEvent, _ctx: &mut GameContext) -> ControlFlow<()> {
+        match evt {
+            InputEvent::Key { key: Key::Space } => {
+                match self.generator.state() {
+                    GenerationState::Running => self.generator.pause(),
+                    GenerationState::Paused => self.generator.resume(),
+                    _ => ()
+                }
+            },
+            InputEvent::Key { key: Key::Escape } => self.generator.cancel(),
+            _ => ()
+        }
         ControlFlow::Continue(())
     }
 
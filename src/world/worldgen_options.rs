@@ -0,0 +1,417 @@
+use std::ops::ControlFlow;
+
+use piston::Key;
+
+use crate::{commons::rng::Rng, engine::{assets::assets, geometry::Size2D, gui::{button::Button, layout_component::LayoutComponent, text_input::TextInput, UIEvent, UINode}, input::InputEvent, render::RenderContext, Color, COLOR_BACKDROP, COLOR_WHITE}, loc, world::{history_generator::WorldGenerationParameters, history_sim::storyteller::StorytellerProfile}, GameContext};
+
+/// World sizes the player can pick from, in chunks
+const WORLD_SIZES: [(&str, Size2D); 3] = [
+    ("worldgen-options-size-small", Size2D(48, 32)),
+    ("worldgen-options-size-medium", Size2D(64, 48)),
+    ("worldgen-options-size-large", Size2D(96, 64)),
+];
+/// Where the controls start, right of the labels
+const CONTROLS_X: f64 = 128.;
+const CONTROLS_WIDTH: f64 = 120.;
+
+/// Lets the player pick the parameters of a new world before generating it
+pub(crate) struct WorldGenOptions {
+    layout: LayoutComponent,
+    presets: Vec<(Preset, Button)>,
+    buttons: Vec<Button>,
+    seed: TextInput,
+    size: usize,
+    size_button: Button,
+    history_length: NumberField,
+    plates: NumberField,
+    seed_cities: NumberField,
+    storytellers: Vec<String>,
+    storyteller: usize,
+    storyteller_button: Button,
+    strength: NumberField,
+    city_count: NumberField,
+    village_count: NumberField,
+    /// Labels on the left, and the row they're on
+    labels: Vec<(String, f64)>,
+    error: Option<String>,
+}
+
+impl WorldGenOptions {
+
+    pub(crate) fn new() -> Self {
+        let mut layout = LayoutComponent::new();
+        layout.anchor_center().size([264., 276.]).padding([8.; 4]);
+
+        let mut presets = Vec::new();
+        for (i, preset) in Preset::ALL.into_iter().enumerate() {
+            let mut button = Button::text(loc!(preset.name())).key(&format!("preset:{i}"));
+            button.layout_component().anchor_top_left(i as f64 * 84., 0.).size([80., 16.]);
+            button.set_selected(preset == Preset::Standard);
+            presets.push((preset, button));
+        }
+
+        let mut buttons = Vec::new();
+        for (key, text, x) in [("back", "worldgen-options-back", 0.), ("generate", "worldgen-options-generate", 168.)] {
+            let mut button = Button::text(loc!(text)).key(key);
+            button.layout_component().anchor_top_left(x, 236.).size([80., 16.]);
+            buttons.push(button);
+        }
+
+        let mut seed = TextInput::text("").key("seed");
+        seed.layout_component().anchor_top_left(CONTROLS_X, 24.).size([CONTROLS_WIDTH, 16.]);
+
+        let mut size_button = Button::text("").key("size");
+        size_button.layout_component().anchor_top_left(CONTROLS_X, 44.).size([CONTROLS_WIDTH, 16.]);
+
+        let storytellers = StorytellerProfile::available();
+        let mut storyteller_button = Button::text("").key("storyteller");
+        storyteller_button.layout_component().anchor_top_left(CONTROLS_X, 124.).size([CONTROLS_WIDTH, 16.]);
+
+        let labels = [
+            ("worldgen-options-seed", 24.),
+            ("worldgen-options-size", 44.),
+            ("worldgen-options-history-length", 64.),
+            ("worldgen-options-plates", 84.),
+            ("worldgen-options-seed-cities", 104.),
+            ("worldgen-options-storyteller", 124.),
+            ("worldgen-options-strength", 144.),
+            ("worldgen-options-city-count", 164.),
+            ("worldgen-options-village-count", 184.),
+        ].into_iter().map(|(key, y)| (loc!(key).clone(), y)).collect();
+
+        let history_length = NumberField::new("worldgen-options-history-length", 64., 100., 5000., false);
+        let plates = NumberField::new("worldgen-options-plates", 84., 1., 20., false);
+        let seed_cities = NumberField::new("worldgen-options-seed-cities", 104., 1., 50., false);
+        let strength = NumberField::new("worldgen-options-strength", 144., 0., 1., true);
+        let city_count = NumberField::new("worldgen-options-city-count", 164., 0., 50., false);
+        let village_count = NumberField::new("worldgen-options-village-count", 184., 1., 100., false);
+
+        let mut options = Self {
+            layout,
+            presets,
+            buttons,
+            seed,
+            size: 0,
+            size_button,
+            history_length,
+            plates,
+            seed_cities,
+            storytellers,
+            storyteller: 0,
+            storyteller_button,
+            strength,
+            city_count,
+            village_count,
+            labels,
+            error: None,
+        };
+        options.show(&Preset::Standard.parameters());
+        return options;
+    }
+
+    /// Fills the inputs with the parameters, except the seed
+    fn show(&mut self, params: &WorldGenerationParameters) {
+        self.size = WORLD_SIZES.iter().position(|(_, size)| *size == params.world_size).unwrap_or(1);
+        self.size_button.set_text(loc!(WORLD_SIZES[self.size].0));
+        self.history_length.set(params.history_length as f64);
+        self.plates.set(params.num_plate_tectonics as f64);
+        self.seed_cities.set(params.number_of_seed_cities as f64);
+        self.storyteller = self.storytellers.iter().position(|name| *name == params.storyteller).unwrap_or(0);
+        if let Some(name) = self.storytellers.get(self.storyteller) {
+            self.storyteller_button.set_text(name);
+        }
+        self.strength.set(params.st_strength as f64);
+        self.city_count.set(params.st_city_count as f64);
+        self.village_count.set(params.st_village_count as f64);
+        self.error = None;
+    }
+
+    /// The parameters in the inputs. Marks the invalid inputs, and returns the first problem found
+    pub(crate) fn parameters(&mut self) -> Result<WorldGenerationParameters, String> {
+        // Every field is checked, so every invalid input is marked
+        let history_length = self.history_length.parse();
+        let plates = self.plates.parse();
+        let seed_cities = self.seed_cities.parse();
+        let strength = self.strength.parse();
+        let city_count = self.city_count.parse();
+        let village_count = self.village_count.parse();
+
+        let storyteller = self.storytellers.get(self.storyteller).cloned().unwrap_or(String::from("balanced"));
+        let mut params = WorldGenerationParameters {
+            seed: self.seed(),
+            world_size: WORLD_SIZES[self.size].1,
+            storyteller: storyteller.clone(),
+            ..Default::default()
+        };
+        // The profile sets the population targets, the counts are the player's
+        StorytellerProfile::load(&storyteller)?.apply_targets(&mut params);
+        params.history_length = history_length? as u16;
+        params.num_plate_tectonics = plates? as u8;
+        params.number_of_seed_cities = seed_cities? as u16;
+        params.st_strength = strength? as f32;
+        params.st_city_count = city_count? as u16;
+        params.st_village_count = village_count? as u16;
+        params.validate()?;
+        return Ok(params);
+    }
+
+    /// A number typed as the seed is used as is, any other text is hashed. Empty means random
+    fn seed(&self) -> u64 {
+        let text = self.seed.value().trim();
+        if text.is_empty() {
+            return Rng::rand().rand_u32() as u64;
+        }
+        return text.parse::<u64>().unwrap_or_else(|_| Rng::seeded(text).rand_u32() as u64);
+    }
+
+    fn text_inputs(&mut self) -> [&mut TextInput; 7] {
+        return [
+            &mut self.seed,
+            &mut self.history_length.input,
+            &mut self.plates.input,
+            &mut self.seed_cities.input,
+            &mut self.strength.input,
+            &mut self.city_count.input,
+            &mut self.village_count.input,
+        ];
+    }
+
+    pub(crate) fn render(&mut self, ctx: &mut RenderContext, game_ctx: &mut GameContext) {
+        let copy = ctx.layout_rect;
+        let rect = self.layout.compute_layout_rect(ctx.layout_rect);
+        ctx.rectangle_fill(rect, &COLOR_BACKDROP);
+        ctx.layout_rect = self.layout.compute_inner_layout_rect(ctx.layout_rect);
+
+        for (_, button) in self.presets.iter_mut() {
+            button.render(&(), ctx, game_ctx);
+        }
+        for button in self.buttons.iter_mut() {
+            button.render(&(), ctx, game_ctx);
+        }
+        self.size_button.render(&(), ctx, game_ctx);
+        self.storyteller_button.render(&(), ctx, game_ctx);
+        for input in self.text_inputs() {
+            input.render(&(), ctx, game_ctx);
+        }
+
+        let x = ctx.layout_rect[0] as i32;
+        let y = ctx.layout_rect[1] as i32;
+        let mut assets = assets();
+        let font = assets.font_standard();
+        for (label, row) in self.labels.iter() {
+            ctx.text_shadow(label, font, [x, y + *row as i32 + 12], &COLOR_WHITE);
+        }
+        if let Some(error) = &self.error {
+            ctx.text_shadow(error, font, [x, y + 224], &Color::from_hex("d95763"));
+        }
+        drop(assets);
+
+        ctx.layout_rect = copy;
+    }
+
+    /// Breaks with the parameters of the world to generate, or with None to go back to the menu
+    pub(crate) fn input(&mut self, evt: &InputEvent, ctx: &mut GameContext) -> ControlFlow<Option<WorldGenerationParameters>> {
+        let typing = self.text_inputs().iter().any(|input| input.focused());
+        let mut changed = None;
+        for input in self.text_inputs() {
+            match input.input(&mut (), evt, ctx) {
+                ControlFlow::Break(UIEvent::InputChanged(key)) => changed = Some(key),
+                ControlFlow::Break(_) => return ControlFlow::Continue(()),
+                ControlFlow::Continue(_) => (),
+            }
+        }
+        if let Some(key) = changed {
+            // The presets don't pick the seed, any other value is no longer the preset's
+            if key != "seed" {
+                for (_, button) in self.presets.iter_mut() {
+                    button.set_selected(false);
+                }
+            }
+            self.error = self.parameters().err();
+            return ControlFlow::Continue(());
+        }
+
+        if let InputEvent::Key { key: Key::Escape } = evt {
+            if !typing {
+                return ControlFlow::Break(None);
+            }
+        }
+
+        let mut clicked = None;
+        for (_, button) in self.presets.iter_mut() {
+            if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = button.input(&mut (), evt, ctx) {
+                clicked = Some(key);
+            }
+        }
+        for button in self.buttons.iter_mut().chain([&mut self.size_button, &mut self.storyteller_button]) {
+            if let ControlFlow::Break(UIEvent::ButtonClicked(key)) = button.input(&mut (), evt, ctx) {
+                clicked = Some(key);
+            }
+        }
+        if let Some(key) = clicked {
+            return self.handle_key(&key);
+        }
+        return ControlFlow::Continue(());
+    }
+
+    fn handle_key(&mut self, key: &str) -> ControlFlow<Option<WorldGenerationParameters>> {
+        match key {
+            "back" => return ControlFlow::Break(None),
+            "generate" => {
+                match self.parameters() {
+                    Ok(params) => return ControlFlow::Break(Some(params)),
+                    Err(error) => self.error = Some(error),
+                }
+            },
+            "size" => {
+                self.size = (self.size + 1) % WORLD_SIZES.len();
+                self.size_button.set_text(loc!(WORLD_SIZES[self.size].0));
+            },
+            "storyteller" => {
+                if self.storytellers.is_empty() {
+                    return ControlFlow::Continue(());
+                }
+                self.storyteller = (self.storyteller + 1) % self.storytellers.len();
+                let name = &self.storytellers[self.storyteller];
+                self.storyteller_button.set_text(name);
+                // Each storyteller aims for its own number of settlements
+                match StorytellerProfile::load(name) {
+                    Ok(profile) => {
+                        self.city_count.set(profile.targets.city_count as f64);
+                        self.village_count.set(profile.targets.village_count as f64);
+                        self.error = self.parameters().err();
+                    },
+                    Err(error) => self.error = Some(error),
+                }
+            },
+            other => {
+                if let Some(i) = other.strip_prefix("preset:").and_then(|i| i.parse::<usize>().ok()) {
+                    for (j, (_, button)) in self.presets.iter_mut().enumerate() {
+                        button.set_selected(i == j);
+                    }
+                    if let Some(preset) = self.presets.get(i).map(|(preset, _)| *preset) {
+                        self.show(&preset.parameters());
+                        for field in [&mut self.history_length, &mut self.plates, &mut self.seed_cities, &mut self.strength, &mut self.city_count, &mut self.village_count] {
+                            field.input.set_invalid(false);
+                        }
+                    }
+                }
+            }
+        }
+        return ControlFlow::Continue(());
+    }
+
+}
+
+/// A number typed by the player, that must be within a range
+struct NumberField {
+    label: String,
+    input: TextInput,
+    min: f64,
+    max: f64,
+    /// Whether the number can have decimals
+    decimals: bool,
+}
+
+impl NumberField {
+
+    fn new(label: &str, row: f64, min: f64, max: f64, decimals: bool) -> Self {
+        let label = loc!(label).clone();
+        let mut input = TextInput::numeric("");
+        input.layout_component().anchor_top_left(CONTROLS_X, row).size([CONTROLS_WIDTH, 16.]);
+        return Self { label, input, min, max, decimals }
+    }
+
+    fn set(&mut self, value: f64) {
+        self.input.set_value(&format!("{value}"));
+    }
+
+    fn parse(&mut self) -> Result<f64, String> {
+        let value = self.input.value().trim().parse::<f64>().ok()
+            .filter(|value| (self.min..=self.max).contains(value) && (self.decimals || value.fract() == 0.));
+        self.input.set_invalid(value.is_none());
+        return value.ok_or(format!("{} must be between {} and {}", self.label, self.min, self.max));
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Preset {
+    Quick,
+    Standard,
+    Epic,
+}
+
+impl Preset {
+
+    const ALL: [Preset; 3] = [Preset::Quick, Preset::Standard, Preset::Epic];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Preset::Quick => "worldgen-options-preset-quick",
+            Preset::Standard => "worldgen-options-preset-standard",
+            Preset::Epic => "worldgen-options-preset-epic",
+        }
+    }
+
+    fn parameters(&self) -> WorldGenerationParameters {
+        let standard = WorldGenerationParameters::default();
+        match self {
+            // Small and short, to try things out
+            Preset::Quick => WorldGenerationParameters {
+                world_size: Size2D(48, 32),
+                num_plate_tectonics: 3,
+                history_length: 500,
+                number_of_seed_cities: 2,
+                st_city_count: 4,
+                st_village_count: 10,
+                ..standard
+            },
+            Preset::Standard => standard,
+            // Large and old, slow to generate
+            Preset::Epic => WorldGenerationParameters {
+                world_size: Size2D(96, 64),
+                num_plate_tectonics: 8,
+                history_length: 4000,
+                number_of_seed_cities: 6,
+                st_city_count: 14,
+                st_village_count: 40,
+                ..standard
+            },
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests_worldgen_options {
+    use super::*;
+
+    #[test]
+    fn test_parameters() {
+        let mut options = WorldGenOptions::new();
+        options.seed.set_value("1234");
+        let params = options.parameters().unwrap();
+        assert_eq!(params.seed, 1234);
+        assert_eq!(params.world_size, Size2D(64, 48));
+        assert_eq!(params.history_length, 2000);
+
+        // Text seeds are hashed, always to the same number
+        options.seed.set_value("kathay");
+        assert_eq!(options.parameters().unwrap().seed, options.parameters().unwrap().seed);
+
+        options.handle_key("preset:2");
+        let params = options.parameters().unwrap();
+        assert_eq!(params.world_size, Size2D(96, 64));
+        assert_eq!(params.history_length, 4000);
+
+        options.history_length.input.set_value("50");
+        options.strength.input.set_value("1.5");
+        assert!(options.parameters().is_err());
+        options.history_length.input.set_value("100");
+        assert!(options.parameters().is_err());
+        options.strength.input.set_value("0.5");
+        assert_eq!(options.parameters().unwrap().st_strength, 0.5);
+    }
+
+}
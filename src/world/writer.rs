@@ -1,6 +1,6 @@
 use text::capitalize;
 
-use crate::{game::{actor::actor::Actor, codex::{Quest, QuestObjective}}, resources::resources::Resources, world::{creature::{CauseOfDeath, CreatureGender, CreatureId, Profession}, date::WorldDate, item::{ArtworkScene, Item}, world::World}};
+use crate::{game::{actor::actor::Actor, codex::{Quest, QuestObjective}}, resources::resources::Resources, world::{creature::{CauseOfDeath, CreatureGender, CreatureId, Profession}, date::WorldDate, item::{ArtworkScene, Item}, site::SiteId, war::{War, WarStatus}, world::World}};
 
 pub(crate) struct Writer<'a> {
     world: &'a World,
@@ -48,7 +48,7 @@ impl<'a> Writer<'a> {
 
     pub(crate) fn describe_quest(&mut self, quest: &Quest) {
         match quest.objective {
-            QuestObjective::KillVarningr(creature_id) => {
+            QuestObjective::KillBeast(creature_id) => {
                 let creature = self.world.creatures.get(&creature_id);
                 self.add_text(&format!("Kill {}", creature.name(&creature_id, self.world, self.resources)));
             },
@@ -104,10 +104,19 @@ impl<'a> Writer<'a> {
                         }
                         None => format!("{description}. It depicts a full-body image of {}", creature.name(&creature_id, self.world, self.resources))
                     };                    
+                },
+                ArtworkScene::Deity { deity_id } => {
+                    let deity = self.world.deities.get(&deity_id);
+                    description = format!("{description}. It depicts {}", deity.description());
                 }
             }
         }
 
+        if let Some(deity_id) = &item.dedicated_to {
+            let deity = self.world.deities.get(deity_id);
+            description = format!("{description}. It is a holy relic, dedicated to {}", deity.description());
+        }
+
         self.add_text(&description);
     }
 
@@ -142,10 +151,10 @@ impl<'a> Writer<'a> {
 
     pub(crate) fn chat_explain_quest(&mut self, quest: &Quest, actor: &Actor) {
         match &quest.objective {
-            QuestObjective::KillVarningr(creature_id) => {
+            QuestObjective::KillBeast(creature_id) => {
                 let creature = self.world.creatures.get(creature_id);
-                let species = self.resources.species.get(&creature.species);
-                self.quote_actor(&format!("A {} has been terrorising us. I want you to go to it's lair and kill it. Here, I marked it on your map.", species.name), actor);
+                let name = creature.name(creature_id, self.world, self.resources);
+                self.quote_actor(&format!("{} has been terrorising us. I want you to go to it's lair and kill it. Here, I marked it on your map.", capitalize(&name)), actor);
             },
             QuestObjective::KillBandits(_site_id) => {
                 self.quote_actor(&format!("A group of bandits has been robbing us. I want you to go to their camp and kill them. Here, I marked it on your map."), actor);
@@ -156,6 +165,21 @@ impl<'a> Writer<'a> {
         }
     }
 
+    pub(crate) fn chat_talk_about_war(&mut self, war: &War, site_id: &SiteId, actor: &Actor) {
+        let enemy = self.world.sites.get(&war.enemy_of(site_id));
+        match war.status {
+            WarStatus::Siege(_) if &war.defender == site_id => {
+                self.quote_actor(&format!("We are besieged by {}. Pray that our walls hold.", enemy.name()), actor);
+            },
+            WarStatus::Siege(_) => {
+                self.quote_actor(&format!("Our army is laying siege to {}. Every sword is needed there.", enemy.name()), actor);
+            },
+            _ => {
+                self.quote_actor(&format!("We are at war with {}. I have nothing else to ask of you.", enemy.name()), actor);
+            }
+        }
+    }
+
     pub(crate) fn quote_actor(&mut self, sentence: &str, actor: &Actor) {
         let pronoun = match actor.gender {
             CreatureGender::Male => "he",
@@ -172,6 +196,7 @@ impl<'a> Writer<'a> {
     fn cause_of_death_description(&self, cause_of_death: &CauseOfDeath) -> String {
         match cause_of_death {
             CauseOfDeath::Disease => String::from("of a sudden illness"),
+            CauseOfDeath::Plague(epidemic_id) => format!("of the {}", self.world.epidemics.get(epidemic_id).name),
             CauseOfDeath::OldAge => String::from("peacefully in their sleep"),
             CauseOfDeath::KilledInBattle(killer_id, _) => format!("by the hand of {}", self.creature_name(killer_id))
         }